@@ -0,0 +1,19 @@
+// With the `capi` feature, generate the C header of ffi.rs into OUT_DIR. The copy in
+// include/ is committed for C hosts; tests/ffi.rs checks it against this one, and
+// `cbindgen --config cbindgen.toml --output include/cz_cfd_simulator.h` refreshes it.
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).expect("reading cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(config)
+            .generate()
+            .expect("generating the C header")
+            .write_to_file(format!("{}/cz_cfd_simulator.h", out_dir));
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
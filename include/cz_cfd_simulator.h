@@ -0,0 +1,99 @@
+/*
+ * C interface of the Czochralski melt flow simulator, see src/ffi.rs.
+ *
+ * Every call returns a CzStatus; on failure cz_last_error() describes it. Fields are
+ * row-major ny x nx doubles, node (i, j) at i * nx + j with i along y from the bottom
+ * wall; field and wall arguments take CzField and CzWall values. Handles are not
+ * thread safe.
+ */
+
+#ifndef CZ_CFD_SIMULATOR_H
+#define CZ_CFD_SIMULATOR_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum CzField {
+  CZ_FIELD_TEMPERATURE = 0,
+  CZ_FIELD_VORTICITY = 1,
+  CZ_FIELD_STREAM_FUNCTION = 2,
+  CZ_FIELD_VELOCITY_X = 3,
+  CZ_FIELD_VELOCITY_Y = 4,
+  CZ_FIELD_CONCENTRATION = 5,
+  CZ_FIELD_GAS_VORTICITY = 6,
+  CZ_FIELD_GAS_STREAM_FUNCTION = 7,
+} CzField;
+
+typedef enum CzStatus {
+  CZ_STATUS_OK = 0,
+  CZ_STATUS_NULL_POINTER = 1,
+  CZ_STATUS_INVALID_ARGUMENT = 2,
+  CZ_STATUS_BUFFER_SIZE = 3,
+  CZ_STATUS_BLEW_UP = 4,
+  CZ_STATUS_PANIC = 5,
+} CzStatus;
+
+typedef enum CzWall {
+  CZ_WALL_BOTTOM = 0,
+  CZ_WALL_TOP = 1,
+  CZ_WALL_LEFT = 2,
+  CZ_WALL_RIGHT = 3,
+} CzWall;
+
+typedef struct CzSimulation CzSimulation;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+const char *cz_last_error(void);
+
+enum CzStatus cz_simulation_new(const char *params_json, struct CzSimulation **out);
+
+enum CzStatus cz_simulation_new_cavity(size_t grid_size,
+                                       double ra,
+                                       double pr,
+                                       double dt,
+                                       struct CzSimulation **out);
+
+void cz_simulation_free(struct CzSimulation *sim);
+
+enum CzStatus cz_simulation_advance(struct CzSimulation *sim, size_t steps);
+
+enum CzStatus cz_simulation_info(const struct CzSimulation *sim,
+                                 size_t *nx,
+                                 size_t *ny,
+                                 double *time,
+                                 size_t *steps);
+
+enum CzStatus cz_simulation_read_field(const struct CzSimulation *sim,
+                                       uint32_t field_id,
+                                       double *buffer,
+                                       size_t len);
+
+enum CzStatus cz_simulation_write_field(struct CzSimulation *sim,
+                                        uint32_t field_id,
+                                        const double *buffer,
+                                        size_t len);
+
+enum CzStatus cz_simulation_read_wall(const struct CzSimulation *sim,
+                                      uint32_t wall_id,
+                                      double *buffer,
+                                      size_t len);
+
+enum CzStatus cz_simulation_set_wall_temperature(struct CzSimulation *sim,
+                                                 uint32_t wall_id,
+                                                 double start,
+                                                 double end,
+                                                 double value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CZ_CFD_SIMULATOR_H */
@@ -0,0 +1 @@
+ALTER TABLE results ADD COLUMN region SMALLINT NOT NULL DEFAULT 0;
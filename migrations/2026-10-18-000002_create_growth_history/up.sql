@@ -0,0 +1,8 @@
+CREATE TABLE growth_history (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES simulation_runs(id) ON DELETE CASCADE,
+    step INTEGER NOT NULL,
+    sim_time DOUBLE PRECISION NOT NULL,
+    crystal_length DOUBLE PRECISION NOT NULL,
+    melt_height DOUBLE PRECISION NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE results ADD COLUMN cell_type SMALLINT NOT NULL DEFAULT 0;
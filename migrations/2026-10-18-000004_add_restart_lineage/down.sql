@@ -0,0 +1,3 @@
+ALTER TABLE results DROP COLUMN stream_function;
+ALTER TABLE results DROP COLUMN vorticity;
+ALTER TABLE simulation_runs DROP COLUMN parent_run_id;
@@ -0,0 +1,3 @@
+ALTER TABLE simulation_runs ADD COLUMN parent_run_id INTEGER REFERENCES simulation_runs(id) ON DELETE SET NULL;
+ALTER TABLE results ADD COLUMN vorticity DOUBLE PRECISION;
+ALTER TABLE results ADD COLUMN stream_function DOUBLE PRECISION;
@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs DROP COLUMN perturbation_seed;
+ALTER TABLE simulation_runs DROP COLUMN perturbation;
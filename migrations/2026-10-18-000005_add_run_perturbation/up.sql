@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs ADD COLUMN perturbation TEXT;
+ALTER TABLE simulation_runs ADD COLUMN perturbation_seed BIGINT;
@@ -0,0 +1 @@
+DROP TABLE continuation_points;
@@ -0,0 +1,13 @@
+CREATE TABLE continuation_points (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES simulation_runs(id) ON DELETE CASCADE,
+    point_index INTEGER NOT NULL,
+    parameter TEXT NOT NULL,
+    parameter_value DOUBLE PRECISION NOT NULL,
+    nusselt DOUBLE PRECISION NOT NULL,
+    max_stream DOUBLE PRECISION NOT NULL,
+    arclength DOUBLE PRECISION NOT NULL,
+    growth_rate DOUBLE PRECISION,
+    frequency DOUBLE PRECISION,
+    kind TEXT NOT NULL
+);
@@ -0,0 +1,14 @@
+ALTER TABLE simulation_runs DROP COLUMN hartmann_number;
+ALTER TABLE simulation_runs DROP COLUMN taylor_number;
+ALTER TABLE simulation_runs DROP COLUMN reynolds_number;
+ALTER TABLE simulation_runs DROP COLUMN marangoni_number;
+ALTER TABLE simulation_runs DROP COLUMN grashof_number;
+ALTER TABLE simulation_runs DROP COLUMN gravity;
+ALTER TABLE simulation_runs DROP COLUMN magnetic_field;
+ALTER TABLE simulation_runs DROP COLUMN crucible_rotation;
+ALTER TABLE simulation_runs DROP COLUMN crystal_rotation;
+ALTER TABLE simulation_runs DROP COLUMN delta_t;
+ALTER TABLE simulation_runs DROP COLUMN crystal_radius;
+ALTER TABLE simulation_runs DROP COLUMN melt_height;
+ALTER TABLE simulation_runs DROP COLUMN crucible_radius;
+ALTER TABLE simulation_runs DROP COLUMN material;
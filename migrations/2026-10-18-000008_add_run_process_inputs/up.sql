@@ -0,0 +1,14 @@
+ALTER TABLE simulation_runs ADD COLUMN material TEXT;
+ALTER TABLE simulation_runs ADD COLUMN crucible_radius DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN melt_height DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN crystal_radius DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN delta_t DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN crystal_rotation DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN crucible_rotation DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN magnetic_field DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN gravity DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN grashof_number DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN marangoni_number DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN reynolds_number DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN taylor_number DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN hartmann_number DOUBLE PRECISION;
@@ -0,0 +1,19 @@
+CREATE TABLE diagnostics (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES simulation_runs(id) ON DELETE CASCADE,
+    step INTEGER NOT NULL,
+    sim_time DOUBLE PRECISION NOT NULL,
+    nu_bottom DOUBLE PRECISION NOT NULL,
+    nu_top DOUBLE PRECISION NOT NULL,
+    nu_left DOUBLE PRECISION NOT NULL,
+    nu_right DOUBLE PRECISION NOT NULL,
+    kinetic_energy DOUBLE PRECISION NOT NULL,
+    enstrophy DOUBLE PRECISION NOT NULL,
+    max_stream DOUBLE PRECISION NOT NULL,
+    max_u DOUBLE PRECISION NOT NULL,
+    max_u_x DOUBLE PRECISION NOT NULL,
+    max_u_y DOUBLE PRECISION NOT NULL,
+    max_v DOUBLE PRECISION NOT NULL,
+    max_v_x DOUBLE PRECISION NOT NULL,
+    max_v_y DOUBLE PRECISION NOT NULL
+);
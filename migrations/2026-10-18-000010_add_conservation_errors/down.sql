@@ -0,0 +1,10 @@
+ALTER TABLE simulation_runs DROP COLUMN conservation_flagged;
+ALTER TABLE simulation_runs DROP COLUMN poisson_residual;
+ALTER TABLE simulation_runs DROP COLUMN divergence;
+ALTER TABLE simulation_runs DROP COLUMN heat_imbalance;
+ALTER TABLE diagnostics DROP COLUMN poisson_residual;
+ALTER TABLE diagnostics DROP COLUMN divergence;
+ALTER TABLE diagnostics DROP COLUMN heat_imbalance;
+ALTER TABLE diagnostics DROP COLUMN storage_rate;
+ALTER TABLE diagnostics DROP COLUMN heat_out;
+ALTER TABLE diagnostics DROP COLUMN heat_in;
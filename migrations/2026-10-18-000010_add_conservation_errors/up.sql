@@ -0,0 +1,10 @@
+ALTER TABLE diagnostics ADD COLUMN heat_in DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE diagnostics ADD COLUMN heat_out DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE diagnostics ADD COLUMN storage_rate DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE diagnostics ADD COLUMN heat_imbalance DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE diagnostics ADD COLUMN divergence DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE diagnostics ADD COLUMN poisson_residual DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE simulation_runs ADD COLUMN heat_imbalance DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN divergence DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN poisson_residual DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN conservation_flagged BOOLEAN;
@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs DROP COLUMN failure_reason;
+ALTER TABLE simulation_runs DROP COLUMN status;
@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs ADD COLUMN status TEXT NOT NULL DEFAULT 'completed';
+ALTER TABLE simulation_runs ADD COLUMN failure_reason TEXT;
@@ -0,0 +1,13 @@
+CREATE TABLE probe_samples (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES simulation_runs(id) ON DELETE CASCADE,
+    probe TEXT NOT NULL,
+    step INTEGER NOT NULL,
+    sim_time DOUBLE PRECISION NOT NULL,
+    x DOUBLE PRECISION NOT NULL,
+    y DOUBLE PRECISION NOT NULL,
+    temperature DOUBLE PRECISION NOT NULL,
+    u_velocity DOUBLE PRECISION NOT NULL,
+    v_velocity DOUBLE PRECISION NOT NULL,
+    vorticity DOUBLE PRECISION NOT NULL
+);
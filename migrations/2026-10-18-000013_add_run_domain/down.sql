@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs DROP COLUMN periodic;
+ALTER TABLE simulation_runs DROP COLUMN domain_width;
@@ -0,0 +1,2 @@
+ALTER TABLE simulation_runs ADD COLUMN domain_width DOUBLE PRECISION NOT NULL DEFAULT 1;
+ALTER TABLE simulation_runs ADD COLUMN periodic BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,5 @@
+ALTER TABLE simulation_runs DROP COLUMN joule_power;
+ALTER TABLE simulation_runs DROP COLUMN coil_current;
+ALTER TABLE simulation_runs DROP COLUMN coil_frequency;
+ALTER TABLE simulation_runs DROP COLUMN heat_sources;
+ALTER TABLE diagnostics DROP COLUMN heat_generated;
@@ -0,0 +1,5 @@
+ALTER TABLE diagnostics ADD COLUMN heat_generated DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE simulation_runs ADD COLUMN heat_sources TEXT;
+ALTER TABLE simulation_runs ADD COLUMN coil_frequency DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN coil_current DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN joule_power DOUBLE PRECISION;
@@ -0,0 +1,4 @@
+ALTER TABLE results DROP COLUMN gas_stream_function;
+ALTER TABLE results DROP COLUMN gas_vorticity;
+ALTER TABLE simulation_runs DROP COLUMN gas_ports;
+ALTER TABLE simulation_runs DROP COLUMN gas_inflow;
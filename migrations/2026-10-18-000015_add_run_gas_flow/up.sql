@@ -0,0 +1,4 @@
+ALTER TABLE simulation_runs ADD COLUMN gas_inflow DOUBLE PRECISION;
+ALTER TABLE simulation_runs ADD COLUMN gas_ports TEXT;
+ALTER TABLE results ADD COLUMN gas_vorticity DOUBLE PRECISION;
+ALTER TABLE results ADD COLUMN gas_stream_function DOUBLE PRECISION;
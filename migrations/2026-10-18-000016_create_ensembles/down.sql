@@ -0,0 +1,5 @@
+DROP TABLE ensemble_fields;
+DROP TABLE ensemble_statistics;
+ALTER TABLE simulation_runs DROP COLUMN ensemble_sample;
+ALTER TABLE simulation_runs DROP COLUMN ensemble_id;
+DROP TABLE ensembles;
@@ -0,0 +1,38 @@
+CREATE TABLE ensembles (
+    id SERIAL PRIMARY KEY,
+    description TEXT NOT NULL,
+    method TEXT NOT NULL,
+    members INTEGER NOT NULL,
+    seed BIGINT NOT NULL,
+    inputs TEXT NOT NULL,
+    time_steps INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE simulation_runs ADD COLUMN ensemble_id INTEGER REFERENCES ensembles(id) ON DELETE CASCADE;
+ALTER TABLE simulation_runs ADD COLUMN ensemble_sample TEXT;
+
+CREATE TABLE ensemble_statistics (
+    id BIGSERIAL PRIMARY KEY,
+    ensemble_id INTEGER NOT NULL REFERENCES ensembles(id) ON DELETE CASCADE,
+    output TEXT NOT NULL,
+    completed INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    mean DOUBLE PRECISION NOT NULL,
+    std DOUBLE PRECISION NOT NULL,
+    p05 DOUBLE PRECISION NOT NULL,
+    p50 DOUBLE PRECISION NOT NULL,
+    p95 DOUBLE PRECISION NOT NULL
+);
+
+CREATE TABLE ensemble_fields (
+    id BIGSERIAL PRIMARY KEY,
+    ensemble_id INTEGER NOT NULL REFERENCES ensembles(id) ON DELETE CASCADE,
+    statistic TEXT NOT NULL,
+    x INTEGER NOT NULL,
+    y INTEGER NOT NULL,
+    temperature DOUBLE PRECISION NOT NULL,
+    stream_function DOUBLE PRECISION NOT NULL,
+    u_velocity DOUBLE PRECISION NOT NULL,
+    v_velocity DOUBLE PRECISION NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE simulation_runs ADD COLUMN parameters TEXT;
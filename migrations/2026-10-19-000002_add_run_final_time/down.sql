@@ -0,0 +1 @@
+ALTER TABLE simulation_runs DROP COLUMN final_time;
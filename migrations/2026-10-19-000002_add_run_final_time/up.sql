@@ -0,0 +1 @@
+ALTER TABLE simulation_runs ADD COLUMN final_time DOUBLE PRECISION;
@@ -0,0 +1,343 @@
+use ndarray::Array2;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::radiation::RadiationModel;
+use crate::simulation::Domain;
+
+// The four walls of the rectangular cavity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Wall {
+    Bottom,
+    Top,
+    Left,
+    Right,
+}
+
+impl fmt::Display for Wall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Wall::Bottom => "bottom",
+            Wall::Top => "top",
+            Wall::Left => "left",
+            Wall::Right => "right",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Wall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bottom" => Ok(Wall::Bottom),
+            "top" => Ok(Wall::Top),
+            "left" => Ok(Wall::Left),
+            "right" => Ok(Wall::Right),
+            other => anyhow::bail!("unknown wall '{}'", other),
+        }
+    }
+}
+
+// Thermal condition applied to a stretch of wall.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThermalBc {
+    Fixed(f64), // Prescribed temperature
+    Adiabatic,  // Zero normal heat flux
+    Radiative { emissivity: f64, t_env: f64 }, // Net loss ε σ (T⁴ − T_env⁴)
+}
+
+impl ThermalBc {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match *self {
+            ThermalBc::Radiative { emissivity, t_env } => SurfaceEmission { emissivity, t_env }.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+// A thermal condition on the part of `wall` between the fractions `start` and `end`
+// of its length (measured along +x for bottom/top, +y for left/right).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoundarySegment {
+    pub wall: Wall,
+    pub start: f64,
+    pub end: f64,
+    pub bc: ThermalBc,
+}
+
+impl BoundarySegment {
+    pub fn whole(wall: Wall, bc: ThermalBc) -> Self {
+        BoundarySegment { wall, start: 0.0, end: 1.0, bc }
+    }
+
+    // Case files and JSON parameters are deserialized without `FromStr`, so they are
+    // checked here.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let (start, end) = (self.start, self.end);
+        anyhow::ensure!(
+            0.0 <= start && start < end && end <= 1.0,
+            "segment on the {} wall from {} to {} must satisfy 0 <= start < end <= 1",
+            self.wall,
+            start,
+            end
+        );
+        self.bc.validate()
+    }
+
+    fn covers(&self, wall: Wall, s: f64) -> bool {
+        self.wall == wall && s >= self.start - 1e-12 && s <= self.end + 1e-12
+    }
+}
+
+// Parses `wall:start:end:bc` where bc is `fixed=<T>`, `adiabatic` or `rad=<emissivity>,<T_env>`,
+// e.g. `top:0.3:1.0:rad=0.3,0.0`.
+impl FromStr for BoundarySegment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() != 4 {
+            anyhow::bail!("expected wall:start:end:bc, got '{}'", s);
+        }
+        let wall: Wall = parts[0].parse()?;
+        let start: f64 = parts[1].parse()?;
+        let end: f64 = parts[2].parse()?;
+        let bc = match parts[3].split_once('=') {
+            None if parts[3] == "adiabatic" => ThermalBc::Adiabatic,
+            Some(("fixed", t)) => ThermalBc::Fixed(t.parse()?),
+            Some(("rad", args)) => {
+                let SurfaceEmission { emissivity, t_env } = args.parse()?;
+                ThermalBc::Radiative { emissivity, t_env }
+            }
+            _ => anyhow::bail!("unknown boundary condition '{}'", parts[3]),
+        };
+        let segment = BoundarySegment { wall, start, end, bc };
+        segment.validate().with_context(|| format!("invalid segment '{}'", s))?;
+        Ok(segment)
+    }
+}
+
+// Scaling of the radiative condition. With θ = (T − T_cold)/ΔT the wall balance reads
+// (θ_n − θ_b)/h = N_r ε ((θ_b + θ_0)⁴ − (θ_env + θ_0)⁴), N_r = σ ΔT³ L / k, θ_0 = T_cold/ΔT.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RadiationParameters {
+    pub radiation_number: f64,     // N_r = σ ΔT³ L / k
+    pub t_offset: f64,             // θ_0 = T_cold / ΔT (absolute temperature shift)
+    pub surface_to_surface: bool,  // Exchange between radiating walls via view factors
+    pub axisymmetric: bool,        // Ring-to-ring view factors about the centre line
+    pub melt_surface: Option<SurfaceEmission>, // The free melt surface radiates too
+    pub iterations: usize,         // Outer radiosity/temperature iterations per step
+}
+
+impl Default for RadiationParameters {
+    fn default() -> Self {
+        RadiationParameters {
+            radiation_number: 1.0,
+            t_offset: 1.0,
+            surface_to_surface: false,
+            axisymmetric: false,
+            melt_surface: None,
+            iterations: 5,
+        }
+    }
+}
+
+// Emission of the free melt surface, the faces between melt and gas nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceEmission {
+    pub emissivity: f64,
+    pub t_env: f64,
+}
+
+impl SurfaceEmission {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!((0.0..=1.0).contains(&self.emissivity), "emissivity must satisfy 0 <= ε <= 1, got {}", self.emissivity);
+        anyhow::ensure!(self.t_env.is_finite(), "environment temperature must be finite, got {}", self.t_env);
+        Ok(())
+    }
+}
+
+// Parses `<emissivity>,<T_env>` with 0 <= ε <= 1 and a finite T_env, as in the `rad=`
+// wall condition.
+impl FromStr for SurfaceEmission {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (e, t) = s.split_once(',').ok_or_else(|| anyhow::anyhow!("expected <emissivity>,<T_env>, got '{}'", s))?;
+        let emission = SurfaceEmission { emissivity: e.parse()?, t_env: t.parse()? };
+        emission.validate().with_context(|| format!("invalid emission '{}'", s))?;
+        Ok(emission)
+    }
+}
+
+// Thermal boundary description of the cavity. Segments are applied in order,
+// so later segments override earlier ones where they overlap.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThermalBoundary {
+    pub segments: Vec<BoundarySegment>,
+    pub radiation: RadiationParameters,
+}
+
+impl ThermalBoundary {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for segment in &self.segments {
+            segment.validate()?;
+        }
+        if let Some(emission) = &self.radiation.melt_surface {
+            emission.validate().context("melt surface emission")?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThermalBoundary {
+    // Hot bottom/sides, cold top (the "crystal")
+    fn default() -> Self {
+        ThermalBoundary {
+            segments: vec![
+                BoundarySegment::whole(Wall::Bottom, ThermalBc::Fixed(1.0)),
+                BoundarySegment::whole(Wall::Top, ThermalBc::Fixed(0.0)),
+                BoundarySegment::whole(Wall::Left, ThermalBc::Fixed(1.0)),
+                BoundarySegment::whole(Wall::Right, ThermalBc::Fixed(1.0)),
+            ],
+            radiation: RadiationParameters::default(),
+        }
+    }
+}
+
+// A boundary node together with its inward neighbour and the resolved condition.
+#[derive(Clone, Copy, Debug)]
+pub struct BoundaryNode {
+    pub node: (usize, usize),
+    pub inner: (usize, usize),
+    pub wall: Wall,
+    pub h: f64, // Distance to the inward neighbour
+    pub bc: ThermalBc,
+}
+
+impl ThermalBoundary {
+    // Condition in effect at fraction `s` along `wall`, if any segment covers it.
+    pub fn bc_at(&self, wall: Wall, s: f64) -> Option<ThermalBc> {
+        self.segments.iter().rev().find(|seg| seg.covers(wall, s)).map(|seg| seg.bc)
+    }
+
+    pub fn has_radiation(&self) -> bool {
+        self.segments.iter().any(|seg| matches!(seg.bc, ThermalBc::Radiative { .. }))
+    }
+
+    // All non-corner wall nodes of an (ny, nx) grid with their resolved conditions.
+    // Corners never enter the interior stencils and are filled in separately. With
+    // periodic x there are no side walls, and the ghost columns carry no bottom or top
+    // nodes of their own.
+    pub fn nodes(&self, domain: &Domain, nx: usize, ny: usize) -> Vec<BoundaryNode> {
+        let (dx, dy) = domain.spacing(nx, ny);
+        let first = domain.first_column();
+        let mut nodes = Vec::new();
+        for j in 1..nx - 1 {
+            let s = (j - first) as f64 * dx / domain.width;
+            if let Some(bc) = self.bc_at(Wall::Bottom, s) {
+                nodes.push(BoundaryNode { node: (0, j), inner: (1, j), wall: Wall::Bottom, h: dy, bc });
+            }
+            if let Some(bc) = self.bc_at(Wall::Top, s) {
+                nodes.push(BoundaryNode { node: (ny - 1, j), inner: (ny - 2, j), wall: Wall::Top, h: dy, bc });
+            }
+        }
+        if domain.periodic {
+            return nodes;
+        }
+        for i in 1..ny - 1 {
+            let s = i as f64 * dy;
+            if let Some(bc) = self.bc_at(Wall::Left, s) {
+                nodes.push(BoundaryNode { node: (i, 0), inner: (i, 1), wall: Wall::Left, h: dx, bc });
+            }
+            if let Some(bc) = self.bc_at(Wall::Right, s) {
+                nodes.push(BoundaryNode { node: (i, nx - 1), inner: (i, nx - 2), wall: Wall::Right, h: dx, bc });
+            }
+        }
+        nodes
+    }
+
+    // Impose the wall conditions on `temp`. Radiative nodes are solved with Newton's
+    // method; with a surface-to-surface model the irradiation is updated in an outer loop.
+    pub fn apply(&self, temp: &mut Array2<f64>, domain: &Domain, radiation: Option<&RadiationModel>) {
+        let (ny, nx) = temp.dim();
+        let nodes = self.nodes(domain, nx, ny);
+
+        for n in &nodes {
+            match n.bc {
+                ThermalBc::Fixed(t) => temp[n.node] = t,
+                ThermalBc::Adiabatic => temp[n.node] = temp[n.inner],
+                ThermalBc::Radiative { .. } => {}
+            }
+        }
+
+        if self.has_radiation() {
+            let rad = &self.radiation;
+            let outer = if radiation.is_some() { rad.iterations.max(1) } else { 1 };
+            for _ in 0..outer {
+                // Irradiation per radiating node, from the enclosure or the environment alone
+                let irradiation = match radiation {
+                    Some(model) => model.irradiation(temp, rad),
+                    None => Vec::new(),
+                };
+                let mut k = 0;
+                for n in nodes.iter() {
+                    if let ThermalBc::Radiative { emissivity, t_env } = n.bc {
+                        let g = irradiation
+                            .get(k)
+                            .copied()
+                            .unwrap_or_else(|| emissive_power(t_env, rad));
+                        k += 1;
+                        temp[n.node] = solve_radiative_node(temp[n.node], temp[n.inner], n.h, emissivity, g, rad);
+                    }
+                }
+            }
+        }
+
+        self.apply_corners(temp);
+    }
+
+    fn apply_corners(&self, temp: &mut Array2<f64>) {
+        let (ny, nx) = temp.dim();
+        let corners = [
+            ((0, 0), Wall::Left, 0.0, (0, 1), (1, 0)),
+            ((ny - 1, 0), Wall::Left, 1.0, (ny - 1, 1), (ny - 2, 0)),
+            ((0, nx - 1), Wall::Right, 0.0, (0, nx - 2), (1, nx - 1)),
+            ((ny - 1, nx - 1), Wall::Right, 1.0, (ny - 1, nx - 2), (ny - 2, nx - 1)),
+        ];
+        for (node, wall, s, a, b) in corners {
+            temp[node] = match self.bc_at(wall, s) {
+                Some(ThermalBc::Fixed(t)) => t,
+                _ => 0.5 * (temp[a] + temp[b]),
+            };
+        }
+    }
+}
+
+// Dimensionless black-body emissive power N_r (θ + θ_0)⁴.
+pub fn emissive_power(theta: f64, rad: &RadiationParameters) -> f64 {
+    rad.radiation_number * (theta + rad.t_offset).powi(4)
+}
+
+// Newton solve of (θ_b − θ_n)/h + ε (E_b(θ_b) − G) = 0 for the wall temperature θ_b.
+fn solve_radiative_node(theta_b: f64, theta_n: f64, h: f64, emissivity: f64, g: f64, rad: &RadiationParameters) -> f64 {
+    let mut t = theta_b;
+    for _ in 0..20 {
+        let f = (t - theta_n) / h + emissivity * (emissive_power(t, rad) - g);
+        let df = 1.0 / h + 4.0 * emissivity * rad.radiation_number * (t + rad.t_offset).powi(3);
+        let delta = f / df;
+        t -= delta;
+        // Keep the absolute temperature positive while iterating
+        t = t.max(-rad.t_offset);
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    t
+}
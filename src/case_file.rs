@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::boundary::{BoundarySegment, ThermalBc, Wall};
+use crate::mask::{Obstacle, Shape};
+use crate::probes::{MonitorLine, Probe};
+use crate::regions::Region;
+
+// Number of straight pieces an arc is flattened into before rasterizing.
+const ARC_SEGMENTS: usize = 128;
+
+// Samples per wall used to turn shape boundary tags into wall segments.
+const WALL_SAMPLES: usize = 2000;
+
+// Human-editable geometry of a case, in domain coordinates (0 ≤ x ≤ width, 0 ≤ y ≤ 1 in
+// units of the domain height) so the same file can be rasterized at any grid size. Shapes
+// are painted in order, later ones on top; a shape's `bc` applies wherever it is the
+// topmost shape along a domain wall. Probes and lines are in the same coordinates.
+//
+//   background = "melt"
+//   [[shapes]]
+//   region = "crucible"
+//   type = "polygon"
+//   points = [[0.0, 0.0], [1.0, 0.0], [1.0, 0.1], [0.0, 0.1]]
+//   bc = { fixed = 1.0 }
+//
+//   [[shapes]]
+//   region = "crystal"
+//   type = "arc"
+//   center = [0.5, 1.0]
+//   radius = 0.2
+//   start_angle = 180.0
+//   end_angle = 360.0
+//
+//   [[probes]]
+//   name = "tc-bottom"
+//   x = 0.5
+//   y = 0.05
+#[derive(Debug, Deserialize)]
+pub struct CaseFile {
+    pub name: Option<String>,
+    pub background: Option<Region>,
+    #[serde(default)]
+    pub shapes: Vec<ShapeSpec>,
+    #[serde(default)]
+    pub boundaries: Vec<BoundarySegment>, // Explicit wall segments, applied after the shape tags
+    #[serde(default)]
+    pub probes: Vec<Probe>, // Thermocouples and other monitoring points
+    #[serde(default)]
+    pub lines: Vec<MonitorLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShapeSpec {
+    pub region: Region,
+    #[serde(flatten)]
+    pub geometry: Geometry,
+    pub bc: Option<ThermalBc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Geometry {
+    Polygon {
+        points: Vec<[f64; 2]>,
+    },
+    // Circular arc from `start_angle` to `end_angle` (degrees, counter-clockwise), closed
+    // by its chord, or through the centre when `sector` is set.
+    Arc {
+        center: [f64; 2],
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        #[serde(default)]
+        sector: bool,
+    },
+}
+
+impl Geometry {
+    fn to_shape(&self) -> Shape {
+        match self {
+            Geometry::Polygon { points } => Shape::Polygon(points.iter().map(|p| (p[0], p[1])).collect()),
+            Geometry::Arc { center, radius, start_angle, end_angle, sector } => {
+                let (a0, a1) = (start_angle.to_radians(), end_angle.to_radians());
+                let sweep = (a1 - a0).rem_euclid(2.0 * PI);
+                let sweep = if sweep == 0.0 { 2.0 * PI } else { sweep };
+                let mut points: Vec<(f64, f64)> = (0..=ARC_SEGMENTS)
+                    .map(|k| {
+                        let a = a0 + sweep * k as f64 / ARC_SEGMENTS as f64;
+                        (center[0] + radius * a.cos(), center[1] + radius * a.sin())
+                    })
+                    .collect();
+                if *sector {
+                    points.push((center[0], center[1]));
+                }
+                Shape::Polygon(points)
+            }
+        }
+    }
+}
+
+impl CaseFile {
+    // Load a case from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading case file {}", path.display()))?;
+        let case: CaseFile = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&text)?,
+            Some("toml") => toml::from_str(&text)?,
+            _ => anyhow::bail!("case file {} must be .toml or .json", path.display()),
+        };
+        case.validate().with_context(|| format!("case file {}", path.display()))?;
+        Ok(case)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for segment in &self.boundaries {
+            segment.validate()?;
+        }
+        for shape in &self.shapes {
+            if let Some(bc) = &shape.bc {
+                bc.validate().with_context(|| format!("boundary condition of the {:?} shape", shape.region))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Shapes to paint over the region map of a domain `width` wide, background first.
+    pub fn obstacles(&self, width: f64) -> Vec<Obstacle> {
+        let background = self.background.map(|region| Obstacle {
+            shape: Shape::Rect { x0: 0.0, y0: 0.0, x1: width, y1: 1.0 },
+            region,
+        });
+        background
+            .into_iter()
+            .chain(self.shapes.iter().map(|s| Obstacle { shape: s.geometry.to_shape(), region: s.region }))
+            .collect()
+    }
+
+    // Wall segments from the shape tags followed by the explicit boundaries. Tags are
+    // resolved by sampling just inside each wall of a domain `width` wide, independently
+    // of the grid.
+    pub fn boundary_segments(&self, width: f64) -> Vec<BoundarySegment> {
+        let shapes: Vec<(Shape, Option<ThermalBc>)> = self.shapes.iter().map(|s| (s.geometry.to_shape(), s.bc)).collect();
+        let inset = 1e-6;
+        let mut segments = Vec::new();
+
+        for wall in [Wall::Bottom, Wall::Top, Wall::Left, Wall::Right] {
+            let mut current: Option<(f64, ThermalBc)> = None;
+            for k in 0..=WALL_SAMPLES {
+                let s = k as f64 / WALL_SAMPLES as f64;
+                let (x, y) = match wall {
+                    Wall::Bottom => (s * width, inset),
+                    Wall::Top => (s * width, 1.0 - inset),
+                    Wall::Left => (inset, s),
+                    Wall::Right => (width - inset, s),
+                };
+                let bc = shapes.iter().rev().find(|(shape, _)| shape.contains(x, y, width)).and_then(|(_, bc)| *bc);
+                match (current, bc) {
+                    (Some((_, open)), Some(bc)) if open == bc => {}
+                    _ => {
+                        if let Some((start, open)) = current.take() {
+                            segments.push(BoundarySegment { wall, start, end: s, bc: open });
+                        }
+                        current = bc.map(|bc| (s, bc));
+                    }
+                }
+            }
+            if let Some((start, open)) = current {
+                segments.push(BoundarySegment { wall, start, end: 1.0, bc: open });
+            }
+        }
+
+        segments.extend(self.boundaries.iter().cloned());
+        segments
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::conservation::ConservationMonitor;
+use crate::diagnostics::DiagnosticsLog;
+use crate::growth::GrowthRecord;
+use crate::mask::CellType;
+use crate::probes::ProbeSet;
+use crate::regions::Region;
+use crate::simulation::{SimParameters, SimState};
+use crate::watchdog::BlowUp;
+
+const MAGIC: &[u8; 8] = b"CZCKPT\0\0";
+// Bumped when a released format changes; loading accepts this version only.
+const VERSION: u32 = 1;
+
+// Set from the signal handler; the run loop checkpoints and stops at the next step boundary.
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// Route SIGINT/SIGTERM to a checkpoint-and-stop request instead of killing the process.
+// The handler only raises a flag, so the file is always written from the main loop.
+pub fn install_signal_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if STOP_REQUESTED.swap(true, Ordering::SeqCst) {
+            eprintln!("Second interrupt, exiting without checkpoint");
+            std::process::exit(130);
+        }
+        eprintln!("Interrupt received, checkpointing after the current step...");
+    })?;
+    Ok(())
+}
+
+pub fn stop_requested() -> bool {
+    STOP_REQUESTED.load(Ordering::SeqCst)
+}
+
+// Everything besides the fields that is needed to continue a run bit for bit. Stored as
+// TOML, which writes NaN and infinities as such: the dumps of a run that blew up carry
+// them in the conservation balance, the diagnostics and the probe samples.
+#[derive(Serialize, Deserialize)]
+pub struct CheckpointMeta {
+    pub run_id: Option<i32>,
+    pub target_steps: usize, // Total steps the run was started for
+    pub steps_taken: usize,
+    pub time: f64,
+    pub params: SimParameters, // Current parameters, including the evolved geometry
+    pub gas_dt: f64,           // Step limit the gas flow set at the last remesh
+    pub crystal_length: Option<f64>,
+    pub growth_history: Vec<GrowthRecord>,
+    pub eddy_potential: Vec<[f64; 2]>, // A of the induction solve, row-major (re, im); empty without a coil
+    // The watchdog as set for the run, with the peaks the next growth check compares to.
+    // The last good snapshot is not stored: it is a copy of the fields, and the first
+    // check after resuming keeps a new one.
+    pub watchdog_interval: usize,
+    pub watchdog_peaks: [f64; 8],
+    pub dump_prefix: Option<String>,
+    pub diagnostics: Option<DiagnosticsLog>,
+    pub probes: Option<ProbeSet>,
+    pub conservation: ConservationMonitor,
+    pub blow_up: Option<BlowUp>, // Set in the state dumps of a run that blew up
+}
+
+// Binary layout, all little endian:
+//   magic (8) | version u32 | meta length u64 | meta TOML | ny u64 | nx u64
+//   | temp, vort, stream, u, v, conc, gas_vort, gas_stream as ny·nx f64
+//   | region ids, cell types as ny·nx i16
+pub struct Checkpoint {
+    pub meta: CheckpointMeta,
+    pub state: SimState,
+}
+
+impl Checkpoint {
+    // Write to a temporary file and rename it over `path`, so an interrupted write
+    // never destroys the previous checkpoint.
+    pub fn save(meta: &CheckpointMeta, state: &SimState, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("ckpt.tmp");
+        {
+            let mut out = BufWriter::new(File::create(&tmp).with_context(|| format!("creating {}", tmp.display()))?);
+            let meta_toml = toml::to_string(meta)?;
+            let (ny, nx) = state.temp.dim();
+
+            out.write_all(MAGIC)?;
+            out.write_all(&VERSION.to_le_bytes())?;
+            out.write_all(&(meta_toml.len() as u64).to_le_bytes())?;
+            out.write_all(meta_toml.as_bytes())?;
+            out.write_all(&(ny as u64).to_le_bytes())?;
+            out.write_all(&(nx as u64).to_le_bytes())?;
+            for field in [&state.temp, &state.vort, &state.stream, &state.u, &state.v, &state.conc, &state.gas_vort, &state.gas_stream] {
+                for value in field.iter() {
+                    out.write_all(&value.to_le_bytes())?;
+                }
+            }
+            for region in state.region.iter() {
+                out.write_all(&region.id().to_le_bytes())?;
+            }
+            for cell in state.mask.iter() {
+                out.write_all(&cell.id().to_le_bytes())?;
+            }
+            out.flush()?;
+            out.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut input = BufReader::new(File::open(path).with_context(|| format!("opening {}", path.display()))?);
+
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        anyhow::ensure!(&magic == MAGIC, "{} is not a simulation checkpoint", path.display());
+        let version = u32::from_le_bytes(read_bytes(&mut input)?);
+        anyhow::ensure!(version == VERSION, "unsupported checkpoint version {} (expected {})", version, VERSION);
+
+        let meta_len = u64::from_le_bytes(read_bytes(&mut input)?) as usize;
+        let mut meta_toml = vec![0u8; meta_len];
+        input.read_exact(&mut meta_toml)?;
+        let meta: CheckpointMeta = toml::from_str(std::str::from_utf8(&meta_toml)?)?;
+
+        let ny = u64::from_le_bytes(read_bytes(&mut input)?) as usize;
+        let nx = u64::from_le_bytes(read_bytes(&mut input)?) as usize;
+        let mut state = SimState::new(nx, ny);
+        for field in [&mut state.temp, &mut state.vort, &mut state.stream, &mut state.u, &mut state.v, &mut state.conc, &mut state.gas_vort, &mut state.gas_stream] {
+            read_field(&mut input, field)?;
+        }
+        for region in state.region.iter_mut() {
+            let id = i16::from_le_bytes(read_bytes(&mut input)?);
+            *region = Region::from_id(id).with_context(|| format!("bad region id {}", id))?;
+        }
+        for cell in state.mask.iter_mut() {
+            let id = i16::from_le_bytes(read_bytes(&mut input)?);
+            *cell = CellType::from_id(id).with_context(|| format!("bad cell type {}", id))?;
+        }
+
+        Ok(Checkpoint { meta, state })
+    }
+}
+
+fn read_bytes<const N: usize>(input: &mut impl Read) -> Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    input.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_field(input: &mut impl Read, field: &mut Array2<f64>) -> Result<()> {
+    for value in field.iter_mut() {
+        *value = f64::from_le_bytes(read_bytes(input)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::Wall;
+use crate::gas::GasFlow;
+use crate::regions::face_conductivity;
+use crate::simulation::SimState;
+
+// A run is flagged as badly resolved when its final balance is worse than these.
+pub const HEAT_IMBALANCE_LIMIT: f64 = 0.05;
+pub const DIVERGENCE_LIMIT: f64 = 1e-8;
+pub const POISSON_RESIDUAL_LIMIT: f64 = 1e-2;
+
+// Global conservation errors of one time step:
+//   heat: Q_in − Q_out + Q_gen = dE/dt with Q through the outer walls, Q_gen = ∫ Q dA of
+//         the volumetric sources and E = ∫ ρc T dA, relative to the largest of the four;
+//   mass: max |∇·u| of the central differences of u = ∂ψ/∂y, v = −∂ψ/∂x, relative to
+//         max |u| / Δx;
+//   ψ:    max |∇²ψ + ω| after the stream function solve, relative to max |ω|.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    pub heat_in: f64,      // Heat entering through the walls
+    pub heat_out: f64,     // Heat leaving through the walls
+    #[serde(default)]
+    pub heat_generated: f64, // Heat released by volumetric sources
+    pub storage_rate: f64, // dE/dt
+    pub heat_imbalance: f64,
+    pub divergence: f64,
+    pub poisson_residual: f64,
+}
+
+impl Balance {
+    pub fn flagged(&self) -> bool {
+        self.heat_imbalance > HEAT_IMBALANCE_LIMIT
+            || self.divergence > DIVERGENCE_LIMIT
+            || self.poisson_residual > POISSON_RESIDUAL_LIMIT
+    }
+}
+
+// Balance of the latest step and the worst errors seen, updated by `Simulation::step`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ConservationMonitor {
+    pub latest: Balance,
+    pub worst: Balance, // Largest of each relative error; the heat terms are unused
+    energy: Option<f64>, // E after the previous step
+}
+
+impl ConservationMonitor {
+    // Close the balance of a step from the wall and source heat at its start, the
+    // diagnostics taken during it and the stored energy at its end. The first step only
+    // records E.
+    pub fn update(&mut self, wall_heat: (f64, f64), heat_generated: f64, energy: f64, dt: f64, divergence: f64, poisson_residual: f64) {
+        let Some(previous) = self.energy.replace(energy) else {
+            return;
+        };
+        let (heat_in, heat_out) = wall_heat;
+        let storage_rate = (energy - previous) / dt;
+        let scale = heat_in.max(heat_out).max(heat_generated.abs()).max(storage_rate.abs());
+        let heat_imbalance = if scale > 1e-12 { (heat_in - heat_out + heat_generated - storage_rate).abs() / scale } else { 0.0 };
+
+        self.latest = Balance { heat_in, heat_out, heat_generated, storage_rate, heat_imbalance, divergence, poisson_residual };
+        self.worst.heat_imbalance = self.worst.heat_imbalance.max(heat_imbalance);
+        self.worst.divergence = self.worst.divergence.max(divergence);
+        self.worst.poisson_residual = self.worst.poisson_residual.max(poisson_residual);
+    }
+}
+
+// Heat entering and leaving through the given outer walls: the conductive flux of the scheme,
+// k_face (T_wall − T_inner) / h, across the faces between each wall node and its inner
+// neighbour. Together with `stored_energy` over the same interior nodes, conduction closes
+// exactly and the imbalance measures the advection scheme and the stream function solve.
+pub fn wall_heat(temp: &Array2<f64>, conductivity: &Array2<f64>, spacing: (f64, f64), walls: &[Wall]) -> (f64, f64) {
+    let (ny, nx) = temp.dim();
+    let (dx, dy) = spacing;
+    let (mut heat_in, mut heat_out) = (0.0, 0.0);
+    for &wall in walls {
+        let (len, h, width) = match wall {
+            Wall::Bottom | Wall::Top => (nx, dy, dx),
+            Wall::Left | Wall::Right => (ny, dx, dy),
+        };
+        // Wall node and its inner neighbour at position s along the wall
+        let nodes = |s: usize| match wall {
+            Wall::Bottom => ([0, s], [1, s]),
+            Wall::Top => ([ny - 1, s], [ny - 2, s]),
+            Wall::Left => ([s, 0], [s, 1]),
+            Wall::Right => ([s, nx - 1], [s, nx - 2]),
+        };
+        for s in 1..len - 1 {
+            let (outer, inner) = nodes(s);
+            let k = face_conductivity(conductivity[outer], conductivity[inner]);
+            let q = width * k * (temp[outer] - temp[inner]) / h;
+            if q > 0.0 {
+                heat_in += q;
+            } else {
+                heat_out -= q;
+            }
+        }
+    }
+    (heat_in, heat_out)
+}
+
+// E = ∫ ρc T dA over the interior nodes, whose temperatures the scheme advances.
+pub fn stored_energy(temp: &Array2<f64>, heat_capacity: &Array2<f64>, spacing: (f64, f64)) -> f64 {
+    let (ny, nx) = temp.dim();
+    let (dx, dy) = spacing;
+    let mut sum = 0.0;
+    for i in 1..ny - 1 {
+        for j in 1..nx - 1 {
+            sum += heat_capacity[[i, j]] * temp[[i, j]];
+        }
+    }
+    sum * dx * dy
+}
+
+// ∫ Q dA of a volumetric source over the same interior nodes as `stored_energy`.
+pub fn generated_heat(q: &Array2<f64>, spacing: (f64, f64)) -> f64 {
+    let (ny, nx) = q.dim();
+    let (dx, dy) = spacing;
+    q.slice(ndarray::s![1..ny - 1, 1..nx - 1]).sum() * dx * dy
+}
+
+// Largest relative discrete divergence over the interior melt and gas nodes, of the
+// velocities u = ∂ψ/∂y, v = −∂ψ/∂x differenced centrally from the stream function of
+// each (ψ in the melt, the gas stream function in the gas). The central differences of
+// these cancel, so anything above round-off means a broken stream function.
+pub fn divergence(state: &SimState, gas: Option<&GasFlow>, spacing: (f64, f64)) -> f64 {
+    let (ny, nx) = state.stream.dim();
+    let (dx, dy) = spacing;
+    let (mut largest, mut speed): (f64, f64) = (0.0, 0.0);
+    for i in 1..ny - 1 {
+        for j in 1..nx - 1 {
+            let psi = if state.is_fluid(i, j) {
+                &state.stream
+            } else if gas.is_some_and(|gas| gas.is_fluid(i, j)) {
+                &state.gas_stream
+            } else {
+                continue;
+            };
+            let u = |i: usize, j: usize| (psi[[i + 1, j]] - psi[[i - 1, j]]) / (2.0 * dy);
+            let v = |i: usize, j: usize| -(psi[[i, j + 1]] - psi[[i, j - 1]]) / (2.0 * dx);
+            let div = (u(i, j + 1) - u(i, j - 1)) / (2.0 * dx) + (v(i + 1, j) - v(i - 1, j)) / (2.0 * dy);
+            largest = largest.max(div.abs());
+            speed = speed.max(u(i, j).abs()).max(v(i, j).abs());
+        }
+    }
+    if speed > 0.0 { largest * dx.min(dy) / speed } else { largest }
+}
+
+// Largest relative residual of ∇²ψ = −f over the interior fluid nodes, with f = ω or
+// ω less a manufactured source.
+pub fn poisson_residual(stream: &Array2<f64>, forcing: &Array2<f64>, state: &SimState, spacing: (f64, f64)) -> f64 {
+    let (ny, nx) = stream.dim();
+    let (dx, dy) = spacing;
+    let (mut largest, mut scale): (f64, f64) = (0.0, 0.0);
+    for i in 1..ny - 1 {
+        for j in 1..nx - 1 {
+            if !state.is_fluid(i, j) {
+                continue;
+            }
+            let laplacian = (stream[[i, j + 1]] - 2.0 * stream[[i, j]] + stream[[i, j - 1]]) / (dx * dx)
+                + (stream[[i + 1, j]] - 2.0 * stream[[i, j]] + stream[[i - 1, j]]) / (dy * dy);
+            largest = largest.max((laplacian + forcing[[i, j]]).abs());
+            scale = scale.max(forcing[[i, j]].abs());
+        }
+    }
+    if scale > 0.0 { largest / scale } else { largest }
+}
@@ -0,0 +1,302 @@
+use anyhow::Result;
+use std::str::FromStr;
+
+use crate::boundary::Wall;
+use crate::diagnostics::wall_nusselt;
+use crate::linalg;
+use crate::simulation::SimParameters;
+use crate::stability::{self, SteadyOperator, StabilitySettings};
+
+// Parameter the steady branch is followed in. The model has neither rotation nor a
+// thermocapillary surface, so there is no Reynolds or Marangoni number to follow and
+// `re` and `ma` are refused when parsed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContinuationParameter {
+    Rayleigh,
+    Prandtl,
+}
+
+impl ContinuationParameter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContinuationParameter::Rayleigh => "ra",
+            ContinuationParameter::Prandtl => "pr",
+        }
+    }
+
+    pub fn get(&self, params: &SimParameters) -> f64 {
+        match self {
+            ContinuationParameter::Rayleigh => params.ra,
+            ContinuationParameter::Prandtl => params.pr,
+        }
+    }
+
+    pub fn set(&self, params: &mut SimParameters, value: f64) {
+        match self {
+            ContinuationParameter::Rayleigh => params.ra = value,
+            ContinuationParameter::Prandtl => params.pr = value,
+        }
+    }
+}
+
+impl FromStr for ContinuationParameter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ra" | "rayleigh" => Ok(ContinuationParameter::Rayleigh),
+            "pr" | "prandtl" => Ok(ContinuationParameter::Prandtl),
+            "re" | "reynolds" => anyhow::bail!("the model has no crystal or crucible rotation, so there is no Reynolds number to continue in (ra, pr)"),
+            "ma" | "marangoni" => anyhow::bail!("the melt surface has no thermocapillary stress, so there is no Marangoni number to continue in (ra, pr)"),
+            other => anyhow::bail!("'{}' is not a continuation parameter of this model (ra, pr)", other),
+        }
+    }
+}
+
+pub struct ContinuationSettings {
+    pub parameter: ContinuationParameter,
+    pub target: f64,       // Stop once the parameter passes this value
+    pub ds: f64,           // Initial arclength step
+    pub ds_min: f64,
+    pub ds_max: f64,
+    pub max_points: usize,
+    pub newton_tol: f64,   // Relative size of the last Newton update
+    pub max_newton: usize,
+    pub nusselt_wall: Wall,
+    pub eigenvalues: Option<StabilitySettings>, // Leading eigenvalue at every point, if set
+}
+
+// Special points are flagged on the first point past the event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointKind {
+    Regular,
+    Fold,        // dp/ds changes sign
+    Bifurcation, // A real eigenvalue crosses zero away from a fold
+    Hopf,        // A complex pair crosses the imaginary axis
+}
+
+impl PointKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PointKind::Regular => "regular",
+            PointKind::Fold => "fold",
+            PointKind::Bifurcation => "bifurcation",
+            PointKind::Hopf => "hopf",
+        }
+    }
+}
+
+// One converged steady state on the branch.
+#[derive(Clone, Debug)]
+pub struct BranchPoint {
+    pub index: usize,
+    pub parameter: f64,
+    pub nusselt: f64,    // Mean |Nu| on the diagnostic wall
+    pub max_stream: f64, // max |ψ|
+    pub arclength: f64,
+    pub growth_rate: Option<f64>, // Leading eigenvalue, when computed
+    pub frequency: Option<f64>,
+    pub kind: PointKind,
+}
+
+// What the branch following reports as it goes, for the caller to show.
+pub enum ContinuationEvent<'a> {
+    Started { newton: usize },                        // The starting point converged
+    Retry { ds: f64 },                                // The corrector failed; the step was cut to ds
+    Point { point: &'a BranchPoint, newton: usize }, // A point was accepted
+}
+
+// Pseudo-arclength continuation of steady states y = (x, p̂) with p̂ = p / p_scale.
+// Each step predicts along the secant (the parameter direction for the first step) and
+// corrects with Newton–GMRES on the bordered system
+//   F(x, p) = 0,   ⟨t, y − y_pred⟩ = 0,
+// using matrix-free Jacobian-vector products of the steady residual. The inner product
+// weights the state by 1/N so that the step is not dominated by the grid size.
+pub struct Continuation {
+    pub op: SteadyOperator,
+    pub settings: ContinuationSettings,
+    scale: f64,
+    x: Vec<f64>,
+    p: f64, // Scaled parameter p̂
+    pub points: Vec<BranchPoint>,
+}
+
+impl Continuation {
+    pub fn new(op: SteadyOperator, settings: ContinuationSettings) -> Self {
+        let p0 = settings.parameter.get(&op.sim.params);
+        let scale = if p0 != 0.0 { p0.abs() } else { 1.0 };
+        let x = op.state();
+        Continuation { op, settings, scale, x, p: p0 / scale, points: Vec::new() }
+    }
+
+    pub fn parameter(&self) -> f64 {
+        self.p * self.scale
+    }
+
+    fn weighted_dot(&self, a: (&[f64], f64), b: (&[f64], f64)) -> f64 {
+        linalg::dot(a.0, b.0) / a.0.len() as f64 + a.1 * b.1
+    }
+
+    fn residual(&mut self, x: &[f64], p: f64) -> Vec<f64> {
+        self.settings.parameter.set(&mut self.op.sim.params, p * self.scale);
+        self.op.residual(x)
+    }
+
+    // Newton–GMRES on the bordered system with tangent `t` from the predicted point.
+    // Returns the number of Newton iterations on success.
+    fn correct(&mut self, pred: (&[f64], f64), t: (&[f64], f64)) -> Option<(Vec<f64>, f64, usize)> {
+        let (mut x, mut p) = (pred.0.to_vec(), pred.1);
+        let n = x.len();
+        for iteration in 1..=self.settings.max_newton {
+            self.op.set_base(&x);
+            let g = self.residual(&x, p);
+            let offset: Vec<f64> = x.iter().zip(pred.0).map(|(a, b)| a - b).collect();
+            let constraint = self.weighted_dot((&offset, p - pred.1), t);
+
+            let mut rhs: Vec<f64> = g.iter().map(|v| -v).collect();
+            rhs.push(-constraint);
+            let eps_scale = f64::EPSILON.sqrt() * (1.0 + linalg::norm(&x) / (n as f64).sqrt() + p.abs());
+            let apply = |v: &[f64]| {
+                let (vx, vp) = v.split_at(n);
+                let v_norm = linalg::norm(v);
+                if v_norm == 0.0 {
+                    return vec![0.0; n + 1];
+                }
+                let eps = eps_scale / v_norm;
+                let mut shifted = x.clone();
+                linalg::axpy(&mut shifted, eps, vx);
+                let shifted_g = self.residual(&shifted, p + eps * vp[0]);
+                let mut jv: Vec<f64> = shifted_g.iter().zip(&g).map(|(a, b)| (a - b) / eps).collect();
+                jv.push(self.weighted_dot((vx, vp[0]), t));
+                jv
+            };
+            let (delta, _, _) = linalg::gmres(apply, &rhs, 1e-6, 80, 2000);
+
+            let (dx, dp) = delta.split_at(n);
+            linalg::axpy(&mut x, 1.0, dx);
+            p += dp[0];
+            if !p.is_finite() || x.iter().any(|v| !v.is_finite()) {
+                return None;
+            }
+            let step = self.weighted_dot((dx, dp[0]), (dx, dp[0])).sqrt();
+            let size = self.weighted_dot((&x, p), (&x, p)).sqrt();
+            if step <= self.settings.newton_tol * (1.0 + size) {
+                return Some((x, p, iteration));
+            }
+        }
+        None
+    }
+
+    // Converge the starting state at its own parameter value, held fixed by the constraint
+    // p̂ = p̂0, and leave the simulation on it. Returns the number of Newton iterations.
+    pub fn converge_start(&mut self) -> Result<usize> {
+        let zero = vec![0.0; self.x.len()];
+        let start = (self.x.clone(), self.p);
+        let (x, p, iterations) = self
+            .correct((&start.0, start.1), (&zero, 1.0))
+            .ok_or_else(|| anyhow::anyhow!("Newton did not converge at the starting point"))?;
+        self.x = x;
+        self.p = p;
+        self.settings.parameter.set(&mut self.op.sim.params, p * self.scale);
+        self.op.set_base(&self.x.clone());
+        Ok(iterations)
+    }
+
+    // Converge the starting state and follow the branch until the target is passed,
+    // the point budget is used up or the step collapses, telling `report` each step.
+    pub fn run(&mut self, mut report: impl FnMut(ContinuationEvent)) -> Result<()> {
+        let iterations = self.converge_start()?;
+        self.record(0.0, PointKind::Regular);
+        report(ContinuationEvent::Started { newton: iterations });
+
+        // First predictor along the parameter, towards the target
+        let direction = (self.settings.target - self.parameter()).signum();
+        let mut tangent = (vec![0.0; self.x.len()], direction);
+        let mut ds = self.settings.ds;
+        let mut arclength = 0.0;
+
+        while self.points.len() < self.settings.max_points {
+            let pred_x: Vec<f64> = self.x.iter().zip(&tangent.0).map(|(x, t)| x + ds * t).collect();
+            let pred_p = self.p + ds * tangent.1;
+            let Some((x, p, iterations)) = self.correct((&pred_x, pred_p), (&tangent.0, tangent.1)) else {
+                ds *= 0.5;
+                if ds < self.settings.ds_min {
+                    anyhow::bail!("step size fell below {} at {} = {:e}", self.settings.ds_min, self.settings.parameter.name(), self.parameter());
+                }
+                report(ContinuationEvent::Retry { ds });
+                continue;
+            };
+
+            // Secant tangent of the accepted step
+            let dx: Vec<f64> = x.iter().zip(&self.x).map(|(a, b)| a - b).collect();
+            let dp = p - self.p;
+            let length = self.weighted_dot((&dx, dp), (&dx, dp)).sqrt();
+            let new_tangent = (linalg::scaled(&dx, 1.0 / length), dp / length);
+            let fold = self.points.len() > 1 && new_tangent.1.signum() != tangent.1.signum();
+
+            self.x = x;
+            self.p = p;
+            arclength += length;
+            tangent = new_tangent;
+            self.record(arclength, if fold { PointKind::Fold } else { PointKind::Regular });
+
+            let last = self.points.last().expect("just recorded");
+            report(ContinuationEvent::Point { point: last, newton: iterations });
+
+            if (self.parameter() - self.settings.target) * direction >= 0.0 {
+                break;
+            }
+            ds = if iterations <= 3 {
+                (ds * 1.5).min(self.settings.ds_max)
+            } else if iterations >= 6 {
+                (ds * 0.7).max(self.settings.ds_min)
+            } else {
+                ds
+            };
+        }
+
+        // Leave the simulation on the last converged state
+        let x = self.x.clone();
+        let p = self.p;
+        self.settings.parameter.set(&mut self.op.sim.params, p * self.scale);
+        self.op.set_base(&x);
+        Ok(())
+    }
+
+    // Append the current state, classifying eigenvalue crossings against the previous point.
+    fn record(&mut self, arclength: f64, mut kind: PointKind) {
+        let (x, parameter) = (self.x.clone(), self.parameter());
+        self.settings.parameter.set(&mut self.op.sim.params, parameter);
+        self.op.set_base(&x);
+        let nusselt = wall_nusselt(&self.op.sim.state.temp, self.settings.nusselt_wall, &self.op.sim.params).abs();
+        let max_stream = self.op.sim.state.stream.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+
+        let leading = self.settings.eigenvalues.as_ref().and_then(|settings| {
+            stability::leading_modes(&mut self.op, settings)
+                .into_iter()
+                .max_by(|a, b| a.growth_rate.total_cmp(&b.growth_rate))
+        });
+        let (growth_rate, frequency) = match leading {
+            Some(mode) => (Some(mode.growth_rate), Some(mode.frequency())),
+            None => (None, None),
+        };
+
+        let previous = self.points.last().and_then(|p| p.growth_rate);
+        if let (Some(before), Some(now)) = (previous, growth_rate) {
+            if kind == PointKind::Regular && before.signum() != now.signum() {
+                kind = if frequency.unwrap_or(0.0) > 0.0 { PointKind::Hopf } else { PointKind::Bifurcation };
+            }
+        }
+
+        self.points.push(BranchPoint {
+            index: self.points.len(),
+            parameter: self.parameter(),
+            nusselt,
+            max_stream,
+            arclength,
+            growth_rate,
+            frequency,
+            kind,
+        });
+    }
+}
@@ -5,19 +5,33 @@ use dotenvy::dotenv;
 use std::env;
 use anyhow::Result;
 
-use crate::models::{NewResultPoint, NewSimulationRun, SimulationRun};
-use crate::schema::{results, simulation_runs};
-use crate::simulation::SimState;
+use crate::continuation::BranchPoint;
+use crate::conservation::Balance;
+use crate::diagnostics::DiagnosticsSample;
+use crate::growth::GrowthRecord;
+use crate::gas::GasParameters;
+use crate::heating::HeatSource;
+use crate::induction::InductionParameters;
+use crate::mask::CellType;
+use crate::probes::ProbeSample;
+use crate::materials::{DimensionlessGroups, MaterialProperties, ProcessInputs};
+use crate::perturbation::Perturbation;
+use crate::models::{
+    DiagnosticsRecord, Ensemble, NewContinuationPoint, NewEnsemble, NewEnsembleFieldPoint, NewEnsembleStatistic, NewGrowthRecord, NewResultPoint,
+    NewSimulationRun, ProbeRecord, RunConservation, RunEnsembleMember, RunGasFlow, RunHeating, RunProcess, SimulationRun,
+};
+use crate::schema::{continuation_points, diagnostics, ensemble_fields, ensemble_statistics, ensembles, growth_history, probe_samples, results, simulation_runs};
+use crate::regions::Region;
+use crate::simulation::{Domain, SimParameters, SimState};
+use crate::uq::{FieldStatistics, Sampling, SummaryStats, UncertainInput, FIELD_STATISTICS};
 
 pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 
-pub fn establish_connection_pool() -> DbPool {
+pub fn establish_connection_pool() -> Result<DbPool> {
     dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let database_url = env::var("DATABASE_URL").map_err(|_| anyhow::anyhow!("DATABASE_URL must be set"))?;
     let manager = ConnectionManager::<PgConnection>::new(database_url);
-    r2d2::Pool::builder()
-        .build(manager)
-        .expect("Failed to create pool.")
+    Ok(r2d2::Pool::builder().build(manager)?)
 }
 
 pub fn create_simulation_run(
@@ -27,6 +41,7 @@ pub fn create_simulation_run(
     time_steps: i32,
     pr: f64,
     ra: f64,
+    parent: Option<i32>,
 ) -> Result<SimulationRun> {
     let mut conn = pool.get()?;
     let new_run = NewSimulationRun {
@@ -35,6 +50,7 @@ pub fn create_simulation_run(
         time_steps,
         prandtl_number: pr,
         rayleigh_number: ra,
+        parent_run_id: parent,
     };
 
     let run = diesel::insert_into(simulation_runs::table)
@@ -43,12 +59,127 @@ pub fn create_simulation_run(
     Ok(run)
 }
 
+// Record the initial perturbation of a run so its transition can be reproduced.
+pub fn set_run_perturbation(pool: &DbPool, run_id: i32, perturbation: &Perturbation) -> Result<()> {
+    let mut conn = pool.get()?;
+    diesel::update(simulation_runs::table.find(run_id))
+        .set((
+            simulation_runs::perturbation.eq(Some(perturbation.to_string())),
+            simulation_runs::perturbation_seed.eq(perturbation.seed().map(|seed| seed as i64)),
+        ))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+pub fn set_run_process(
+    pool: &DbPool,
+    run_id: i32,
+    material: &MaterialProperties,
+    process: &ProcessInputs,
+    groups: &DimensionlessGroups,
+) -> Result<()> {
+    let mut conn = pool.get()?;
+    let changes = RunProcess {
+        material: &material.name,
+        crucible_radius: process.crucible_radius,
+        melt_height: process.melt_height,
+        crystal_radius: process.crystal_radius,
+        delta_t: process.delta_t,
+        crystal_rotation: process.crystal_rotation,
+        crucible_rotation: process.crucible_rotation,
+        magnetic_field: process.magnetic_field,
+        gravity: process.gravity,
+        grashof_number: groups.grashof,
+        marangoni_number: groups.marangoni,
+        reynolds_number: groups.reynolds,
+        taylor_number: groups.taylor,
+        hartmann_number: groups.hartmann,
+    };
+    diesel::update(simulation_runs::table.find(run_id)).set(&changes).execute(&mut conn)?;
+    Ok(())
+}
+
+pub fn set_run_status(pool: &DbPool, run_id: i32, status: &str, reason: Option<&str>) -> Result<()> {
+    let mut conn = pool.get()?;
+    diesel::update(simulation_runs::table.find(run_id))
+        .set((simulation_runs::status.eq(status), simulation_runs::failure_reason.eq(reason)))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+pub fn set_run_domain(pool: &DbPool, run_id: i32, domain: &Domain) -> Result<()> {
+    let mut conn = pool.get()?;
+    diesel::update(simulation_runs::table.find(run_id))
+        .set((simulation_runs::domain_width.eq(domain.width), simulation_runs::periodic.eq(domain.periodic)))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+// The full parameters as JSON, so the run's geometry and conditions can be rebuilt. Runs
+// that change their geometry (crystal pulling) store the final one with their results.
+pub fn set_run_parameters(pool: &DbPool, run_id: i32, params: &SimParameters) -> Result<()> {
+    let mut conn = pool.get()?;
+    diesel::update(simulation_runs::table.find(run_id))
+        .set(simulation_runs::parameters.eq(serde_json::to_string(params)?))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+// Parameters stored with a run, if it was recorded with them.
+pub fn get_run_parameters(run: &SimulationRun) -> Result<Option<SimParameters>> {
+    Ok(run.parameters.as_deref().map(serde_json::from_str).transpose()?)
+}
+
+// Heat sources as their specs joined by "; ", and the coil with the Joule heating it produced.
+pub fn set_run_heating(
+    pool: &DbPool,
+    run_id: i32,
+    sources: &[HeatSource],
+    induction: Option<&InductionParameters>,
+    joule_power: Option<f64>,
+) -> Result<()> {
+    let mut conn = pool.get()?;
+    let specs: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+    let changes = RunHeating {
+        heat_sources: (!specs.is_empty()).then(|| specs.join("; ")),
+        coil_frequency: induction.map(|i| i.frequency),
+        coil_current: induction.map(|i| i.current),
+        joule_power,
+    };
+    diesel::update(simulation_runs::table.find(run_id)).set(&changes).execute(&mut conn)?;
+    Ok(())
+}
+
+pub fn set_run_gas_flow(pool: &DbPool, run_id: i32, gas: &GasParameters) -> Result<()> {
+    let mut conn = pool.get()?;
+    let changes = RunGasFlow { gas_inflow: gas.inflow, gas_ports: gas.ports() };
+    diesel::update(simulation_runs::table.find(run_id)).set(&changes).execute(&mut conn)?;
+    Ok(())
+}
+
+pub fn set_run_conservation(pool: &DbPool, run_id: i32, balance: &Balance) -> Result<()> {
+    let mut conn = pool.get()?;
+    let changes = RunConservation {
+        heat_imbalance: balance.heat_imbalance,
+        divergence: balance.divergence,
+        poisson_residual: balance.poisson_residual,
+        conservation_flagged: balance.flagged(),
+    };
+    diesel::update(simulation_runs::table.find(run_id)).set(&changes).execute(&mut conn)?;
+    Ok(())
+}
+
 pub fn save_simulation_results(
     pool: &DbPool,
     run_id: i32,
     final_state: &SimState,
 ) -> Result<()> {
     let mut conn = pool.get()?;
+    conn.transaction(|conn| insert_results(conn, run_id, final_state))?;
+    Ok(())
+}
+
+fn insert_results(conn: &mut PgConnection, run_id: i32, final_state: &SimState) -> QueryResult<()> {
     let (ny, nx) = final_state.temp.dim();
     let mut new_points = Vec::new();
 
@@ -61,67 +192,441 @@ pub fn save_simulation_results(
                 temperature: final_state.temp[[i, j]],
                 u_velocity: final_state.u[[i, j]],
                 v_velocity: final_state.v[[i, j]],
+                region: final_state.region[[i, j]].id(),
+                cell_type: final_state.mask[[i, j]].id(),
+                vorticity: Some(final_state.vort[[i, j]]),
+                stream_function: Some(final_state.stream[[i, j]]),
+                concentration: Some(final_state.conc[[i, j]]),
+                gas_vorticity: Some(final_state.gas_vort[[i, j]]),
+                gas_stream_function: Some(final_state.gas_stream[[i, j]]),
             });
         }
     }
 
-    // Use a bulk insert for performance
-    diesel::insert_into(results::table)
-        .values(&new_points)
+    // Bulk insert in chunks: at 13 columns per row a single statement exceeds the bind
+    // parameter limit (65535) beyond about 71x71 nodes. The callers run this in a
+    // transaction, so a failed chunk leaves no partial field behind.
+    for chunk in new_points.chunks(5000) {
+        diesel::insert_into(results::table).values(chunk).execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn save_growth_history(pool: &DbPool, run_id: i32, history: &[GrowthRecord]) -> Result<()> {
+    let mut conn = pool.get()?;
+    conn.transaction(|conn| insert_growth_history(conn, run_id, history))?;
+    Ok(())
+}
+
+fn insert_growth_history(conn: &mut PgConnection, run_id: i32, history: &[GrowthRecord]) -> QueryResult<()> {
+    let records: Vec<NewGrowthRecord> = history
+        .iter()
+        .map(|r| NewGrowthRecord {
+            run_id,
+            step: r.step as i32,
+            sim_time: r.time,
+            crystal_length: r.crystal_length,
+            melt_height: r.melt_height,
+        })
+        .collect();
+
+    // A record every log interval adds up over a long pull
+    for chunk in records.chunks(5000) {
+        diesel::insert_into(growth_history::table).values(chunk).execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn save_diagnostics(pool: &DbPool, run_id: i32, history: &[DiagnosticsSample]) -> Result<()> {
+    let mut conn = pool.get()?;
+    conn.transaction(|conn| insert_diagnostics(conn, run_id, history))?;
+    Ok(())
+}
+
+fn insert_diagnostics(conn: &mut PgConnection, run_id: i32, history: &[DiagnosticsSample]) -> QueryResult<()> {
+    let records: Vec<DiagnosticsRecord> = history
+        .iter()
+        .map(|s| DiagnosticsRecord {
+            run_id,
+            step: s.step as i32,
+            sim_time: s.time,
+            nu_bottom: s.nusselt[0],
+            nu_top: s.nusselt[1],
+            nu_left: s.nusselt[2],
+            nu_right: s.nusselt[3],
+            kinetic_energy: s.kinetic_energy,
+            enstrophy: s.enstrophy,
+            max_stream: s.max_stream,
+            max_u: s.max_u.0,
+            max_u_x: s.max_u.1,
+            max_u_y: s.max_u.2,
+            max_v: s.max_v.0,
+            max_v_x: s.max_v.1,
+            max_v_y: s.max_v.2,
+            heat_in: s.balance.heat_in,
+            heat_out: s.balance.heat_out,
+            storage_rate: s.balance.storage_rate,
+            heat_imbalance: s.balance.heat_imbalance,
+            divergence: s.balance.divergence,
+            poisson_residual: s.balance.poisson_residual,
+            heat_generated: s.balance.heat_generated,
+        })
+        .collect();
+
+    // 22 columns per sample: one statement would pass the bind parameter limit at about
+    // 2978 samples
+    for chunk in records.chunks(2000) {
+        diesel::insert_into(diagnostics::table).values(chunk).execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn get_diagnostics(pool: &DbPool, run_id: i32) -> Result<Vec<DiagnosticsSample>> {
+    let mut conn = pool.get()?;
+    let records = diagnostics::table
+        .filter(diagnostics::run_id.eq(run_id))
+        .order(diagnostics::step.asc())
+        .select(DiagnosticsRecord::as_select())
+        .load(&mut conn)?;
+    Ok(records
+        .into_iter()
+        .map(|r| DiagnosticsSample {
+            step: r.step as usize,
+            time: r.sim_time,
+            nusselt: [r.nu_bottom, r.nu_top, r.nu_left, r.nu_right],
+            kinetic_energy: r.kinetic_energy,
+            enstrophy: r.enstrophy,
+            max_stream: r.max_stream,
+            max_u: (r.max_u, r.max_u_x, r.max_u_y),
+            max_v: (r.max_v, r.max_v_x, r.max_v_y),
+            balance: Balance {
+                heat_in: r.heat_in,
+                heat_out: r.heat_out,
+                heat_generated: r.heat_generated,
+                storage_rate: r.storage_rate,
+                heat_imbalance: r.heat_imbalance,
+                divergence: r.divergence,
+                poisson_residual: r.poisson_residual,
+            },
+        })
+        .collect())
+}
+
+pub fn save_probe_samples(pool: &DbPool, run_id: i32, samples: &[ProbeSample]) -> Result<()> {
+    let mut conn = pool.get()?;
+    conn.transaction(|conn| insert_probe_samples(conn, run_id, samples))?;
+    Ok(())
+}
+
+fn insert_probe_samples(conn: &mut PgConnection, run_id: i32, samples: &[ProbeSample]) -> QueryResult<()> {
+    let records: Vec<ProbeRecord> = samples
+        .iter()
+        .map(|s| ProbeRecord {
+            run_id,
+            probe: s.probe.clone(),
+            step: s.step as i32,
+            sim_time: s.time,
+            x: s.x,
+            y: s.y,
+            temperature: s.temp,
+            u_velocity: s.u,
+            v_velocity: s.v,
+            vorticity: s.vort,
+        })
+        .collect();
+
+    // Long runs with many line points exceed the bind parameter limit in one statement
+    for chunk in records.chunks(5000) {
+        diesel::insert_into(probe_samples::table).values(chunk).execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn get_probe_samples(pool: &DbPool, run_id: i32) -> Result<Vec<ProbeSample>> {
+    let mut conn = pool.get()?;
+    let records = probe_samples::table
+        .filter(probe_samples::run_id.eq(run_id))
+        .order((probe_samples::step.asc(), probe_samples::id.asc()))
+        .select(ProbeRecord::as_select())
+        .load(&mut conn)?;
+    Ok(records
+        .into_iter()
+        .map(|r| ProbeSample {
+            probe: r.probe,
+            step: r.step as usize,
+            time: r.sim_time,
+            x: r.x,
+            y: r.y,
+            temp: r.temperature,
+            u: r.u_velocity,
+            v: r.v_velocity,
+            vort: r.vorticity,
+        })
+        .collect())
+}
+
+// Everything a completed run stores with its final state.
+pub struct CompletedRun<'a> {
+    pub params: &'a SimParameters,
+    pub state: &'a SimState,
+    pub time: f64,
+    pub growth: Option<&'a [GrowthRecord]>,
+    pub diagnostics: Option<&'a [DiagnosticsSample]>,
+    pub probes: Option<&'a [ProbeSample]>,
+    pub balance: &'a Balance,
+}
+
+// Save a completed run and mark it so in one transaction: a save that fails part way
+// leaves the run as it was, with none of its data, rather than half of it.
+pub fn save_completed_run(pool: &DbPool, run_id: i32, run: &CompletedRun) -> Result<()> {
+    let mut conn = pool.get()?;
+    let parameters = serde_json::to_string(run.params)?;
+    let conservation = RunConservation {
+        heat_imbalance: run.balance.heat_imbalance,
+        divergence: run.balance.divergence,
+        poisson_residual: run.balance.poisson_residual,
+        conservation_flagged: run.balance.flagged(),
+    };
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::update(simulation_runs::table.find(run_id)).set(simulation_runs::parameters.eq(parameters)).execute(conn)?;
+        insert_results(conn, run_id, run.state)?;
+        if let Some(history) = run.growth {
+            insert_growth_history(conn, run_id, history)?;
+        }
+        if let Some(history) = run.diagnostics {
+            insert_diagnostics(conn, run_id, history)?;
+        }
+        if let Some(samples) = run.probes {
+            insert_probe_samples(conn, run_id, samples)?;
+        }
+        diesel::update(simulation_runs::table.find(run_id)).set(&conservation).execute(conn)?;
+        diesel::update(simulation_runs::table.find(run_id))
+            .set((
+                simulation_runs::status.eq("completed"),
+                simulation_runs::failure_reason.eq(None::<&str>),
+                simulation_runs::final_time.eq(run.time),
+            ))
+            .execute(conn)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// Store the branch of a continuation run as its bifurcation diagram.
+pub fn save_continuation_points(pool: &DbPool, run_id: i32, parameter: &str, points: &[BranchPoint]) -> Result<()> {
+    let mut conn = pool.get()?;
+    let records: Vec<NewContinuationPoint> = points
+        .iter()
+        .map(|p| NewContinuationPoint {
+            run_id,
+            point_index: p.index as i32,
+            parameter,
+            parameter_value: p.parameter,
+            nusselt: p.nusselt,
+            max_stream: p.max_stream,
+            arclength: p.arclength,
+            growth_rate: p.growth_rate,
+            frequency: p.frequency,
+            kind: p.kind.as_str(),
+        })
+        .collect();
+
+    // A long branch exceeds the bind parameter limit in one statement; the chunks go in
+    // together or not at all
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        for chunk in records.chunks(5000) {
+            diesel::insert_into(continuation_points::table).values(chunk).execute(conn)?;
+        }
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+pub fn create_ensemble(
+    pool: &DbPool,
+    desc: &str,
+    method: Sampling,
+    members: usize,
+    seed: u64,
+    inputs: &[UncertainInput],
+    time_steps: usize,
+) -> Result<Ensemble> {
+    let mut conn = pool.get()?;
+    let inputs: Vec<String> = inputs.iter().map(|i| i.to_string()).collect();
+    let new_ensemble = NewEnsemble {
+        description: desc,
+        method: method.name(),
+        members: members as i32,
+        seed: seed as i64,
+        inputs: &inputs.join(" "),
+        time_steps: time_steps as i32,
+    };
+    let ensemble = diesel::insert_into(ensembles::table).values(&new_ensemble).get_result(&mut conn)?;
+    Ok(ensemble)
+}
+
+// Group a run into an ensemble with the input values it was given.
+pub fn set_run_ensemble_member(pool: &DbPool, run_id: i32, ensemble_id: i32, sample: &str) -> Result<()> {
+    let mut conn = pool.get()?;
+    let changes = RunEnsembleMember { ensemble_id, ensemble_sample: sample.to_string() };
+    diesel::update(simulation_runs::table.find(run_id)).set(&changes).execute(&mut conn)?;
+    Ok(())
+}
+
+// Statistics of the scalar outputs over the `completed` members of an ensemble, with the
+// number of members that blew up or were rejected and are left out.
+pub fn save_ensemble_statistics(pool: &DbPool, ensemble_id: i32, completed: usize, failed: usize, stats: &[(&str, SummaryStats)]) -> Result<()> {
+    let mut conn = pool.get()?;
+    let records: Vec<NewEnsembleStatistic> = stats
+        .iter()
+        .map(|(output, s)| NewEnsembleStatistic {
+            ensemble_id,
+            output,
+            completed: completed as i32,
+            failed: failed as i32,
+            mean: s.mean,
+            std: s.std,
+            p05: s.p05,
+            p50: s.p50,
+            p95: s.p95,
+        })
+        .collect();
+
+    diesel::insert_into(ensemble_statistics::table)
+        .values(&records)
         .execute(&mut conn)?;
 
     Ok(())
 }
 
-pub fn list_simulation_runs(pool: &DbPool) -> Result<()> {
-    use crate::schema::simulation_runs::dsl::*;
+// Node-wise statistics of T, ψ, u and v, one row per node and statistic.
+pub fn save_ensemble_fields(pool: &DbPool, ensemble_id: i32, fields: &[FieldStatistics; 4]) -> Result<()> {
     let mut conn = pool.get()?;
-    let runs = simulation_runs.load::<SimulationRun>(&mut conn)?;
+    let [temp, stream, u, v] = fields;
+    let (ny, nx) = temp.mean.dim();
+    let mut new_points = Vec::new();
 
-    println!("--- Available Simulation Runs ---");
-    println!(
-        "{:<5} | {:<25} | {:<10} | {:<10} | {:<10} | {:<10}",
-        "ID", "Description", "Grid", "Steps", "Pr", "Ra"
-    );
-    println!("{}", "-".repeat(85));
-    for run in runs {
-        println!(
-            "{:<5} | {:<25} | {:<10} | {:<10} | {:<10.2} | {:<10.1e}",
-            run.id, run.description, run.grid_size, run.time_steps, run.prandtl_number, run.rayleigh_number
-        );
+    for statistic in FIELD_STATISTICS {
+        let (t, psi, uu, vv) = (temp.get(statistic)?, stream.get(statistic)?, u.get(statistic)?, v.get(statistic)?);
+        for i in 0..ny {
+            for j in 0..nx {
+                new_points.push(NewEnsembleFieldPoint {
+                    ensemble_id,
+                    statistic,
+                    x: j as i32,
+                    y: i as i32,
+                    temperature: t[[i, j]],
+                    stream_function: psi[[i, j]],
+                    u_velocity: uu[[i, j]],
+                    v_velocity: vv[[i, j]],
+                });
+            }
+        }
     }
+
+    // Five statistics of a fine grid exceed the bind parameter limit in one statement;
+    // the chunks go in together or not at all
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        for chunk in new_points.chunks(5000) {
+            diesel::insert_into(ensemble_fields::table).values(chunk).execute(conn)?;
+        }
+        Ok(())
+    })?;
+
     Ok(())
 }
 
+pub fn load_simulation_runs(pool: &DbPool) -> Result<Vec<SimulationRun>> {
+    let mut conn = pool.get()?;
+    let runs = simulation_runs::table.order(simulation_runs::id.asc()).load::<SimulationRun>(&mut conn)?;
+    Ok(runs)
+}
+
+pub fn get_simulation_run(pool: &DbPool, run_id: i32) -> Result<SimulationRun> {
+    let mut conn = pool.get()?;
+    let run = simulation_runs::table.find(run_id).first::<SimulationRun>(&mut conn)?;
+    Ok(run)
+}
+
+// A run with the chain of parent runs it was restarted from, oldest first, and the runs
+// restarted from it.
+pub struct RunLineage {
+    pub ancestors: Vec<SimulationRun>,
+    pub run: SimulationRun,
+    pub children: Vec<SimulationRun>,
+}
+
+pub fn get_run_lineage(pool: &DbPool, run_id: i32) -> Result<RunLineage> {
+    let mut conn = pool.get()?;
+
+    let run = simulation_runs::table.find(run_id).first::<SimulationRun>(&mut conn)?;
+    let mut ancestors = Vec::new();
+    let mut parent_id = run.parent_run_id;
+    while let Some(id) = parent_id {
+        let parent = simulation_runs::table.find(id).first::<SimulationRun>(&mut conn)?;
+        parent_id = parent.parent_run_id;
+        ancestors.push(parent);
+        if ancestors.len() > 10_000 {
+            anyhow::bail!("lineage of run {} does not terminate", run_id);
+        }
+    }
+    ancestors.reverse();
+
+    let children = simulation_runs::table
+        .filter(simulation_runs::parent_run_id.eq(run_id))
+        .order(simulation_runs::id.asc())
+        .load::<SimulationRun>(&mut conn)?;
+    Ok(RunLineage { ancestors, run, children })
+}
 
 pub fn get_simulation_results(pool: &DbPool, run_id_to_get: i32) -> Result<SimState> {
     use crate::schema::results::dsl::*;
-    use crate::schema::simulation_runs::dsl::{grid_size, simulation_runs};
+    use crate::schema::simulation_runs::dsl::{domain_width, grid_size, periodic, simulation_runs};
 
     let mut conn = pool.get()?;
 
-    // First get grid size for the run
-    let size = simulation_runs
+    // First get grid size and domain for the run
+    let (size, width, is_periodic) = simulation_runs
         .find(run_id_to_get)
-        .select(grid_size)
-        .first::<i32>(&mut conn)?;
+        .select((grid_size, domain_width, periodic))
+        .first::<(i32, f64, bool)>(&mut conn)?;
     
-    let (nx, ny) = (size as usize, size as usize);
+    let ny = size as usize;
+    let domain = Domain { width, periodic: is_periodic };
+    let nx = domain.nx(ny);
     let mut state = SimState::new(nx, ny);
 
     // Get all result points for the run
     let points = results
         .filter(run_id.eq(run_id_to_get))
-        .load::<(i64, i32, i32, i32, f64, f64, f64)>(&mut conn)?;
+        .select((x, y, temperature, u_velocity, v_velocity, region, cell_type, vorticity, stream_function, concentration, gas_vorticity, gas_stream_function))
+        .load::<(i32, i32, f64, f64, f64, i16, i16, Option<f64>, Option<f64>, Option<f64>, Option<f64>, Option<f64>)>(&mut conn)?;
 
+    let mut has_vorticity = true;
     for point in points {
-        let (_id, _run_id, x, y, temp, u_vel, v_vel) = point;
-        if x < nx as i32 && y < ny as i32 {
-            state.temp[[y as usize, x as usize]] = temp;
-            state.u[[y as usize, x as usize]] = u_vel;
-            state.v[[y as usize, x as usize]] = v_vel;
+        let (px, py, temp, u_vel, v_vel, region_id, cell_type_id, vort, psi, conc, gas_omega, gas_psi) = point;
+        if px < nx as i32 && py < ny as i32 {
+            let idx = [py as usize, px as usize];
+            state.temp[idx] = temp;
+            state.u[idx] = u_vel;
+            state.v[idx] = v_vel;
+            state.region[idx] = Region::from_id(region_id).unwrap_or(Region::Melt);
+            state.mask[idx] = CellType::from_id(cell_type_id).unwrap_or(CellType::Fluid);
+            state.vort[idx] = vort.unwrap_or(0.0);
+            state.stream[idx] = psi.unwrap_or(0.0);
+            state.conc[idx] = conc.unwrap_or(0.0);
+            state.gas_vort[idx] = gas_omega.unwrap_or(0.0);
+            state.gas_stream[idx] = gas_psi.unwrap_or(0.0);
+            has_vorticity &= vort.is_some();
         }
     }
 
+    // Runs stored before vorticity was saved: rebuild it from the velocity field.
+    // The stream function is then recovered by the Poisson iterations of the next steps.
+    if !has_vorticity {
+        state.vort = state.vorticity_from_velocity(&domain);
+    }
+
     Ok(state)
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::Wall;
+use crate::conservation::Balance;
+use crate::simulation::{SimParameters, SimState};
+
+pub const WALLS: [Wall; 4] = [Wall::Bottom, Wall::Top, Wall::Left, Wall::Right];
+
+// Local Nusselt number along a wall, in the direction of increasing x or y: the
+// conductive heat flux into the domain scaled by ΔT / L, from a second order one-sided
+// difference of the temperature normal to the wall. Positive where the wall is hotter
+// than the fluid next to it. With periodic x the top and bottom profiles skip the ghost
+// columns, and no heat crosses the periodic sides, which are reported as zero.
+pub fn local_nusselt(temp: &Array2<f64>, wall: Wall, params: &SimParameters) -> Vec<f64> {
+    let (ny, nx) = temp.dim();
+    let (dx, dy) = params.spacing();
+    let periodic = params.domain.periodic;
+
+    // Nodes along the wall and spacing normal to it; node(s, k) is k nodes in from position s
+    let (along, h) = match wall {
+        Wall::Bottom | Wall::Top if periodic => (1..nx - 1, dy),
+        Wall::Bottom | Wall::Top => (0..nx, dy),
+        Wall::Left | Wall::Right if periodic => return vec![0.0; ny],
+        Wall::Left | Wall::Right => (0..ny, dx),
+    };
+    let node = |s: usize, k: usize| match wall {
+        Wall::Bottom => temp[[k, s]],
+        Wall::Top => temp[[ny - 1 - k, s]],
+        Wall::Left => temp[[s, k]],
+        Wall::Right => temp[[s, nx - 1 - k]],
+    };
+    along.map(|s| (3.0 * node(s, 0) - 4.0 * node(s, 1) + node(s, 2)) / (2.0 * h)).collect()
+}
+
+// Mean Nusselt number of a wall: the local Nusselt number averaged along the wall with
+// the trapezoidal rule, or the rectangle rule over the interior columns with periodic x.
+pub fn wall_nusselt(temp: &Array2<f64>, wall: Wall, params: &SimParameters) -> f64 {
+    let flux = local_nusselt(temp, wall, params);
+    let len = flux.len();
+    if params.domain.periodic {
+        return flux.iter().sum::<f64>() / len as f64;
+    }
+    let interior: f64 = flux[1..len - 1].iter().sum();
+    (interior + 0.5 * (flux[0] + flux[len - 1])) / (len as f64 - 1.0)
+}
+
+// Global flow and heat transfer measures of one state.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsSample {
+    pub step: usize,
+    pub time: f64,
+    pub nusselt: [f64; 4],      // Mean Nu of the bottom, top, left and right walls
+    pub kinetic_energy: f64,    // ½ ∫ (u² + v²) dA
+    pub enstrophy: f64,         // ½ ∫ ω² dA
+    pub max_stream: f64,        // max |ψ|
+    pub max_u: (f64, f64, f64), // Largest |u| and its (x, y)
+    pub max_v: (f64, f64, f64),
+    #[serde(default)]
+    pub balance: Balance,       // Conservation errors of the step that ended here
+}
+
+// Samples taken every `interval` steps of `Simulation::run`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DiagnosticsLog {
+    pub interval: usize,
+    pub history: Vec<DiagnosticsSample>,
+}
+
+// Trapezoidal rule over the whole grid; with periodic x the rectangle rule over the
+// interior columns, which is the trapezoidal rule of the periodic field.
+fn integrate(field: impl Fn(usize, usize) -> f64, ny: usize, nx: usize, params: &SimParameters) -> f64 {
+    let (dx, dy) = params.spacing();
+    let weight = |k: usize, n: usize| if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+    let column_weight = |j: usize| match params.domain.periodic {
+        true if j == 0 || j == nx - 1 => 0.0,
+        true => 1.0,
+        false => weight(j, nx),
+    };
+    let mut sum = 0.0;
+    for i in 0..ny {
+        for j in 0..nx {
+            sum += weight(i, ny) * column_weight(j) * field(i, j);
+        }
+    }
+    sum * dx * dy
+}
+
+// Largest |value| of a field and its position, skipping the ghost columns of a periodic grid.
+fn largest(field: &Array2<f64>, params: &SimParameters) -> (f64, f64, f64) {
+    let (dx, dy) = params.spacing();
+    let (nx, periodic) = (field.ncols(), params.domain.periodic);
+    let first = if periodic { 1 } else { 0 };
+    field
+        .indexed_iter()
+        .filter(|((_, j), _)| !periodic || (*j > 0 && *j < nx - 1))
+        .fold((0.0, 0.0, 0.0), |best, ((i, j), v)| if v.abs() > best.0 { (v.abs(), (j - first) as f64 * dx, i as f64 * dy) } else { best })
+}
+
+pub fn sample(state: &SimState, params: &SimParameters, step: usize, time: f64) -> DiagnosticsSample {
+    let (ny, nx) = state.temp.dim();
+    DiagnosticsSample {
+        step,
+        time,
+        nusselt: WALLS.map(|wall| wall_nusselt(&state.temp, wall, params)),
+        kinetic_energy: 0.5 * integrate(|i, j| state.u[[i, j]].powi(2) + state.v[[i, j]].powi(2), ny, nx, params),
+        enstrophy: 0.5 * integrate(|i, j| state.vort[[i, j]].powi(2), ny, nx, params),
+        max_stream: state.stream.iter().fold(0.0f64, |m, v| m.max(v.abs())),
+        max_u: largest(&state.u, params),
+        max_v: largest(&state.v, params),
+        balance: Balance::default(),
+    }
+}
+
+pub fn print_history(history: &[DiagnosticsSample]) {
+    println!(
+        "{:<8} | {:<10} | {:<9} | {:<9} | {:<9} | {:<9} | {:<10} | {:<10} | {:<9} | {:<20} | max |v| (x, y)",
+        "Step", "Time", "Nu bottom", "Nu top", "Nu left", "Nu right", "KE", "Enstrophy", "|psi|max", "max |u| (x, y)"
+    );
+    println!("{}", "-".repeat(150));
+    for s in history {
+        println!(
+            "{:<8} | {:<10.4e} | {:<9.4} | {:<9.4} | {:<9.4} | {:<9.4} | {:<10.4e} | {:<10.4e} | {:<9.4} | {:<8.3} ({:.3}, {:.3}) | {:<8.3} ({:.3}, {:.3})",
+            s.step,
+            s.time,
+            s.nusselt[0],
+            s.nusselt[1],
+            s.nusselt[2],
+            s.nusselt[3],
+            s.kinetic_energy,
+            s.enstrophy,
+            s.max_stream,
+            s.max_u.0,
+            s.max_u.1,
+            s.max_u.2,
+            s.max_v.0,
+            s.max_v.1,
+            s.max_v.2
+        );
+    }
+
+    println!();
+    println!("{:<8} | {:<10} | {:<10} | {:<10} | {:<10} | {:<10} | {:<10} | {:<10} | Poisson", "Step", "Time", "Q in", "Q out", "Q gen", "dE/dt", "Heat err", "Div err");
+    println!("{}", "-".repeat(107));
+    for s in history {
+        let b = &s.balance;
+        println!(
+            "{:<8} | {:<10.4e} | {:<10.4e} | {:<10.4e} | {:<10.4e} | {:<10.3e} | {:<10.3e} | {:<10.3e} | {:.3e}{}",
+            s.step,
+            s.time,
+            b.heat_in,
+            b.heat_out,
+            b.heat_generated,
+            b.storage_rate,
+            b.heat_imbalance,
+            b.divergence,
+            b.poisson_residual,
+            if b.flagged() { "  !" } else { "" }
+        );
+    }
+}
@@ -0,0 +1,332 @@
+#![allow(clippy::missing_safety_doc)]
+
+use ndarray::Array2;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::boundary::{BoundarySegment, ThermalBc, Wall};
+use crate::simulation::{SimParameters, SimState, Simulation};
+use crate::solver::Solver;
+
+// C interface for embedding the solver, e.g. in a furnace controller test bench. The
+// header is committed as include/cz_cfd_simulator.h and checked against the one the
+// build script generates with the `capi` feature. Every function returns a `CzStatus`;
+// on failure `cz_last_error` gives the message. Panics are caught at the boundary and
+// reported as `CZ_STATUS_PANIC`.
+//
+// Fields are exchanged as row-major ny × nx buffers of doubles, node (i, j) at i·nx + j
+// with i along y from the bottom wall.
+//
+// Safety contract of every function: handles come from cz_simulation_new* and are not
+// used after cz_simulation_free, output pointers are valid or null where allowed,
+// strings are NUL-terminated and buffers valid for `len` doubles. Handles must not be
+// shared between threads without a lock.
+
+// Result codes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CzStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidArgument = 2, // Unknown field or wall, bad parameters or a read-only field
+    BufferSize = 3,      // The buffer length does not match the field or wall
+    BlewUp = 4,          // The watchdog stopped the run; the state is that of the failed step
+    Panic = 5,
+}
+
+// Field selectors for cz_simulation_read_field / cz_simulation_write_field.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CzField {
+    Temperature = 0,
+    Vorticity = 1,
+    StreamFunction = 2,
+    VelocityX = 3, // Read only, derived from ψ every step
+    VelocityY = 4, // Read only
+    Concentration = 5,
+    GasVorticity = 6,     // Read only
+    GasStreamFunction = 7, // Read only
+}
+
+// Wall selectors, as in boundary::Wall.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CzWall {
+    Bottom = 0,
+    Top = 1,
+    Left = 2,
+    Right = 3,
+}
+
+// Opaque handle to a simulation.
+pub struct CzSimulation {
+    sim: Simulation,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_error(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+}
+
+// Run `body`, turning errors and panics into status codes and the last error message.
+fn guard(body: impl FnOnce() -> Result<(), (CzStatus, String)>) -> CzStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => CzStatus::Ok,
+        Ok(Err((status, message))) => {
+            set_error(message);
+            status
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_error(format!("panic: {}", message));
+            CzStatus::Panic
+        }
+    }
+}
+
+fn invalid(message: impl Into<String>) -> (CzStatus, String) {
+    (CzStatus::InvalidArgument, message.into())
+}
+
+unsafe fn handle<'a>(sim: *const CzSimulation) -> Result<&'a CzSimulation, (CzStatus, String)> {
+    sim.as_ref().ok_or((CzStatus::NullPointer, "null simulation handle".to_string()))
+}
+
+unsafe fn handle_mut<'a>(sim: *mut CzSimulation) -> Result<&'a mut CzSimulation, (CzStatus, String)> {
+    sim.as_mut().ok_or((CzStatus::NullPointer, "null simulation handle".to_string()))
+}
+
+fn wall(id: u32) -> Result<Wall, (CzStatus, String)> {
+    match id {
+        0 => Ok(Wall::Bottom),
+        1 => Ok(Wall::Top),
+        2 => Ok(Wall::Left),
+        3 => Ok(Wall::Right),
+        _ => Err(invalid(format!("unknown wall {}", id))),
+    }
+}
+
+fn field_id(id: u32) -> Result<CzField, (CzStatus, String)> {
+    const FIELDS: [CzField; 8] = [
+        CzField::Temperature,
+        CzField::Vorticity,
+        CzField::StreamFunction,
+        CzField::VelocityX,
+        CzField::VelocityY,
+        CzField::Concentration,
+        CzField::GasVorticity,
+        CzField::GasStreamFunction,
+    ];
+    FIELDS.get(id as usize).copied().ok_or_else(|| invalid(format!("unknown field {}", id)))
+}
+
+fn field(state: &SimState, field: CzField) -> &Array2<f64> {
+    match field {
+        CzField::Temperature => &state.temp,
+        CzField::Vorticity => &state.vort,
+        CzField::StreamFunction => &state.stream,
+        CzField::VelocityX => &state.u,
+        CzField::VelocityY => &state.v,
+        CzField::Concentration => &state.conc,
+        CzField::GasVorticity => &state.gas_vort,
+        CzField::GasStreamFunction => &state.gas_stream,
+    }
+}
+
+// The transported and solved fields; the others are derived from them every step.
+fn field_mut(state: &mut SimState, field: CzField) -> Option<&mut Array2<f64>> {
+    match field {
+        CzField::Temperature => Some(&mut state.temp),
+        CzField::Vorticity => Some(&mut state.vort),
+        CzField::StreamFunction => Some(&mut state.stream),
+        CzField::Concentration => Some(&mut state.conc),
+        _ => None,
+    }
+}
+
+fn publish(sim: Simulation, out: *mut *mut CzSimulation) -> Result<(), (CzStatus, String)> {
+    if out.is_null() {
+        return Err((CzStatus::NullPointer, "null output pointer".to_string()));
+    }
+    unsafe { *out = Box::into_raw(Box::new(CzSimulation { sim })) };
+    Ok(())
+}
+
+// Message of the last failed call on this thread, valid until the next failure.
+#[no_mangle]
+pub extern "C" fn cz_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+// The checks of `SimParameters::validate`, the ones the Python classes make too.
+fn check_parameters(params: &SimParameters) -> Result<(), (CzStatus, String)> {
+    params.validate().map_err(|e| invalid(e.to_string()))
+}
+
+// Create a simulation from the JSON form of SimParameters, as stored in checkpoints.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_new(params_json: *const c_char, out: *mut *mut CzSimulation) -> CzStatus {
+    guard(|| {
+        if params_json.is_null() {
+            return Err((CzStatus::NullPointer, "null parameter string".to_string()));
+        }
+        let json = CStr::from_ptr(params_json).to_str().map_err(|e| invalid(e.to_string()))?;
+        let params: SimParameters = serde_json::from_str(json).map_err(|e| invalid(format!("bad parameters: {}", e)))?;
+        check_parameters(&params)?;
+        let sim = Simulation::new(params);
+        sim.check_geometry().map_err(|e| invalid(e.to_string()))?;
+        publish(sim, out)
+    })
+}
+
+// Create a unit cavity with the default wall temperatures: hot bottom and sides, cold top.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_new_cavity(grid_size: usize, ra: f64, pr: f64, dt: f64, out: *mut *mut CzSimulation) -> CzStatus {
+    guard(|| {
+        if grid_size < 3 {
+            return Err(invalid("need grid_size >= 3"));
+        }
+        let params = SimParameters { dt, ..SimParameters::cavity(grid_size, pr, ra) };
+        check_parameters(&params)?;
+        publish(Simulation::new(params), out)
+    })
+}
+
+// Destroy a simulation; null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_free(sim: *mut CzSimulation) {
+    if !sim.is_null() {
+        drop(Box::from_raw(sim));
+    }
+}
+
+// Advance `steps` time steps with the watchdog checks of the run loop.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_advance(sim: *mut CzSimulation, steps: usize) -> CzStatus {
+    guard(|| {
+        match handle_mut(sim)?.sim.advance_watched(steps) {
+            Some(reason) => Err((CzStatus::BlewUp, reason)),
+            None => Ok(()),
+        }
+    })
+}
+
+// Grid size, elapsed time and steps taken; any output pointer may be null.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_info(sim: *const CzSimulation, nx: *mut usize, ny: *mut usize, time: *mut f64, steps: *mut usize) -> CzStatus {
+    guard(|| {
+        let sim = &handle(sim)?.sim;
+        let (rows, columns) = sim.state.temp.dim();
+        for (out, value) in [(nx, columns), (ny, rows), (steps, sim.steps_taken)] {
+            if let Some(out) = out.as_mut() {
+                *out = value;
+            }
+        }
+        if let Some(time) = time.as_mut() {
+            *time = sim.time;
+        }
+        Ok(())
+    })
+}
+
+// Copy a field (a CzField value) into `buffer` of `len` = nx·ny doubles.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_read_field(sim: *const CzSimulation, field_id: u32, buffer: *mut f64, len: usize) -> CzStatus {
+    guard(|| {
+        let values = field(&handle(sim)?.sim.state, self::field_id(field_id)?);
+        if buffer.is_null() {
+            return Err((CzStatus::NullPointer, "null buffer".to_string()));
+        }
+        if len != values.len() {
+            return Err((CzStatus::BufferSize, format!("field has {} values, buffer {}", values.len(), len)));
+        }
+        let out = std::slice::from_raw_parts_mut(buffer, len);
+        for (o, v) in out.iter_mut().zip(values.iter()) {
+            *o = *v;
+        }
+        Ok(())
+    })
+}
+
+// Overwrite a field from `buffer` of `len` = nx·ny doubles. The wall conditions are
+// imposed again afterwards, so wall temperatures stay those of the boundary conditions.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_write_field(sim: *mut CzSimulation, field_id: u32, buffer: *const f64, len: usize) -> CzStatus {
+    guard(|| {
+        let sim = &mut handle_mut(sim)?.sim;
+        let values = field_mut(&mut sim.state, self::field_id(field_id)?)
+            .ok_or_else(|| invalid(format!("field {} is derived and read only", field_id)))?;
+        if buffer.is_null() {
+            return Err((CzStatus::NullPointer, "null buffer".to_string()));
+        }
+        if len != values.len() {
+            return Err((CzStatus::BufferSize, format!("field has {} values, buffer {}", values.len(), len)));
+        }
+        let input = std::slice::from_raw_parts(buffer, len);
+        if let Some(bad) = input.iter().find(|v| !v.is_finite()) {
+            return Err(invalid(format!("non-finite value {}", bad)));
+        }
+        for (v, i) in values.iter_mut().zip(input) {
+            *v = *i;
+        }
+        sim.apply_wall_conditions();
+        Ok(())
+    })
+}
+
+// Copy the temperatures along a wall (a CzWall value), corners included, into `buffer`
+// of `len` = nx (bottom, top) or ny (left, right) doubles.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_read_wall(sim: *const CzSimulation, wall_id: u32, buffer: *mut f64, len: usize) -> CzStatus {
+    guard(|| {
+        let temp = &handle(sim)?.sim.state.temp;
+        let (ny, nx) = temp.dim();
+        let values = match wall(wall_id)? {
+            Wall::Bottom => temp.row(0),
+            Wall::Top => temp.row(ny - 1),
+            Wall::Left => temp.column(0),
+            Wall::Right => temp.column(nx - 1),
+        };
+        if buffer.is_null() {
+            return Err((CzStatus::NullPointer, "null buffer".to_string()));
+        }
+        if len != values.len() {
+            return Err((CzStatus::BufferSize, format!("wall has {} nodes, buffer {}", values.len(), len)));
+        }
+        let out = std::slice::from_raw_parts_mut(buffer, len);
+        for (o, v) in out.iter_mut().zip(values.iter()) {
+            *o = *v;
+        }
+        Ok(())
+    })
+}
+
+// Prescribe temperature `value` on the wall stretch between the fractions `start` and
+// `end` of its length, e.g. a heater setpoint. Setting the same stretch again replaces
+// its value instead of stacking conditions.
+#[no_mangle]
+pub unsafe extern "C" fn cz_simulation_set_wall_temperature(sim: *mut CzSimulation, wall_id: u32, start: f64, end: f64, value: f64) -> CzStatus {
+    guard(|| {
+        let sim = &mut handle_mut(sim)?.sim;
+        let wall = wall(wall_id)?;
+        if !(0.0 <= start && start < end && end <= 1.0 && value.is_finite()) {
+            return Err(invalid("need 0 <= start < end <= 1 and a finite temperature"));
+        }
+        let segment = BoundarySegment { wall, start, end, bc: ThermalBc::Fixed(value) };
+        // The latest setpoint of a stretch wins over the other segments
+        let segments = &mut sim.params.thermal.segments;
+        segments.retain(|s| !(s.wall == wall && s.start == start && s.end == end && matches!(s.bc, ThermalBc::Fixed(_))));
+        segments.push(segment);
+        sim.apply_wall_conditions();
+        Ok(())
+    })
+}
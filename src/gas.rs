@@ -0,0 +1,437 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::boundary::Wall;
+use crate::regions::Region;
+use crate::simulation::SimState;
+
+// A stretch of outer wall the purge gas enters or leaves through, between the fractions
+// `start` and `end` of the wall (along +x for bottom/top, +y for left/right).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasPort {
+    pub wall: Wall,
+    pub start: f64,
+    pub end: f64,
+}
+
+impl GasPort {
+    fn covers(&self, wall: Wall, s: f64) -> bool {
+        self.wall == wall && s >= self.start - 1e-12 && s <= self.end + 1e-12
+    }
+}
+
+// `wall:start:end`
+impl FromStr for GasPort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let [wall, start, end] = parts.as_slice() else {
+            anyhow::bail!("expected wall:start:end, got '{}'", s);
+        };
+        let port = GasPort { wall: wall.parse()?, start: start.parse()?, end: end.parse()? };
+        anyhow::ensure!(0.0 <= port.start && port.start < port.end && port.end <= 1.0, "port '{}' must satisfy 0 <= start < end <= 1", s);
+        Ok(port)
+    }
+}
+
+impl fmt::Display for GasPort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let wall = match self.wall {
+            Wall::Bottom => "bottom",
+            Wall::Top => "top",
+            Wall::Left => "left",
+            Wall::Right => "right",
+        };
+        write!(f, "{}:{}:{}", wall, self.start, self.end)
+    }
+}
+
+// Purge gas in the gas region, in the melt's scales (length H, time H²/α_melt, velocity
+// α_melt/H). Its vorticity diffuses with ν_g/α_melt and its buoyancy is the melt's
+// Ra Pr scaled by β_g/β_melt. The defaults are for argon at 1 atm over liquid silicon.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasParameters {
+    pub viscosity: f64,       // ν_g / α_melt
+    pub viscosity_ratio: f64, // μ_g / μ_melt, transmits the shear stress at the free surface
+    pub expansion_ratio: f64, // β_g / β_melt
+    pub inlets: Vec<GasPort>,
+    pub outlets: Vec<GasPort>,
+    pub inflow: f64, // Normal speed at the inlets
+    pub inlet_temperature: f64,
+}
+
+impl GasParameters {
+    pub fn new(inlets: Vec<GasPort>, outlets: Vec<GasPort>, inflow: f64, inlet_temperature: f64, viscosity: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(viscosity > 0.0, "the gas viscosity must be positive");
+        anyhow::ensure!(inlets.is_empty() == outlets.is_empty(), "purge gas needs both inlets and outlets, or neither");
+        Ok(GasParameters { viscosity, viscosity_ratio: 0.08, expansion_ratio: 4.2, inlets, outlets, inflow, inlet_temperature })
+    }
+
+    // Ports as `in <port>, ..., out <port>, ...` for the run record.
+    pub fn ports(&self) -> String {
+        let inlets = self.inlets.iter().map(|p| format!("in {}", p));
+        let outlets = self.outlets.iter().map(|p| format!("out {}", p));
+        inlets.chain(outlets).collect::<Vec<_>>().join(", ")
+    }
+}
+
+// Role of a node in the gas flow solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasNode {
+    Outside, // Not gas
+    Fluid,
+    Wall,    // Gas node on a no-slip wall: the outer frame or next to a solid
+    Surface, // Gas node on the melt's free surface, moving with the melt
+    Inlet,
+    Outlet,
+}
+
+// Upwind u·∇f at an interior node.
+pub fn upwind_advection(f: &Array2<f64>, u: f64, v: f64, i: usize, j: usize, dx: f64, dy: f64) -> f64 {
+    let adv_x = if u > 0.0 { u * (f[[i, j]] - f[[i, j - 1]]) / dx } else { u * (f[[i, j + 1]] - f[[i, j]]) / dx };
+    let adv_y = if v > 0.0 { v * (f[[i, j]] - f[[i - 1, j]]) / dy } else { v * (f[[i + 1, j]] - f[[i, j]]) / dy };
+    adv_x + adv_y
+}
+
+// Vorticity–stream function flow of the gas region, with its own ω and ψ in `SimState`
+// and its velocities written into the state's u and v at the gas nodes. The walls of
+// the gas flow are the gas nodes next to solids and the melt, so at the free surface
+// both flows share the row of nodes above the melt:
+//   the gas sees a wall moving with the melt surface velocity, from the melt's ψ and ω;
+//   the melt sees the gas shear stress, μ_melt ∂u/∂y = μ_g ∂u_g/∂y, as its surface
+//   vorticity ω = (μ_g / μ_melt) ω_g.
+// Heat crosses the free surface by conduction in the shared temperature field, so flux
+// and temperature are continuous there; at gas fluid nodes the gas velocity advects it.
+//
+// ψ on the outer frame follows from the port velocities, integrated counter-clockwise
+// from ψ = 0 in the bottom-left corner. Every solid body takes the ψ of the frame where it
+// touches it, or 0 if it does not; the outflow of each connected gas region is matched to
+// its inflow so that ψ is single valued. Outlets have a uniform normal velocity.
+pub struct GasFlow {
+    pub params: GasParameters,
+    pub nodes: Array2<GasNode>,
+    wall_stream: Array2<f64>, // ψ on the gas walls and ports
+    port_speed: Array2<f64>,  // Inward normal speed at the ports, negative at outlets
+    pub unbalanced: usize,    // Gas regions with inlets but no outlet, which cannot carry flow
+    dx: f64,
+    dy: f64,
+}
+
+impl GasFlow {
+    pub fn new(params: &GasParameters, region: &Array2<Region>, spacing: (f64, f64)) -> Self {
+        let (ny, nx) = region.dim();
+        let (dx, dy) = spacing;
+        let gas = |i: usize, j: usize| region[[i, j]] == Region::Gas;
+        let frame = |i: usize, j: usize| i == 0 || j == 0 || i == ny - 1 || j == nx - 1;
+
+        // Port on the frame node (i, j), skipping the corners
+        let port = |i: usize, j: usize| {
+            let (wall, s) = match (i, j) {
+                (0, j) if j > 0 && j < nx - 1 => (Wall::Bottom, j as f64 / (nx - 1) as f64),
+                (i, j) if i == ny - 1 && j > 0 && j < nx - 1 => (Wall::Top, j as f64 / (nx - 1) as f64),
+                (i, 0) if i > 0 && i < ny - 1 => (Wall::Left, i as f64 / (ny - 1) as f64),
+                (i, j) if j == nx - 1 && i > 0 && i < ny - 1 => (Wall::Right, i as f64 / (ny - 1) as f64),
+                _ => return None,
+            };
+            if params.inlets.iter().any(|p| p.covers(wall, s)) {
+                Some((GasNode::Inlet, wall))
+            } else if params.outlets.iter().any(|p| p.covers(wall, s)) {
+                Some((GasNode::Outlet, wall))
+            } else {
+                None
+            }
+        };
+
+        let nodes = Array2::from_shape_fn((ny, nx), |(i, j)| {
+            if !gas(i, j) {
+                return GasNode::Outside;
+            }
+            if frame(i, j) {
+                return port(i, j).map_or(GasNode::Wall, |(kind, _)| kind);
+            }
+            if !gas(i - 1, j) && region[[i - 1, j]] == Region::Melt {
+                return GasNode::Surface;
+            }
+            if !(gas(i + 1, j) && gas(i - 1, j) && gas(i, j + 1) && gas(i, j - 1)) {
+                return GasNode::Wall;
+            }
+            GasNode::Fluid
+        });
+
+        // Connected gas regions and connected solid bodies
+        let gas_parts = components(region, |r| r == Region::Gas);
+        let bodies = components(region, |r| r != Region::Gas);
+
+        // Inflow and outlet length of each gas region; a port node spans one spacing
+        let length = |wall: Wall| match wall {
+            Wall::Bottom | Wall::Top => dx,
+            Wall::Left | Wall::Right => dy,
+        };
+        let parts = gas_parts.iter().flatten().max().map_or(0, |m| m + 1);
+        let (mut inflow, mut outlet_length) = (vec![0.0; parts], vec![0.0; parts]);
+        for ((i, j), node) in nodes.indexed_iter() {
+            if let (Some((kind, wall)), Some(part)) = (port(i, j), gas_parts[[i, j]]) {
+                match kind {
+                    GasNode::Inlet => inflow[part] += params.inflow * length(wall),
+                    _ => outlet_length[part] += length(wall),
+                }
+                debug_assert!(*node == kind);
+            }
+        }
+        let unbalanced = (0..parts).filter(|&p| inflow[p] != 0.0 && outlet_length[p] == 0.0).count();
+        let mut port_speed = Array2::zeros((ny, nx));
+        for ((i, j), node) in nodes.indexed_iter() {
+            let part = gas_parts[[i, j]];
+            port_speed[[i, j]] = match (node, part) {
+                (GasNode::Inlet, Some(p)) if outlet_length[p] > 0.0 => params.inflow,
+                (GasNode::Outlet, Some(p)) if outlet_length[p] > 0.0 => -inflow[p] / outlet_length[p],
+                _ => 0.0,
+            };
+        }
+
+        // ψ along the frame, counter-clockwise from the bottom-left corner: dψ/ds is the
+        // outward normal velocity, lumped over the spacing around each port node
+        let mut walk: Vec<(usize, usize)> = (0..nx).map(|j| (0, j)).collect();
+        walk.extend((1..ny).map(|i| (i, nx - 1)));
+        walk.extend((0..nx - 1).rev().map(|j| (ny - 1, j)));
+        walk.extend((1..ny - 1).rev().map(|i| (i, 0)));
+        let mut frame_stream = Array2::zeros((ny, nx));
+        let mut psi = 0.0;
+        let mut previous = 0.0;
+        for &(i, j) in &walk {
+            let flux = port(i, j).map_or(0.0, |(_, wall)| -port_speed[[i, j]] * length(wall));
+            psi += 0.5 * (previous + flux);
+            frame_stream[[i, j]] = psi;
+            previous = flux;
+        }
+
+        // Each body takes the frame ψ where the walk first meets it
+        let mut body_stream = vec![None; bodies.iter().flatten().max().map_or(0, |m| m + 1)];
+        for &(i, j) in &walk {
+            if let Some(b) = bodies[[i, j]] {
+                body_stream[b].get_or_insert(frame_stream[[i, j]]);
+            }
+        }
+        let body_at = |i: usize, j: usize| bodies[[i, j]].and_then(|b| body_stream[b]);
+
+        let mut wall_stream = Array2::zeros((ny, nx));
+        for ((i, j), node) in nodes.indexed_iter() {
+            wall_stream[[i, j]] = match node {
+                GasNode::Outside | GasNode::Fluid => 0.0,
+                _ if frame(i, j) => frame_stream[[i, j]],
+                _ => [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)]
+                    .into_iter()
+                    .find_map(|(ii, jj)| body_at(ii, jj))
+                    .unwrap_or(0.0),
+            };
+        }
+
+        GasFlow { params: params.clone(), nodes, wall_stream, port_speed, unbalanced, dx, dy }
+    }
+
+    pub fn is_fluid(&self, i: usize, j: usize) -> bool {
+        self.nodes[[i, j]] == GasNode::Fluid
+    }
+
+    // Largest normal speed at the ports, for the time step limit.
+    pub fn fastest_port(&self) -> f64 {
+        self.port_speed.iter().fold(0.0f64, |m, s| m.max(s.abs()))
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.nodes.iter().any(|n| *n == GasNode::Fluid), "the gas region has no interior nodes to carry a flow");
+        anyhow::ensure!(self.unbalanced == 0, "{} gas region(s) have an inlet but no outlet", self.unbalanced);
+        Ok(())
+    }
+
+    // Fixed number of Jacobi sweeps for ∇²ψ_g = −ω_g on the gas fluid nodes.
+    pub fn solve_stream_function(&self, state: &mut SimState, sweeps: usize) {
+        let (ny, nx) = state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        for ((i, j), node) in self.nodes.indexed_iter() {
+            if !matches!(node, GasNode::Outside | GasNode::Fluid) {
+                state.gas_stream[[i, j]] = self.wall_stream[[i, j]];
+            }
+        }
+        for _ in 0..sweeps {
+            let stream_old = state.gas_stream.clone();
+            for i in 1..ny - 1 {
+                for j in 1..nx - 1 {
+                    if !self.is_fluid(i, j) {
+                        continue;
+                    }
+                    state.gas_stream[[i, j]] = ((stream_old[[i, j + 1]] + stream_old[[i, j - 1]]) * dy * dy
+                        + (stream_old[[i + 1, j]] + stream_old[[i - 1, j]]) * dx * dx
+                        + state.gas_vort[[i, j]] * dx * dx * dy * dy)
+                        / (2.0 * (dx * dx + dy * dy));
+                }
+            }
+        }
+    }
+
+    // Gas velocities at the fluid nodes and the prescribed normal velocity at the ports.
+    pub fn update_velocities(&self, state: &mut SimState) {
+        let (ny, nx) = state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        for ((i, j), node) in self.nodes.indexed_iter() {
+            let (u, v) = match node {
+                GasNode::Fluid => (
+                    (state.gas_stream[[i + 1, j]] - state.gas_stream[[i - 1, j]]) / (2.0 * dy),
+                    -(state.gas_stream[[i, j + 1]] - state.gas_stream[[i, j - 1]]) / (2.0 * dx),
+                ),
+                GasNode::Inlet | GasNode::Outlet => {
+                    let speed = self.port_speed[[i, j]];
+                    match (i, j) {
+                        (0, _) => (0.0, speed),
+                        (i, _) if i == ny - 1 => (0.0, -speed),
+                        (_, 0) => (speed, 0.0),
+                        _ if j == nx - 1 => (-speed, 0.0),
+                        _ => (0.0, 0.0),
+                    }
+                }
+                _ => continue,
+            };
+            state.u[[i, j]] = u;
+            state.v[[i, j]] = v;
+        }
+    }
+
+    // Wall vorticity of the gas flow, and the melt's surface vorticity from the gas shear.
+    // Walls take the mean of Thom's formula over their fluid neighbours, the free surface
+    // the moving-wall form ω = 2 (Δy u_s − (ψ₁ − ψ_w)) / Δy² towards the gas above it, with
+    // the melt surface velocity u_s = −(ψ_melt + Δy² ω_s / 2) / Δy from the melt node
+    // below. Inlets carry plug flow (ω = 0) and outlets have ∂ω/∂n = 0.
+    pub fn update_wall_vorticity(&self, state: &mut SimState) {
+        let (ny, nx) = state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        let ratio = self.params.viscosity_ratio;
+        for ((i, j), node) in self.nodes.indexed_iter() {
+            match node {
+                GasNode::Outside | GasNode::Fluid => {}
+                GasNode::Inlet => state.gas_vort[[i, j]] = 0.0,
+                GasNode::Outlet => {
+                    let inner = match (i, j) {
+                        (0, _) => (1, j),
+                        (i, _) if i == ny - 1 => (ny - 2, j),
+                        (_, 0) => (i, 1),
+                        _ => (i, nx - 2),
+                    };
+                    state.gas_vort[[i, j]] = state.gas_vort[inner];
+                }
+                GasNode::Wall | GasNode::Surface => {
+                    let melt_below = *node == GasNode::Surface && state.is_fluid(i - 1, j);
+                    let surface_speed = if melt_below {
+                        -(state.stream[[i - 1, j]] + 0.5 * dy * dy * ratio * state.gas_vort[[i, j]]) / dy
+                    } else {
+                        0.0
+                    };
+                    let psi_w = self.wall_stream[[i, j]];
+                    let mut wall_vort = 0.0;
+                    let mut fluid_neighbours = 0;
+                    let neighbours = [
+                        (i + 1 < ny).then(|| (i + 1, j, dy * dy, surface_speed * dy)),
+                        (i > 0).then(|| (i - 1, j, dy * dy, 0.0)),
+                        (j + 1 < nx).then(|| (i, j + 1, dx * dx, 0.0)),
+                        (j > 0).then(|| (i, j - 1, dx * dx, 0.0)),
+                    ];
+                    for (ii, jj, h2, moving) in neighbours.into_iter().flatten() {
+                        if self.is_fluid(ii, jj) {
+                            wall_vort += 2.0 * (moving - (state.gas_stream[[ii, jj]] - psi_w)) / h2;
+                            fluid_neighbours += 1;
+                        }
+                    }
+                    state.gas_vort[[i, j]] = if fluid_neighbours > 0 { wall_vort / fluid_neighbours as f64 } else { 0.0 };
+                    if melt_below {
+                        state.vort[[i, j]] = ratio * state.gas_vort[[i, j]];
+                    }
+                }
+            }
+        }
+    }
+
+    // ∂ω_g/∂t at the gas fluid nodes: diffusion, upwind advection and buoyancy.
+    pub fn vorticity_rate(&self, state: &SimState, ra_pr: f64) -> Array2<f64> {
+        let (ny, nx) = state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        let w = &state.gas_vort;
+        let mut rate = Array2::zeros((ny, nx));
+        for i in 1..ny - 1 {
+            for j in 1..nx - 1 {
+                if !self.is_fluid(i, j) {
+                    continue;
+                }
+                let diffusion = self.params.viscosity
+                    * ((w[[i, j + 1]] - 2.0 * w[[i, j]] + w[[i, j - 1]]) / (dx * dx) + (w[[i + 1, j]] - 2.0 * w[[i, j]] + w[[i - 1, j]]) / (dy * dy));
+                let advection = upwind_advection(w, state.u[[i, j]], state.v[[i, j]], i, j, dx, dy);
+                let buoyancy = ra_pr * self.params.expansion_ratio * (state.temp[[i, j + 1]] - state.temp[[i, j - 1]]) / (2.0 * dx);
+                rate[[i, j]] = diffusion - advection + buoyancy;
+            }
+        }
+        rate
+    }
+
+    // Inlet temperature at the inlets, zero normal gradient at the outlets.
+    pub fn apply_ports(&self, temp: &mut Array2<f64>) {
+        let (ny, nx) = temp.dim();
+        for ((i, j), node) in self.nodes.indexed_iter() {
+            match node {
+                GasNode::Inlet => temp[[i, j]] = self.params.inlet_temperature,
+                GasNode::Outlet => {
+                    let inner = match (i, j) {
+                        (0, _) => (1, j),
+                        (i, _) if i == ny - 1 => (ny - 2, j),
+                        (_, 0) => (i, 1),
+                        _ => (i, nx - 2),
+                    };
+                    temp[[i, j]] = temp[inner];
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Heat carried in through the inlets and out through the outlets, ρc |v_n| T per node.
+    pub fn port_heat(&self, temp: &Array2<f64>, heat_capacity: &Array2<f64>) -> (f64, f64) {
+        let (ny, _) = temp.dim();
+        let (mut heat_in, mut heat_out) = (0.0, 0.0);
+        for ((i, j), node) in self.nodes.indexed_iter() {
+            let width = if i == 0 || i == ny - 1 { self.dx } else { self.dy };
+            let flux = heat_capacity[[i, j]] * self.port_speed[[i, j]].abs() * temp[[i, j]] * width;
+            match node {
+                GasNode::Inlet => heat_in += flux,
+                GasNode::Outlet => heat_out += flux,
+                _ => {}
+            }
+        }
+        (heat_in, heat_out)
+    }
+}
+
+// Label the 4-connected components of the nodes whose region satisfies `member`.
+fn components(region: &Array2<Region>, member: impl Fn(Region) -> bool) -> Array2<Option<usize>> {
+    let (ny, nx) = region.dim();
+    let mut label = Array2::from_elem((ny, nx), None);
+    let mut next = 0;
+    for start in 0..ny * nx {
+        let (i0, j0) = (start / nx, start % nx);
+        if label[[i0, j0]].is_some() || !member(region[[i0, j0]]) {
+            continue;
+        }
+        let mut stack = vec![(i0, j0)];
+        label[[i0, j0]] = Some(next);
+        while let Some((i, j)) = stack.pop() {
+            let neighbours = [(i + 1 < ny).then(|| (i + 1, j)), (i > 0).then(|| (i - 1, j)), (j + 1 < nx).then(|| (i, j + 1)), (j > 0).then(|| (i, j - 1))];
+            for (ii, jj) in neighbours.into_iter().flatten() {
+                if label[[ii, jj]].is_none() && member(region[[ii, jj]]) {
+                    label[[ii, jj]] = Some(next);
+                    stack.push((ii, jj));
+                }
+            }
+        }
+        next += 1;
+    }
+    label
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::regions::RegionLayout;
+
+// Crystal pulling parameters, dimensionless with the domain height and the thermal time scale.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrowthParameters {
+    pub pull_rate: f64,     // Crystal growth rate
+    pub density_ratio: f64, // ρ_crystal / ρ_melt
+    pub log_interval: usize, // Steps between recorded growth samples
+}
+
+// Crystal length and melt height at one instant.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrowthRecord {
+    pub step: usize,
+    pub time: f64,
+    pub crystal_length: f64,
+    pub melt_height: f64,
+}
+
+// Evolution of the crystal and the melt it is pulled from. Growth is mass conserving:
+// the melt area frozen onto the crystal comes out of the melt pool, so the free surface
+// drops by (ρ_s/ρ_l) (w_crystal / w_crucible) per unit of grown length. The crystal rises
+// with the pull and grows from the surface, so its top is at the melt height plus its
+// length, and the part above the domain is outside the simulation.
+pub struct Growth {
+    pub params: GrowthParameters,
+    pub crystal_length: f64,
+    pub history: Vec<GrowthRecord>,
+}
+
+impl Growth {
+    // Start from the crystal of the layout, by default the part reaching the domain top.
+    pub fn new(params: GrowthParameters, layout: &RegionLayout) -> Self {
+        Growth {
+            params,
+            crystal_length: layout.crystal_length.unwrap_or(1.0 - layout.melt_height),
+            history: Vec::new(),
+        }
+    }
+
+    // Grow the crystal over one time step and lower the melt level of a domain `width`
+    // wide accordingly. Returns false once the crucible has run dry.
+    pub fn advance(&mut self, layout: &mut RegionLayout, dt: f64, width: f64) -> bool {
+        let crucible_floor = layout.susceptor_thickness + layout.crucible_thickness;
+        let crucible_width = layout.crucible_width(width);
+        if layout.melt_height <= crucible_floor {
+            return false;
+        }
+
+        let grown = self.params.pull_rate * dt;
+        self.crystal_length += grown;
+        layout.melt_height -= self.params.density_ratio * layout.crystal_width / crucible_width * grown;
+        layout.melt_height = layout.melt_height.max(crucible_floor);
+        layout.crystal_length = Some(self.crystal_length);
+        true
+    }
+
+    pub fn record(&mut self, step: usize, time: f64, layout: &RegionLayout) {
+        self.history.push(GrowthRecord {
+            step,
+            time,
+            crystal_length: self.crystal_length,
+            melt_height: layout.melt_height,
+        });
+    }
+}
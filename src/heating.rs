@@ -0,0 +1,171 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::mask::Shape;
+use crate::regions::Region;
+use crate::simulation::{wrap_columns, Domain};
+
+// Where a volumetric heat source deposits its heat, in domain coordinates: x from 0 to
+// the domain width and y from 0 to 1, both in units of the height.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceShape {
+    Uniform,        // Every node of the domain
+    Region(Region), // Every node of one material region, e.g. the susceptor
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+    Disk { cx: f64, cy: f64, r: f64 },
+    Gaussian { cx: f64, cy: f64, radius: f64 }, // exp(−d² / radius²)
+}
+
+// Time dependence of a source, the factor its rate is multiplied with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Schedule {
+    #[default]
+    Constant,
+    Ramp { time: f64 },                          // Linear from 0 to 1 over `time`, then 1
+    Oscillating { period: f64, amplitude: f64 }, // 1 + amplitude sin(2π t / period)
+}
+
+impl Schedule {
+    pub fn factor(&self, time: f64) -> f64 {
+        match *self {
+            Schedule::Constant => 1.0,
+            Schedule::Ramp { time: ramp } => (time / ramp).min(1.0),
+            Schedule::Oscillating { period, amplitude } => 1.0 + amplitude * (2.0 * PI * time / period).sin(),
+        }
+    }
+}
+
+// Volumetric heat source Q(x, y, t) = q s(x, y) f(t) in the temperature equation
+// ρc ∂T/∂t = ∇·(k∇T) + Q, scaled with k_melt ΔT / H²: q = 1 over the whole melt
+// generates the heat a unit temperature difference conducts across it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeatSource {
+    pub shape: SourceShape,
+    pub q: f64, // Rate inside the shape, at the centre of a Gaussian
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+impl HeatSource {
+    // s(x, y) at every node of the region map. With periodic x the ghost columns take
+    // the values of the interior columns they copy.
+    pub fn field(&self, region: &Array2<Region>, domain: &Domain) -> Array2<f64> {
+        let (ny, nx) = region.dim();
+        let (dx, dy) = domain.spacing(nx, ny);
+        let first = domain.first_column() as f64;
+        let mut field = Array2::from_shape_fn((ny, nx), |(i, j)| {
+            let (x, y) = ((j as f64 - first) * dx, i as f64 * dy);
+            let inside = |shape: Shape| if shape.contains(x, y, domain.width) { 1.0 } else { 0.0 };
+            match self.shape {
+                SourceShape::Uniform => 1.0,
+                SourceShape::Region(r) => if region[[i, j]] == r { 1.0 } else { 0.0 },
+                SourceShape::Rect { x0, y0, x1, y1 } => inside(Shape::Rect { x0, y0, x1, y1 }),
+                SourceShape::Disk { cx, cy, r } => inside(Shape::Disk { cx, cy, r }),
+                SourceShape::Gaussian { cx, cy, radius } => (-((x - cx).powi(2) + (y - cy).powi(2)) / (radius * radius)).exp(),
+            }
+        });
+        if domain.periodic {
+            wrap_columns(&mut field);
+        }
+        field
+    }
+}
+
+// `shape:q[@schedule]` with shape = uniform | region:<region> | rect:x0,y0,x1,y1 |
+// disk:cx,cy,r | gauss:cx,cy,radius and schedule = ramp=<time> | sine=<period>,<amplitude>.
+impl FromStr for HeatSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (spec, schedule) = match s.split_once('@') {
+            Some((spec, schedule)) => (spec, Some(schedule)),
+            None => (s, None),
+        };
+        let parts: Vec<&str> = spec.split(':').collect();
+        let (q, shape) = match parts.as_slice() {
+            ["uniform", q] => (q, SourceShape::Uniform),
+            ["region", region, q] => (q, SourceShape::Region(region.parse()?)),
+            [kind, values, q] => {
+                let values = values
+                    .split(',')
+                    .map(|v| v.trim().parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>()?;
+                let shape = match (*kind, values.as_slice()) {
+                    ("rect", &[x0, y0, x1, y1]) => SourceShape::Rect { x0, y0, x1, y1 },
+                    ("disk", &[cx, cy, r]) => SourceShape::Disk { cx, cy, r },
+                    ("gauss", &[cx, cy, radius]) if radius > 0.0 => SourceShape::Gaussian { cx, cy, radius },
+                    _ => anyhow::bail!("cannot build heat source '{}' from {:?}", kind, values),
+                };
+                (q, shape)
+            }
+            _ => anyhow::bail!("expected shape:q[@schedule], got '{}'", s),
+        };
+
+        let schedule = match schedule.map(|s| s.split_once('=')) {
+            None => Schedule::Constant,
+            Some(Some(("ramp", time))) => {
+                let time: f64 = time.parse()?;
+                anyhow::ensure!(time > 0.0, "the ramp time must be positive");
+                Schedule::Ramp { time }
+            }
+            Some(Some(("sine", values))) => {
+                let (period, amplitude) = values.split_once(',').ok_or_else(|| anyhow::anyhow!("expected sine=<period>,<amplitude>"))?;
+                let (period, amplitude): (f64, f64) = (period.parse()?, amplitude.parse()?);
+                anyhow::ensure!(period > 0.0, "the period must be positive");
+                Schedule::Oscillating { period, amplitude }
+            }
+            Some(_) => anyhow::bail!("unknown schedule in '{}', expected ramp=<time> or sine=<period>,<amplitude>", s),
+        };
+        Ok(HeatSource { shape, q: q.parse()?, schedule })
+    }
+}
+
+impl fmt::Display for HeatSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.shape {
+            SourceShape::Uniform => write!(f, "uniform")?,
+            SourceShape::Region(region) => write!(f, "region:{}", region)?,
+            SourceShape::Rect { x0, y0, x1, y1 } => write!(f, "rect:{},{},{},{}", x0, y0, x1, y1)?,
+            SourceShape::Disk { cx, cy, r } => write!(f, "disk:{},{},{}", cx, cy, r)?,
+            SourceShape::Gaussian { cx, cy, radius } => write!(f, "gauss:{},{},{}", cx, cy, radius)?,
+        }
+        write!(f, ":{}", self.q)?;
+        match self.schedule {
+            Schedule::Constant => Ok(()),
+            Schedule::Ramp { time } => write!(f, "@ramp={}", time),
+            Schedule::Oscillating { period, amplitude } => write!(f, "@sine={},{}", period, amplitude),
+        }
+    }
+}
+
+// The sources of a run on its grid, rebuilt whenever the region map changes, plus the
+// Joule heating of the induction coils if there are any.
+#[derive(Default)]
+pub struct HeatSourceField {
+    terms: Vec<(Array2<f64>, Schedule)>, // q s(x, y) of each source and its schedule
+    pub joule: Option<Array2<f64>>,      // Time-averaged Joule heating, steady for a fixed geometry
+}
+
+impl HeatSourceField {
+    pub fn new(sources: &[HeatSource], region: &Array2<Region>, domain: &Domain, joule: Option<Array2<f64>>) -> Self {
+        let terms = sources.iter().map(|s| (s.field(region, domain) * s.q, s.schedule)).collect();
+        HeatSourceField { terms, joule }
+    }
+
+    // Q at every node at `time`, or None without any source.
+    pub fn at(&self, time: f64) -> Option<Array2<f64>> {
+        if self.terms.is_empty() && self.joule.is_none() {
+            return None;
+        }
+        let mut q = self.joule.clone().unwrap_or_else(|| Array2::zeros(self.terms[0].0.dim()));
+        for (field, schedule) in &self.terms {
+            q.scaled_add(schedule.factor(time), field);
+        }
+        Some(q)
+    }
+}
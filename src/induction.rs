@@ -0,0 +1,263 @@
+use ndarray::{s, Array2};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::regions::Region;
+use crate::simulation::Domain;
+
+// Electrical conductivity of each region relative to the melt's. The defaults are for
+// liquid silicon (1.2e6 S/m) with solid silicon, fused quartz and graphite around it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ElectricalConductivity {
+    pub melt: f64,
+    pub crystal: f64,
+    pub crucible: f64,
+    pub susceptor: f64,
+    pub gas: f64,
+    pub heat_shield: f64,
+}
+
+impl Default for ElectricalConductivity {
+    fn default() -> Self {
+        ElectricalConductivity { melt: 1.0, crystal: 0.04, crucible: 0.0, susceptor: 0.1, gas: 0.0, heat_shield: 0.1 }
+    }
+}
+
+impl ElectricalConductivity {
+    pub fn of(&self, region: Region) -> f64 {
+        match region {
+            Region::Melt => self.melt,
+            Region::Crystal => self.crystal,
+            Region::Crucible => self.crucible,
+            Region::Susceptor => self.susceptor,
+            Region::Gas => self.gas,
+            Region::HeatShield => self.heat_shield,
+        }
+    }
+}
+
+// One turn of the induction coil, a circular loop around the axis at radius r and height
+// z, in units of the melt height with z = 0 at the domain bottom.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Coil {
+    pub r: f64,
+    pub z: f64,
+}
+
+// `r,z`
+impl FromStr for Coil {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r, z) = s.split_once(',').ok_or_else(|| anyhow::anyhow!("expected r,z, got '{}'", s))?;
+        let coil = Coil { r: r.trim().parse()?, z: z.trim().parse()? };
+        anyhow::ensure!(coil.r > 0.0, "a coil turn needs a positive radius");
+        Ok(coil)
+    }
+}
+
+// Free space solved around the furnace, in melt heights, before the potential is set to zero.
+const DEFAULT_PADDING: f64 = 2.0;
+
+fn default_padding() -> f64 {
+    DEFAULT_PADDING
+}
+
+// RF induction heating by coil turns in series carrying a time-harmonic current I e^{iωt}.
+// Lengths are scaled with the melt height H and A with μ₀ I, so the azimuthal vector
+// potential of the axisymmetric problem solves
+//   ∂²A/∂r² + (1/r) ∂A/∂r − A/r² + ∂²A/∂z² − i ω̂ σ̂ A = −Σ δ(r − r_c) δ(z − z_c),
+// ω̂ = ω μ₀ σ_melt H² = 2 (H/δ)² with δ the skin depth in the melt. The time-averaged
+// Joule heating ½ σ ω² |A|² in units of k_melt ΔT / H² is then
+//   Q = ½ σ̂ ω̂² Î² |A|²,  Î = I / √(σ_melt H² k_melt ΔT).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InductionParameters {
+    pub frequency: f64, // ω̂
+    pub current: f64,   // Î
+    pub coils: Vec<Coil>,
+    #[serde(default)]
+    pub conductivity: ElectricalConductivity,
+    #[serde(default = "default_padding")]
+    pub padding: f64,
+}
+
+impl InductionParameters {
+    pub fn new(frequency: f64, current: f64, coils: Vec<Coil>) -> anyhow::Result<Self> {
+        anyhow::ensure!(frequency > 0.0, "the coil frequency must be positive");
+        anyhow::ensure!(!coils.is_empty(), "induction heating needs at least one coil turn");
+        Ok(InductionParameters { frequency, current, coils, conductivity: ElectricalConductivity::default(), padding: DEFAULT_PADDING })
+    }
+
+    // Skin depth in the melt over H.
+    pub fn skin_depth(&self) -> f64 {
+        (2.0 / self.frequency).sqrt()
+    }
+}
+
+// Eddy currents in the furnace cross-section. The grid is read as a diametral section
+// with the axis at its centre line; the right half defines the axisymmetric body and the
+// solution is mirrored onto the left. The potential is solved on a meridional (r, z) grid
+// with the spacing of the flow grid, extended by the padding and over the coils, with
+// A = 0 on the axis and on the far boundaries.
+pub struct EddyCurrentSolver {
+    params: InductionParameters,
+    spacing: (f64, f64), // (Δx, Δy) of the flow grid, also (Δr, Δz)
+    axis: f64,           // x of the axis on the flow grid
+    first: usize,        // Flow grid column at x = 0, after the ghost column with periodic x
+    below: usize,        // Rows of the meridional grid below the flow grid
+    source: Array2<f64>, // Coil current density per unit current
+    pub potential: Array2<Complex64>, // A over (z, r), warm start of the next solve
+    pub iterations: usize, // Iterations of the latest solve
+}
+
+impl EddyCurrentSolver {
+    pub fn new(params: &InductionParameters, ny: usize, spacing: (f64, f64), domain: &Domain) -> Self {
+        let (dr, dz) = spacing;
+        let axis = 0.5 * domain.width;
+        let z_min = params.coils.iter().fold(0.0f64, |m, c| m.min(c.z)) - params.padding;
+        let z_max = params.coils.iter().fold(1.0f64, |m, c| m.max(c.z)) + params.padding;
+        let r_max = params.coils.iter().fold(axis, |m, c| m.max(c.r)) + params.padding;
+
+        let below = (-z_min / dz).ceil() as usize;
+        let rows = below + ny + ((z_max - 1.0) / dz).ceil() as usize;
+        let columns = (r_max / dr).ceil() as usize + 1;
+
+        // Each turn's unit current is spread over the four nodes around it
+        let mut source = Array2::zeros((rows, columns));
+        for coil in &params.coils {
+            let (fr, fz) = (coil.r / dr, coil.z / dz + below as f64);
+            let (k, i) = (fr as usize, fz as usize);
+            let (sr, sz) = (fr - k as f64, fz - i as f64);
+            for (ii, kk, w) in [(i, k, (1.0 - sz) * (1.0 - sr)), (i, k + 1, (1.0 - sz) * sr), (i + 1, k, sz * (1.0 - sr)), (i + 1, k + 1, sz * sr)] {
+                source[[ii, kk]] += w / (dr * dz);
+            }
+        }
+
+        EddyCurrentSolver {
+            params: params.clone(),
+            spacing,
+            axis,
+            first: domain.first_column(),
+            below,
+            potential: Array2::zeros((rows, columns)),
+            source,
+            iterations: 0,
+        }
+    }
+
+    // σ̂ on the meridional grid from the region map of the flow grid; zero outside it.
+    fn conductivity(&self, region: &Array2<Region>) -> Array2<f64> {
+        let (ny, nx) = region.dim();
+        let dx = self.spacing.0;
+        Array2::from_shape_fn(self.potential.dim(), |(i, k)| {
+            let j = ((self.axis + k as f64 * dx) / dx).round() as usize + self.first;
+            match i.checked_sub(self.below) {
+                Some(iy) if iy < ny && j < nx => self.params.conductivity.of(region[[iy, j]]),
+                _ => 0.0,
+            }
+        })
+    }
+
+    // The discrete operator times r at every interior node, which makes it complex
+    // symmetric: the r-face coefficients r_{k±1/2} / Δr² are shared by both neighbours.
+    fn apply(&self, sigma: &Array2<f64>, a: &Array2<Complex64>) -> Array2<Complex64> {
+        let (rows, columns) = a.dim();
+        let (dr, dz) = self.spacing;
+        let omega = self.params.frequency;
+        let mut out = Array2::zeros((rows, columns));
+        for i in 1..rows - 1 {
+            for k in 1..columns - 1 {
+                let r = k as f64 * dr;
+                let (ce, cw, cz) = ((r + 0.5 * dr) / (dr * dr), (r - 0.5 * dr) / (dr * dr), r / (dz * dz));
+                let diag = Complex64::new(ce + cw + 1.0 / r + 2.0 * cz, r * omega * sigma[[i, k]]);
+                out[[i, k]] = a[[i, k]] * diag - a[[i, k + 1]] * ce - a[[i, k - 1]] * cw - (a[[i + 1, k]] + a[[i - 1, k]]) * cz;
+            }
+        }
+        out
+    }
+
+    // Solve for A, warm started, and return the Joule heating at the nodes of the flow grid.
+    // The system is complex symmetric but not Hermitian, so SOR may diverge at high
+    // frequencies; the conjugate orthogonal conjugate gradient method (CG with the
+    // unconjugated product xᵀy) with a Jacobi preconditioner converges at any frequency.
+    pub fn solve(&mut self, region: &Array2<Region>) -> Array2<f64> {
+        let sigma = self.conductivity(region);
+        let (rows, columns) = self.potential.dim();
+        let (dr, dz) = self.spacing;
+        let omega = self.params.frequency;
+        let dot = |x: &Array2<Complex64>, y: &Array2<Complex64>| x.iter().zip(y.iter()).map(|(a, b)| a * b).sum::<Complex64>();
+        let norm = |x: &Array2<Complex64>| x.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+
+        // Right-hand side r J and the inverse diagonal, both zero on the boundary
+        let mut rhs = Array2::zeros((rows, columns));
+        let mut inverse_diag = Array2::zeros((rows, columns));
+        for i in 1..rows - 1 {
+            for k in 1..columns - 1 {
+                let r = k as f64 * dr;
+                rhs[[i, k]] = Complex64::new(r * self.source[[i, k]], 0.0);
+                inverse_diag[[i, k]] = Complex64::new(2.0 * r / (dr * dr) + 1.0 / r + 2.0 * r / (dz * dz), r * omega * sigma[[i, k]]).inv();
+            }
+        }
+
+        let target = 1e-10 * norm(&rhs);
+        let mut residual = &rhs - &self.apply(&sigma, &self.potential);
+        let mut z = &residual * &inverse_diag;
+        let mut direction = z.clone();
+        let mut rho = dot(&residual, &z);
+        self.iterations = 0;
+        while norm(&residual) > target && self.iterations < 10 * (rows + columns) {
+            self.iterations += 1;
+            let q = self.apply(&sigma, &direction);
+            let alpha = rho / dot(&direction, &q);
+            self.potential.scaled_add(alpha, &direction);
+            residual.scaled_add(-alpha, &q);
+            z = &residual * &inverse_diag;
+            let rho_next = dot(&residual, &z);
+            direction = &z + &(direction * (rho_next / rho));
+            rho = rho_next;
+        }
+        self.joule_heating(region)
+    }
+
+    // Q = ½ σ̂ ω̂² Î² |A|² at every flow node, with A interpolated linearly in r.
+    pub fn joule_heating(&self, region: &Array2<Region>) -> Array2<f64> {
+        let (ny, nx) = region.dim();
+        let dx = self.spacing.0;
+        let scale = 0.5 * (self.params.frequency * self.params.current).powi(2);
+        let columns = self.potential.ncols();
+        Array2::from_shape_fn((ny, nx), |(iy, j)| {
+            let conductivity = self.params.conductivity.of(region[[iy, j]]);
+            if conductivity == 0.0 {
+                return 0.0;
+            }
+            let i = iy + self.below;
+            let fr = ((j as f64 - self.first as f64) * dx - self.axis).abs() / dx;
+            let k = (fr as usize).min(columns - 2);
+            let s = fr - k as f64;
+            let a = self.potential[[i, k]] * (1.0 - s) + self.potential[[i, k + 1]] * s;
+            scale * conductivity * a.norm_sqr()
+        })
+    }
+}
+
+// ∫ Q dA over the cross-section and the largest Q with its position, for the run summary.
+// The ghost columns of a periodic grid are left out.
+pub fn print_summary(params: &InductionParameters, joule: &Array2<f64>, domain: &Domain, spacing: (f64, f64), iterations: usize) {
+    let (dx, dy) = spacing;
+    let first = domain.first_column();
+    let joule = joule.slice(s![.., first..joule.ncols() - first]);
+    let total: f64 = joule.sum() * dx * dy;
+    let ((i, j), peak) = joule
+        .indexed_iter()
+        .fold(((0, 0), 0.0f64), |best, (idx, q)| if *q > best.1 { (idx, *q) } else { best });
+    println!(
+        "Induction heating: {} coil turn(s), ω̂ = {:e} (skin depth {:.4} H), Î = {:e}, {} COCG iterations",
+        params.coils.len(),
+        params.frequency,
+        params.skin_depth(),
+        params.current,
+        iterations
+    );
+    println!("  ∫Q dA = {:.6e}, peak Q = {:.6e} at (x = {:.4}, y = {:.4})", total, peak, j as f64 * dx, i as f64 * dy);
+}
@@ -0,0 +1,45 @@
+// Czochralski melt flow simulator as a library, for tools that drive `Simulation` or
+// plug in a solver of their own. The `cz_cfd_simulator` binary is a CLI over it; the
+// run database is behind the `db` feature, so the solver builds without Postgres, and
+// the Python extension module and the C interface behind the `python` and `capi` features.
+
+pub mod boundary;
+pub mod case_file;
+pub mod checkpoint;
+pub mod conservation;
+pub mod continuation;
+#[cfg(feature = "db")]
+pub mod db;
+pub mod diagnostics;
+#[cfg(feature = "capi")]
+pub mod ffi;
+pub mod gas;
+pub mod growth;
+pub mod heating;
+pub mod induction;
+pub mod linalg;
+pub mod mask;
+pub mod materials;
+#[cfg(feature = "db")]
+pub mod models;
+pub mod perturbation;
+pub mod probes;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod radiation;
+pub mod regions;
+pub mod rng;
+#[cfg(feature = "db")]
+pub mod schema;
+pub mod simulation;
+pub mod solutal;
+pub mod solver;
+pub mod stability;
+pub mod uq;
+pub mod validation;
+pub mod verification;
+pub mod visualization;
+pub mod watchdog;
+
+pub use simulation::{SimParameters, SimState, Simulation};
+pub use solver::Solver;
@@ -0,0 +1,294 @@
+use num_complex::Complex64;
+
+// Small dense and Krylov tools for the matrix-free stability and continuation solvers.
+// Operators are closures acting on flat vectors; the dense matrices involved are only
+// the (k+1)×k Hessenberg matrices of a Krylov basis, so plain Vec<Vec<_>> is enough.
+
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// Orthonormal Krylov basis V and Hessenberg matrix H with A V_k = V_{k+1} H.
+pub struct Arnoldi {
+    pub basis: Vec<Vec<f64>>, // k+1 vectors (k after a breakdown)
+    pub h: Vec<Vec<f64>>,     // (k+1)×k, row major
+}
+
+impl Arnoldi {
+    pub fn dim(&self) -> usize {
+        self.h.first().map_or(0, |row| row.len())
+    }
+
+    // Build a basis of dimension up to `k`. Stops early when the Krylov space is invariant.
+    pub fn new(start: &[f64], k: usize, mut apply: impl FnMut(&[f64]) -> Vec<f64>) -> Self {
+        let mut basis = vec![scaled(start, 1.0 / norm(start))];
+        let mut h = vec![vec![0.0; k]; k + 1];
+        let mut dim = k;
+
+        for j in 0..k {
+            let mut w = apply(&basis[j]);
+            // Modified Gram–Schmidt, repeated once to keep the basis orthogonal
+            for _ in 0..2 {
+                for (i, v) in basis.iter().enumerate() {
+                    let c = dot(&w, v);
+                    h[i][j] += c;
+                    axpy(&mut w, -c, v);
+                }
+            }
+            let beta = norm(&w);
+            h[j + 1][j] = beta;
+            if beta < 1e-14 * h[j][j].abs().max(1.0) {
+                dim = j + 1;
+                break;
+            }
+            basis.push(scaled(&w, 1.0 / beta));
+        }
+
+        h.truncate(dim + 1);
+        for row in h.iter_mut() {
+            row.truncate(dim);
+        }
+        basis.truncate(dim + 1);
+        Arnoldi { basis, h }
+    }
+
+    // Ritz value, vector and residual estimate |h_{k+1,k} y_k| for an eigenvalue of H_k.
+    pub fn ritz_pair(&self, mu: Complex64) -> (Vec<Complex64>, f64) {
+        let k = self.dim();
+        let y = inverse_iteration(&self.h[..k], mu);
+        let n = self.basis[0].len();
+        let mut x = vec![Complex64::new(0.0, 0.0); n];
+        for (v, yi) in self.basis.iter().zip(&y) {
+            for (xe, ve) in x.iter_mut().zip(v) {
+                *xe += yi * ve;
+            }
+        }
+        let residual = self.h.get(k).map_or(0.0, |row| row[k - 1].abs() * y[k - 1].norm());
+        (x, residual)
+    }
+}
+
+// Restarted GMRES for A x = b from x = 0. Returns the solution, the final residual
+// norm and the number of operator applications.
+pub fn gmres(
+    mut apply: impl FnMut(&[f64]) -> Vec<f64>,
+    b: &[f64],
+    tol: f64,
+    restart: usize,
+    max_iterations: usize,
+) -> (Vec<f64>, f64, usize) {
+    let n = b.len();
+    let mut x = vec![0.0; n];
+    let b_norm = norm(b);
+    if b_norm == 0.0 {
+        return (x, 0.0, 0);
+    }
+    let mut residual = b.to_vec();
+    let mut residual_norm = b_norm;
+    let mut applications = 0;
+
+    while applications < max_iterations && residual_norm > tol * b_norm {
+        let mut basis = vec![scaled(&residual, 1.0 / residual_norm)];
+        let mut h: Vec<Vec<f64>> = Vec::new(); // Columns, already rotated to triangular form
+        let mut rotations: Vec<(f64, f64)> = Vec::new();
+        let mut g = vec![residual_norm];
+
+        for j in 0..restart.min(max_iterations - applications) {
+            let mut w = apply(&basis[j]);
+            applications += 1;
+            let mut column = vec![0.0; j + 2];
+            for _ in 0..2 {
+                for (i, v) in basis.iter().enumerate() {
+                    let c = dot(&w, v);
+                    column[i] += c;
+                    axpy(&mut w, -c, v);
+                }
+            }
+            column[j + 1] = norm(&w);
+            for (i, &(c, s)) in rotations.iter().enumerate() {
+                let (a, b) = (column[i], column[i + 1]);
+                column[i] = c * a + s * b;
+                column[i + 1] = -s * a + c * b;
+            }
+            let r = column[j].hypot(column[j + 1]);
+            let (c, s) = if r == 0.0 { (1.0, 0.0) } else { (column[j] / r, column[j + 1] / r) };
+            column[j] = r;
+            g.push(-s * g[j]);
+            g[j] *= c;
+            rotations.push((c, s));
+
+            let beta = column[j + 1];
+            column.truncate(j + 1);
+            h.push(column);
+            if g[j + 1].abs() <= tol * b_norm || beta == 0.0 {
+                break;
+            }
+            basis.push(scaled(&w, 1.0 / beta));
+        }
+
+        // Back substitution for the least-squares coefficients
+        let k = h.len();
+        let mut y = vec![0.0; k];
+        for i in (0..k).rev() {
+            let sum: f64 = (i + 1..k).map(|j| h[j][i] * y[j]).sum();
+            y[i] = (g[i] - sum) / h[i][i];
+        }
+        for (v, yi) in basis.iter().zip(&y) {
+            axpy(&mut x, *yi, v);
+        }
+
+        // True residual of the restart
+        let ax = apply(&x);
+        applications += 1;
+        residual = b.iter().zip(&ax).map(|(bi, ai)| bi - ai).collect();
+        residual_norm = norm(&residual);
+        if k == 0 || h[k - 1][k - 1] == 0.0 {
+            break;
+        }
+    }
+    (x, residual_norm, applications)
+}
+
+// Eigenvalues of an upper Hessenberg matrix by the shifted QR algorithm in complex
+// arithmetic (Wilkinson shifts, deflation on small subdiagonals).
+pub fn hessenberg_eigenvalues(h: &[Vec<f64>]) -> Vec<Complex64> {
+    let m = h.len();
+    let mut a: Vec<Vec<Complex64>> = h.iter().map(|row| row[..m].iter().map(|&x| Complex64::new(x, 0.0)).collect()).collect();
+    let mut eigenvalues = Vec::with_capacity(m);
+    let mut n = m;
+    let mut iterations = 0;
+
+    while n > 0 {
+        // Lowest start of an unreduced block ending at n-1
+        let mut lo = n - 1;
+        while lo > 0 {
+            let scale = a[lo][lo].norm() + a[lo - 1][lo - 1].norm();
+            if a[lo][lo - 1].norm() <= f64::EPSILON * scale.max(f64::MIN_POSITIVE) {
+                a[lo][lo - 1] = Complex64::new(0.0, 0.0);
+                break;
+            }
+            lo -= 1;
+        }
+        if lo == n - 1 {
+            eigenvalues.push(a[n - 1][n - 1]);
+            n -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > 1000 {
+            // No convergence; return the diagonal of the remaining block as estimates
+            eigenvalues.extend((0..n).rev().map(|i| a[i][i]));
+            break;
+        }
+        let shift = if iterations % 11 == 0 {
+            a[n - 1][n - 1] + Complex64::new(0.75 * a[n - 1][n - 2].norm(), 0.0) // Exceptional shift
+        } else {
+            wilkinson_shift(a[n - 2][n - 2], a[n - 2][n - 1], a[n - 1][n - 2], a[n - 1][n - 1])
+        };
+        qr_step(&mut a, lo, n, shift);
+    }
+
+    eigenvalues.reverse();
+    eigenvalues
+}
+
+// Eigenvalue of [[a, b], [c, d]] closer to d.
+fn wilkinson_shift(a: Complex64, b: Complex64, c: Complex64, d: Complex64) -> Complex64 {
+    let half = (a - d) * 0.5;
+    let disc = (half * half + b * c).sqrt();
+    let (l1, l2) = ((a + d) * 0.5 + disc, (a + d) * 0.5 - disc);
+    if (l1 - d).norm() < (l2 - d).norm() { l1 } else { l2 }
+}
+
+// One explicit shifted QR step A - σI = QR, A ← RQ + σI on the block [lo, n).
+fn qr_step(a: &mut [Vec<Complex64>], lo: usize, n: usize, shift: Complex64) {
+    for (i, row) in a.iter_mut().enumerate().take(n).skip(lo) {
+        row[i] -= shift;
+    }
+    let mut rotations = Vec::with_capacity(n - lo);
+    for k in lo..n - 1 {
+        let (x, y) = (a[k][k], a[k + 1][k]);
+        let r = (x.norm_sqr() + y.norm_sqr()).sqrt();
+        let (c, s) = if r == 0.0 { (Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)) } else { (x / r, y / r) };
+        let (upper, lower) = a.split_at_mut(k + 1);
+        for (p, q) in upper[k][k..n].iter_mut().zip(&mut lower[0][k..n]) {
+            (*p, *q) = (c.conj() * *p + s.conj() * *q, -s * *p + c * *q);
+        }
+        rotations.push((c, s));
+    }
+    for (k, (c, s)) in (lo..n - 1).zip(rotations) {
+        for row in a.iter_mut().take((k + 2).min(n)).skip(lo) {
+            let (p, q) = (row[k], row[k + 1]);
+            row[k] = p * c + q * s;
+            row[k + 1] = -p * s.conj() + q * c.conj();
+        }
+    }
+    for (i, row) in a.iter_mut().enumerate().take(n).skip(lo) {
+        row[i] += shift;
+    }
+}
+
+// Eigenvector of a small real matrix for a known eigenvalue, by complex inverse iteration.
+pub fn inverse_iteration(a: &[Vec<f64>], mu: Complex64) -> Vec<Complex64> {
+    let m = a.len();
+    let mu = mu + Complex64::new(1e-10, 1e-10) * mu.norm().max(1e-10); // Keep A - μI invertible
+    let mut y = vec![Complex64::new(1.0, 0.0); m];
+    for _ in 0..3 {
+        let mut shifted: Vec<Vec<Complex64>> = a
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row.iter().enumerate().map(|(j, &x)| Complex64::new(x, 0.0) - if i == j { mu } else { Complex64::new(0.0, 0.0) }).collect())
+            .collect();
+        y = solve_complex(&mut shifted, y);
+        let scale: f64 = y.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+        y.iter_mut().for_each(|v| *v /= scale);
+    }
+    y
+}
+
+// Gaussian elimination with partial pivoting; near-zero pivots are replaced by a tiny
+// value, which is what inverse iteration wants anyway.
+fn solve_complex(a: &mut [Vec<Complex64>], mut b: Vec<Complex64>) -> Vec<Complex64> {
+    let m = b.len();
+    let tiny = 1e-300;
+    for k in 0..m {
+        let pivot = (k..m).max_by(|&p, &q| a[p][k].norm().total_cmp(&a[q][k].norm())).unwrap_or(k);
+        a.swap(k, pivot);
+        b.swap(k, pivot);
+        if a[k][k].norm() < tiny {
+            a[k][k] = Complex64::new(tiny, 0.0);
+        }
+        let (upper, lower) = a.split_at_mut(k + 1);
+        let pivot_row = &upper[k];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[k] / pivot_row[k];
+            for (x, p) in row[k..].iter_mut().zip(&pivot_row[k..]) {
+                *x -= factor * p;
+            }
+            let bk = b[k];
+            b[k + 1 + offset] -= factor * bk;
+        }
+    }
+    let mut x = vec![Complex64::new(0.0, 0.0); m];
+    for k in (0..m).rev() {
+        let sum: Complex64 = (k + 1..m).map(|j| a[k][j] * x[j]).sum();
+        x[k] = (b[k] - sum) / a[k][k];
+    }
+    x
+}
+
+pub fn axpy(y: &mut [f64], alpha: f64, x: &[f64]) {
+    for (ye, xe) in y.iter_mut().zip(x) {
+        *ye += alpha * xe;
+    }
+}
+
+pub fn scaled(x: &[f64], alpha: f64) -> Vec<f64> {
+    x.iter().map(|v| alpha * v).collect()
+}
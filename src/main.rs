@@ -1,11 +1,10 @@
 use clap::{Parser, Subcommand};
 use anyhow::Result;
+use std::path::PathBuf;
 
-mod db;
-mod models;
-mod schema;
-mod simulation;
-mod visualization;
+use cz_cfd_simulator::{
+    boundary, case_file, checkpoint, continuation, db, diagnostics, gas, growth, heating, induction, mask, materials, models, perturbation, probes, regions, simulation, solutal, stability, uq, validation, verification, visualization, watchdog,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -16,22 +15,321 @@ struct Cli {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once at startup
 enum Commands {
     /// Run a new simulation and save the results
     Run {
         #[arg(short, long, default_value = "Test Run")]
         description: String,
+        /// Grid points over the height; the width gets the same spacing
         #[arg(short, long, default_value_t = 41)]
         grid_size: usize,
         #[arg(short, long, default_value_t = 1000)]
         steps: usize,
+        /// Width of the domain over its height
+        #[arg(long, default_value_t = 1.0)]
+        width: f64,
+        /// Periodic side boundaries instead of walls, e.g. for Rayleigh-Bénard layers; left and right wall conditions are ignored
+        #[arg(long)]
+        periodic: bool,
         #[arg(long, default_value_t = 0.71)]
         prandtl: f64,
         #[arg(long, default_value_t = 10000.0)]
         rayleigh: f64,
+        /// Extra wall condition `wall:start:end:bc`, bc = fixed=<T> | adiabatic | rad=<eps>,<T_env> (repeatable)
+        #[arg(long = "bc")]
+        boundary_segments: Vec<boundary::BoundarySegment>,
+        /// Radiation number N_r = σ ΔT³ L / k
+        #[arg(long, default_value_t = 1.0)]
+        radiation_number: f64,
+        /// Cold reference temperature over ΔT, shifts θ to an absolute scale for T⁴
+        #[arg(long, default_value_t = 1.0)]
+        radiation_offset: f64,
+        /// Exchange radiation between radiating walls using view factors
+        #[arg(long)]
+        surface_to_surface: bool,
+        /// Take the view factors between rings about the centre line, for an axisymmetric furnace
+        #[arg(long)]
+        axisymmetric_radiation: bool,
+        /// Let the free melt surface radiate, `<emissivity>,<T_env>`
+        #[arg(long)]
+        melt_surface_rad: Option<boundary::SurfaceEmission>,
+        /// Simulate crystal, crucible and susceptor as conducting regions around the melt
+        #[arg(long)]
+        multi_region: bool,
+        /// Pull the crystal at this dimensionless rate (needs --multi-region)
+        #[arg(long)]
+        pull_rate: Option<f64>,
+        /// Crystal to melt density ratio used to lower the melt level
+        #[arg(long, default_value_t = 0.91)]
+        density_ratio: f64,
+        /// Initial crystal length above the melt surface (needs --multi-region) [default: up to the domain top]
+        #[arg(long)]
+        crystal_length: Option<f64>,
+        /// Shape painted into the domain: rect:x0,y0,x1,y1:region | disk:cx,cy,r:region | bowl:depth:region (repeatable)
+        #[arg(long = "obstacle")]
+        obstacles: Vec<mask::Obstacle>,
+        /// Round the crucible bottom corners with an arc of this depth
+        #[arg(long)]
+        curved_bottom: Option<f64>,
+        /// Dip the crystal this far into the melt (width follows the layout, 0.4 without one)
+        #[arg(long)]
+        immersed_crystal: Option<f64>,
+        /// Add a heat shield above the melt beside the crystal (needs --multi-region)
+        #[arg(long)]
+        heat_shield: bool,
+        /// Geometry case file (.toml or .json) with tagged polygons and arcs
+        #[arg(long)]
+        case: Option<PathBuf>,
+        /// Start from the final fields of this stored run (its grid size is used)
+        #[arg(long)]
+        from: Option<i32>,
+        /// Checkpoint file, written periodically and on SIGINT/SIGTERM [default: run_<id>.ckpt]
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+        /// Steps between checkpoints (0 = only when interrupted)
+        #[arg(long, default_value_t = 1000)]
+        checkpoint_interval: usize,
+        /// Solve for a solute concentration with this Lewis number (thermosolutal convection)
+        #[arg(long)]
+        lewis: Option<f64>,
+        /// Solutal to thermal buoyancy ratio N, negative when the solute opposes heat
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        buoyancy_ratio: f64,
+        /// Extra concentration wall condition `wall:start:end:bc`, bc = fixed=<C> | adiabatic (impermeable)
+        #[arg(long = "conc-bc")]
+        concentration_segments: Vec<boundary::BoundarySegment>,
+        /// Seed the initial state: field:kind[:amp=..,..], field = vort | temp | conc,
+        /// kind = random (seed) | fourier (kx, ky) | blobs (count, radius, seed)
+        #[arg(long)]
+        perturb: Option<perturbation::Perturbation>,
+        /// Melt material (silicon, germanium, gaas, sapphire or a .toml file); Pr and Ra
+        /// are then computed from the dimensional inputs below instead of --prandtl/--rayleigh
+        #[arg(long)]
+        material: Option<String>,
+        /// Crucible radius [m]
+        #[arg(long, default_value_t = 0.1, requires = "material")]
+        crucible_radius: f64,
+        /// Melt height [m], the length scale of the simulated square
+        #[arg(long, default_value_t = 0.05, requires = "material")]
+        melt_height: f64,
+        /// Crystal radius [m]
+        #[arg(long, default_value_t = 0.05, requires = "material")]
+        crystal_radius: f64,
+        /// Temperature difference across the melt [K]
+        #[arg(long, default_value_t = 10.0, requires = "material")]
+        delta_t: f64,
+        /// Crystal rotation rate [rpm]
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true, requires = "material")]
+        crystal_rpm: f64,
+        /// Crucible rotation rate [rpm]
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true, requires = "material")]
+        crucible_rpm: f64,
+        /// Applied magnetic field [T]
+        #[arg(long, default_value_t = 0.0, requires = "material")]
+        magnetic_field: f64,
+        /// Steps between diagnostics samples (Nusselt numbers, energy, extrema); 0 disables them
+        #[arg(long, default_value_t = 100)]
+        diagnostics_interval: usize,
+        /// Steps between checks for non-finite or runaway fields; 0 disables the watchdog
+        #[arg(long, default_value_t = 1)]
+        watchdog_interval: usize,
+        /// Named probe `name:x,y` sampling T, u, v and vorticity, with 0 <= x <= width and
+        /// 0 <= y <= 1 as for obstacles (repeatable)
+        #[arg(long = "probe")]
+        probes: Vec<probes::Probe>,
+        /// Monitor line `name:x0,y0:x1,y1[:points]`, `vertical-centerline`, `horizontal-centerline` or `surface[:height]`, at the melt surface by default (repeatable)
+        #[arg(long = "line")]
+        lines: Vec<probes::MonitorLine>,
+        /// Steps between probe and line samples
+        #[arg(long, default_value_t = 100)]
+        probe_interval: usize,
+        /// Volumetric heat source `shape:q[@schedule]`, shape = uniform | region:<region> | rect:x0,y0,x1,y1 |
+        /// disk:cx,cy,r | gauss:cx,cy,radius, schedule = ramp=<time> | sine=<period>,<amplitude> (repeatable)
+        #[arg(long = "heat-source")]
+        heat_sources: Vec<heating::HeatSource>,
+        /// Induction coil turn `r,z` around the axis at the domain centre, in melt heights (repeatable)
+        #[arg(long = "coil")]
+        coils: Vec<induction::Coil>,
+        /// Coil frequency as the shielding parameter ω μ₀ σ_melt H² = 2 (H/δ)²
+        #[arg(long, default_value_t = 50.0)]
+        coil_frequency: f64,
+        /// Coil current per turn over √(σ_melt H² k_melt ΔT)
+        #[arg(long, default_value_t = 1.0)]
+        coil_current: f64,
+        /// Solve the purge gas flow in the gas region (needs --multi-region or gas obstacles)
+        #[arg(long)]
+        gas_flow: bool,
+        /// Gas inlet `wall:start:end` on the outer frame, as fractions of the wall (repeatable)
+        #[arg(long = "gas-inlet")]
+        gas_inlets: Vec<gas::GasPort>,
+        /// Gas outlet `wall:start:end`, sharing the inflow of its gas region evenly (repeatable)
+        #[arg(long = "gas-outlet")]
+        gas_outlets: Vec<gas::GasPort>,
+        /// Gas speed at the inlets, in units of α_melt / H
+        #[arg(long, default_value_t = 200.0)]
+        gas_inflow: f64,
+        /// Gas temperature at the inlets
+        #[arg(long, default_value_t = 0.0)]
+        gas_inlet_temperature: f64,
+        /// Gas kinematic viscosity over the melt's thermal diffusivity
+        #[arg(long, default_value_t = 10.0)]
+        gas_viscosity: f64,
+    },
+    /// Show the built-in melt property sets, or one material
+    Materials {
+        /// Built-in name or .toml file
+        material: Option<String>,
+    },
+    /// Continue an interrupted run from its checkpoint file
+    Resume {
+        #[arg(short, long)]
+        checkpoint: PathBuf,
+        /// Steps between checkpoints (0 = only when interrupted)
+        #[arg(long, default_value_t = 1000)]
+        checkpoint_interval: usize,
+    },
+    /// Leading eigenvalues and eigenmodes of the linearized equations around a steady state
+    Stability {
+        /// Stored run whose final state and parameters are the base state
+        #[arg(short, long)]
+        id: Option<i32>,
+        /// Checkpoint whose state and full set of parameters are the base state
+        #[arg(long, conflicts_with = "id")]
+        checkpoint: Option<PathBuf>,
+        /// Time horizon of the linearized propagator the Arnoldi iteration is run on
+        #[arg(long, default_value_t = 0.02)]
+        horizon: f64,
+        /// Arnoldi basis size
+        #[arg(long, default_value_t = 40)]
+        krylov: usize,
+        /// Number of leading eigenvalues to report
+        #[arg(long, default_value_t = 6)]
+        modes: usize,
+        /// Seed of the Arnoldi start vector
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+    /// Follow steady solutions in a parameter with pseudo-arclength continuation
+    Continuation {
+        /// Stored run whose final state and parameters start the branch
+        #[arg(short, long)]
+        id: Option<i32>,
+        /// Checkpoint whose state and full set of parameters start the branch
+        #[arg(long, conflicts_with = "id")]
+        checkpoint: Option<PathBuf>,
+        /// Grid size when starting from rest (no run or checkpoint given)
+        #[arg(short, long, default_value_t = 21)]
+        grid_size: usize,
+        /// Prandtl number when starting from rest
+        #[arg(long, default_value_t = 0.71)]
+        prandtl: f64,
+        /// Rayleigh number when starting from rest
+        #[arg(long, default_value_t = 1000.0)]
+        rayleigh: f64,
+        /// Continuation parameter: ra | pr; there is no re or ma, as the model has no rotation
+        /// and no thermocapillary surface
+        #[arg(long, default_value = "ra")]
+        parameter: continuation::ContinuationParameter,
+        /// Parameter value to continue to
+        #[arg(long)]
+        to: f64,
+        /// Initial arclength step, in units of the starting parameter value
+        #[arg(long, default_value_t = 0.5)]
+        ds: f64,
+        /// Largest arclength step
+        #[arg(long, default_value_t = 20.0)]
+        ds_max: f64,
+        #[arg(long, default_value_t = 50)]
+        max_points: usize,
+        /// Compute the leading eigenvalue at every point to detect bifurcations
+        #[arg(long)]
+        eigenvalues: bool,
+        /// Wall whose mean Nusselt number is recorded
+        #[arg(long, default_value = "top")]
+        nusselt_wall: boundary::Wall,
+    },
+    /// Propagate input uncertainty through an ensemble of runs (Monte Carlo or Latin hypercube)
+    Uq {
+        #[arg(short, long, default_value = "UQ ensemble")]
+        description: String,
+        #[arg(short, long, default_value_t = 21)]
+        grid_size: usize,
+        /// Steps every member is run for
+        #[arg(short, long, default_value_t = 1000)]
+        steps: usize,
+        /// Prandtl number of members where it is not uncertain
+        #[arg(long, default_value_t = 0.71)]
+        prandtl: f64,
+        /// Rayleigh number of members where it is not uncertain
+        #[arg(long, default_value_t = 10000.0)]
+        rayleigh: f64,
+        /// Simulate crystal, crucible and susceptor as conducting regions around the melt
+        #[arg(long)]
+        multi_region: bool,
+        /// Uncertain input `param=dist`, param = pr | ra | t-<wall>, dist = normal:<mean>,<sd>[%]
+        /// | uniform:<lo>,<hi> | lognormal:<mu>,<sigma> (repeatable); there is no ma, as the
+        /// model has no thermocapillary surface
+        #[arg(long = "input", required = true)]
+        inputs: Vec<uq::UncertainInput>,
+        /// Sampling method: mc | lhs
+        #[arg(long, default_value = "lhs")]
+        method: uq::Sampling,
+        /// Number of ensemble members
+        #[arg(long, default_value_t = 32)]
+        samples: usize,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Members run at once (default: one per available core)
+        #[arg(long)]
+        jobs: Option<usize>,
+    },
+    /// Check the solver against exact and published reference results
+    Validate {
+        /// Validation case: double-diffusive | de-vahl-davis[:<Ra>], the differentially heated
+        /// cavity at Ra = 1e3, 1e4, 1e5 and 1e6 (Ra = 1e6 takes a few minutes) | rayleigh-benard,
+        /// the onset of convection in a periodic layer heated from below
+        #[arg(short, long, default_value = "de-vahl-davis")]
+        case: validation::ValidationCase,
+    },
+    /// Observed orders of accuracy in space and time from a manufactured solution
+    Verify {
+        /// Manufactured solution: steady | unsteady
+        #[arg(short, long, default_value = "unsteady")]
+        solution: verification::ManufacturedSolution,
+        /// Grid sizes of the spatial study, coarsest first (h halves between them)
+        #[arg(long, value_delimiter = ',', default_value = "11,21,41,81")]
+        grids: Vec<usize>,
+        /// Grid size of the temporal study
+        #[arg(long, default_value_t = 17)]
+        time_grid: usize,
+        /// Step counts of the temporal study, fewest first (must be stable on the grid)
+        #[arg(long, value_delimiter = ',', default_value = "64,128,256,512")]
+        time_steps: Vec<usize>,
+        /// Final time of every run
+        #[arg(long, default_value_t = 0.05)]
+        t_end: f64,
+    },
+    /// Print and plot the diagnostics time series of a run
+    Diagnostics {
+        #[arg(short, long)]
+        id: i32,
+    },
+    /// Print the latest probe values of a run, or export its probe time series
+    Probes {
+        #[arg(short, long)]
+        id: i32,
+        /// Write all samples to this CSV file
+        #[arg(long)]
+        csv: Option<PathBuf>,
     },
     /// List all previous simulation runs
     List,
+    /// Show the runs a run was restarted from and the runs restarted from it
+    Lineage {
+        #[arg(short, long)]
+        id: i32,
+    },
     /// Query a past simulation and generate a visualization
     Query {
         #[arg(short, long)]
@@ -41,48 +339,537 @@ enum Commands {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let pool = db::establish_connection_pool();
 
     match &cli.command {
-        Commands::Run { description, grid_size, steps, prandtl, rayleigh } => {
+        Commands::Run {
+            description,
+            grid_size,
+            steps,
+            width,
+            periodic,
+            prandtl,
+            rayleigh,
+            boundary_segments,
+            radiation_number,
+            radiation_offset,
+            surface_to_surface,
+            axisymmetric_radiation,
+            melt_surface_rad,
+            multi_region,
+            pull_rate,
+            density_ratio,
+            crystal_length,
+            obstacles,
+            curved_bottom,
+            immersed_crystal,
+            heat_shield,
+            case,
+            from,
+            checkpoint,
+            checkpoint_interval,
+            lewis,
+            buoyancy_ratio,
+            concentration_segments,
+            perturb,
+            material,
+            crucible_radius,
+            melt_height,
+            crystal_radius,
+            delta_t,
+            crystal_rpm,
+            crucible_rpm,
+            magnetic_field,
+            diagnostics_interval,
+            watchdog_interval,
+            probes,
+            lines,
+            probe_interval,
+            heat_sources,
+            coils,
+            coil_frequency,
+            coil_current,
+            gas_flow,
+            gas_inlets,
+            gas_outlets,
+            gas_inflow,
+            gas_inlet_temperature,
+            gas_viscosity,
+        } => {
+            let pool = db::establish_connection_pool()?;
+            if pull_rate.is_some() && !multi_region {
+                anyhow::bail!("--pull-rate needs --multi-region");
+            }
+            if crystal_length.is_some() && !multi_region {
+                anyhow::bail!("--crystal-length needs --multi-region");
+            }
+            if *heat_shield && !multi_region {
+                anyhow::bail!("--heat-shield needs --multi-region");
+            }
+            let solutal = match lewis {
+                Some(lewis) => {
+                    let mut walls = boundary::ThermalBoundary::default();
+                    walls.segments.extend(concentration_segments.iter().cloned());
+                    Some(solutal::SolutalParameters::new(*lewis, *buoyancy_ratio, walls)?)
+                }
+                None if !concentration_segments.is_empty() => anyhow::bail!("--conc-bc needs --lewis"),
+                None => None,
+            };
+
+            let process = match material {
+                Some(material) => {
+                    let material = materials::MaterialProperties::resolve(material)?;
+                    let inputs = materials::ProcessInputs {
+                        crucible_radius: *crucible_radius,
+                        melt_height: *melt_height,
+                        crystal_radius: *crystal_radius,
+                        delta_t: *delta_t,
+                        crystal_rotation: *crystal_rpm,
+                        crucible_rotation: *crucible_rpm,
+                        magnetic_field: *magnetic_field,
+                        gravity: 9.81,
+                    };
+                    let groups = materials::DimensionlessGroups::new(&material, &inputs);
+                    groups.print(&material);
+                    Some((material, inputs, groups))
+                }
+                None => None,
+            };
+            let (prandtl, rayleigh) = match &process {
+                Some((_, _, groups)) => (&groups.prandtl, &groups.rayleigh),
+                None => (prandtl, rayleigh),
+            };
+
+            let induction = match coils.is_empty() {
+                true => None,
+                false => Some(induction::InductionParameters::new(*coil_frequency, *coil_current, coils.clone())?),
+            };
+
+            let gas = match gas_flow {
+                true => Some(gas::GasParameters::new(gas_inlets.clone(), gas_outlets.clone(), *gas_inflow, *gas_inlet_temperature, *gas_viscosity)?),
+                false if !gas_inlets.is_empty() || !gas_outlets.is_empty() => anyhow::bail!("--gas-inlet and --gas-outlet need --gas-flow"),
+                false => None,
+            };
+
             println!("Starting new simulation...");
 
-            // 1. Create a record for this simulation run
+            // A continuation runs on the grid and domain of its parent run
+            let (grid_size, domain) = match from {
+                Some(parent) => {
+                    let parent_run = db::get_simulation_run(&pool, *parent)?;
+                    if parent_run.status != "completed" {
+                        anyhow::bail!("run {} is {} and has no final state to restart from", parent, parent_run.status);
+                    }
+                    let domain = simulation::Domain { width: parent_run.domain_width, periodic: parent_run.periodic };
+                    (parent_run.grid_size as usize, domain)
+                }
+                None => {
+                    anyhow::ensure!(*width > 0.0, "--width must be positive");
+                    (*grid_size, simulation::Domain { width: *width, periodic: *periodic })
+                }
+            };
+            if induction.is_some() && domain.periodic {
+                anyhow::bail!("induction heating needs an axisymmetric domain, not periodic sides");
+            }
+            if *axisymmetric_radiation && domain.periodic {
+                anyhow::bail!("axisymmetric radiation needs an axisymmetric domain, not periodic sides");
+            }
+            if gas.is_some() && domain.periodic {
+                anyhow::bail!("the purge gas flow needs walls at the sides, not periodic ones");
+            }
+
+            // 1. Setup the simulation; a case or geometry that cannot run leaves no run record
+            let case = case.as_deref().map(case_file::CaseFile::load).transpose()?;
+            let mut thermal = boundary::ThermalBoundary::default();
+            if let Some(case) = &case {
+                println!("Using geometry case '{}'", case.name.as_deref().unwrap_or("unnamed"));
+                thermal.segments.extend(case.boundary_segments(domain.width));
+            }
+            thermal.segments.extend(boundary_segments.iter().cloned());
+            thermal.radiation.radiation_number = *radiation_number;
+            thermal.radiation.t_offset = *radiation_offset;
+            thermal.radiation.surface_to_surface = *surface_to_surface;
+            thermal.radiation.axisymmetric = *axisymmetric_radiation;
+            thermal.radiation.melt_surface = *melt_surface_rad;
+
+            let layout = multi_region.then(|| regions::RegionLayout { crystal_length: *crystal_length, ..regions::RegionLayout::default() });
+            let parts = regions::FurnaceParts { curved_bottom: *curved_bottom, immersed_crystal: *immersed_crystal, heat_shield: *heat_shield };
+            let mut shapes = parts.obstacles(layout.as_ref(), domain.width);
+            if let Some(case) = &case {
+                shapes.extend(case.obstacles(domain.width));
+            }
+            shapes.extend(obstacles.iter().cloned());
+
+            let params = simulation::SimParameters {
+                thermal,
+                layout,
+                growth: pull_rate.map(|pull_rate| growth::GrowthParameters {
+                    pull_rate,
+                    density_ratio: *density_ratio,
+                    log_interval: 100,
+                }),
+                obstacles: shapes,
+                solutal,
+                heat_sources: heat_sources.clone(),
+                induction,
+                gas,
+                ..simulation::SimParameters::on_domain(grid_size, *prandtl, *rayleigh, domain)
+            };
+            params.validate()?;
+            let mut sim = simulation::Simulation::new(params);
+            sim.check_geometry()?;
+            if sim.time_step() < sim.params.dt {
+                println!("Time step lowered to {:e} for the gas flow", sim.time_step());
+            }
+            sim.print_induction();
+            let mut probes = probes.clone();
+            let mut lines = lines.clone();
+            if let Some(case) = &case {
+                probes.extend(case.probes.iter().cloned());
+                lines.extend(case.lines.iter().cloned());
+            }
+            if !probes.is_empty() || !lines.is_empty() {
+                sim.probes = Some(probes::ProbeSet::new(*probe_interval, &probes, &lines, &sim.params)?);
+            }
+            if let Some(parent) = from {
+                println!("Restarting from the final state of run {}", parent);
+                sim.restart_from(db::get_simulation_results(&pool, *parent)?)?;
+            }
+            if let Some(perturbation) = perturb {
+                println!("Perturbing the initial state with {}", perturbation);
+                sim.perturb(perturbation);
+            }
+
+            // 2. Create a record for this simulation run
             let run = db::create_simulation_run(
                 &pool,
                 description,
-                *grid_size as i32,
+                grid_size as i32,
                 *steps as i32,
                 *prandtl,
                 *rayleigh,
+                *from,
             )?;
             println!("Created simulation run with ID: {}", run.id);
+            db::set_run_status(&pool, run.id, "running", None)?;
+            db::set_run_domain(&pool, run.id, &domain)?;
+            if let Some((material, inputs, groups)) = &process {
+                db::set_run_process(&pool, run.id, material, inputs, groups)?;
+            }
+            if !sim.params.heat_sources.is_empty() || sim.params.induction.is_some() {
+                db::set_run_heating(&pool, run.id, &sim.params.heat_sources, sim.params.induction.as_ref(), sim.joule_power())?;
+            }
+            if let Some(gas) = &sim.params.gas {
+                println!("Purge gas flow: inflow {}, {}", gas.inflow, gas.ports());
+                db::set_run_gas_flow(&pool, run.id, gas)?;
+            }
+            db::set_run_parameters(&pool, run.id, &sim.params)?;
+            if let Some(perturbation) = perturb {
+                db::set_run_perturbation(&pool, run.id, perturbation)?;
+            }
 
-            // 2. Setup and run the simulation
-            let params = simulation::SimParameters {
-                nx: *grid_size,
-                ny: *grid_size,
-                dt: 0.0001, // A small, stable time step
-                pr: *prandtl,
-                ra: *rayleigh,
+            // 3. Run it
+            if *diagnostics_interval > 0 {
+                sim.diagnostics = Some(diagnostics::DiagnosticsLog { interval: *diagnostics_interval, history: Vec::new() });
+            }
+            sim.checkpoint = Some(simulation::CheckpointSettings {
+                path: checkpoint.clone().unwrap_or_else(|| PathBuf::from(format!("run_{}.ckpt", run.id))),
+                interval: *checkpoint_interval,
+                run_id: Some(run.id),
+            });
+            sim.watchdog.interval = *watchdog_interval;
+            sim.watchdog.dump_prefix = Some(format!("run_{}_blowup", run.id));
+            checkpoint::install_signal_handler()?;
+            match sim.run_with_progress(*steps, print_progress)? {
+                simulation::RunOutcome::Completed => {}
+                simulation::RunOutcome::Interrupted => {
+                    print_checkpoint(&sim);
+                    db::set_run_status(&pool, run.id, "interrupted", None)?;
+                    println!("Run {} interrupted; continue it with the resume command.", run.id);
+                    return Ok(());
+                }
+                simulation::RunOutcome::BlewUp(report) => return fail_run(&pool, run.id, &report),
+            }
+
+            finish_run(&pool, run.id, &sim)?;
+        }
+        Commands::Resume { checkpoint, checkpoint_interval } => {
+            let pool = db::establish_connection_pool()?;
+            let ckpt = checkpoint::Checkpoint::load(checkpoint)?;
+            let run_id = ckpt.meta.run_id.ok_or_else(|| anyhow::anyhow!("checkpoint has no run id"))?;
+            let remaining = ckpt.meta.target_steps.saturating_sub(ckpt.meta.steps_taken);
+            println!(
+                "Resuming run {} at step {} (t = {:.6}), {} steps to go...",
+                run_id, ckpt.meta.steps_taken, ckpt.meta.time, remaining
+            );
+
+            let mut sim = simulation::Simulation::from_checkpoint(ckpt);
+            sim.checkpoint = Some(simulation::CheckpointSettings {
+                path: checkpoint.clone(),
+                interval: *checkpoint_interval,
+                run_id: Some(run_id),
+            });
+            db::set_run_status(&pool, run_id, "running", None)?;
+            checkpoint::install_signal_handler()?;
+            match sim.run_with_progress(remaining, print_progress)? {
+                simulation::RunOutcome::Completed => {}
+                simulation::RunOutcome::Interrupted => {
+                    print_checkpoint(&sim);
+                    db::set_run_status(&pool, run_id, "interrupted", None)?;
+                    println!("Run {} interrupted again; resume it from the same checkpoint.", run_id);
+                    return Ok(());
+                }
+                simulation::RunOutcome::BlewUp(report) => return fail_run(&pool, run_id, &report),
+            }
+
+            finish_run(&pool, run_id, &sim)?;
+        }
+        Commands::Stability { id, checkpoint, horizon, krylov, modes, seed } => {
+            // The database is only needed for a stored base state
+            let pool = id.map(|_| db::establish_connection_pool()).transpose()?;
+            let Some((sim, source)) = load_base_state(pool.as_ref(), *id, checkpoint.as_deref())? else {
+                anyhow::bail!("give a stored run with --id or a --checkpoint");
             };
-            let mut sim = simulation::Simulation::new(params);
-            sim.run(*steps);
+            let label = source.map_or("checkpoint".to_string(), |id| format!("run_{}", id));
+            println!("Linear stability at Pr = {}, Ra = {:e}", sim.params.pr, sim.params.ra);
 
-            // 3. Save the results to the database
-            println!("Saving results to database...");
-            db::save_simulation_results(&pool, run.id, &sim.state)?;
-            println!("Results saved successfully.");
+            let mut op = stability::SteadyOperator::new(sim);
+            let x0 = op.state();
+            op.set_base(&x0);
+            let steadiness = op.residual(&x0).iter().fold(0.0f64, |m, v| m.max(v.abs()));
+            println!("Base state: {} unknowns, max |d/dt| = {:.3e}", op.len(), steadiness);
+            if steadiness > 1e-3 {
+                println!("Warning: the base state is not converged; eigenvalues describe a transient");
+            }
 
-            // 4. Generate a visualization
-            let output_file = format!("run_{}_temp.png", run.id);
-            visualization::draw_temperature_map(&sim.state, &output_file)?;
+            let settings = stability::StabilitySettings { horizon: *horizon, krylov_dim: *krylov, modes: *modes, seed: *seed };
+            let eigenmodes = stability::leading_modes(&mut op, &settings);
+
+            println!("{:<5} | {:<14} | {:<14} | {:<12} | {:<10}", "Mode", "Growth rate", "Ang. freq.", "Frequency", "Residual");
+            println!("{}", "-".repeat(66));
+            for (k, mode) in eigenmodes.iter().enumerate() {
+                println!(
+                    "{:<5} | {:<14.6e} | {:<14.6e} | {:<12.4e} | {:<10.2e}",
+                    k, mode.growth_rate, mode.angular_frequency, mode.frequency(), mode.residual
+                );
+            }
+            if let Some(leading) = eigenmodes.first() {
+                let verdict = if leading.growth_rate > 0.0 { "unstable" } else { "linearly stable" };
+                let kind = if leading.angular_frequency > 0.0 { "oscillatory" } else { "stationary" };
+                println!("Leading mode is {} ({}).", kind, verdict);
+
+                // Rotate the phase so the largest component is real, then plot the real part
+                let peak = leading.vector.iter().copied().max_by(|a, b| a.norm().total_cmp(&b.norm())).unwrap_or_default();
+                let phase = if peak.norm() > 0.0 { peak.conj() / peak.norm() } else { peak };
+                let real: Vec<f64> = leading.vector.iter().map(|v| (v * phase).re).collect();
+                let (temp, vort, stream) = op.fields(&real);
+                visualization::draw_field_map(&temp, "Leading Mode: Temperature", &format!("stability_{}_mode_temp.png", label))?;
+                visualization::draw_field_map(&vort, "Leading Mode: Vorticity", &format!("stability_{}_mode_vort.png", label))?;
+                visualization::draw_field_map(&stream, "Leading Mode: Stream Function", &format!("stability_{}_mode_stream.png", label))?;
+            }
+        }
+        Commands::Continuation {
+            id,
+            checkpoint,
+            grid_size,
+            prandtl,
+            rayleigh,
+            parameter,
+            to,
+            ds,
+            ds_max,
+            max_points,
+            eigenvalues,
+            nusselt_wall,
+        } => {
+            let pool = db::establish_connection_pool()?;
+            let (sim, source) = match load_base_state(Some(&pool), *id, checkpoint.as_deref())? {
+                Some(base) => base,
+                None => {
+                    let params = simulation::SimParameters::cavity(*grid_size, *prandtl, *rayleigh);
+                    params.validate()?;
+                    (simulation::Simulation::new(params), None)
+                }
+            };
+            let start = parameter.get(&sim.params);
+            let run = db::create_simulation_run(
+                &pool,
+                &format!("Continuation in {} from {:e} to {:e}", parameter.name(), start, to),
+                sim.params.ny as i32,
+                0,
+                sim.params.pr,
+                sim.params.ra,
+                source,
+            )?;
+            println!("Created continuation run with ID: {}", run.id);
+            db::set_run_domain(&pool, run.id, &sim.params.domain)?;
+
+            let settings = continuation::ContinuationSettings {
+                parameter: *parameter,
+                target: *to,
+                ds: *ds,
+                ds_min: 1e-4,
+                ds_max: *ds_max,
+                max_points: *max_points,
+                newton_tol: 1e-8,
+                max_newton: 8,
+                nusselt_wall: *nusselt_wall,
+                eigenvalues: eigenvalues.then_some(stability::StabilitySettings { horizon: 0.02, krylov_dim: 20, modes: 3, seed: 1 }),
+            };
+            let mut cont = continuation::Continuation::new(stability::SteadyOperator::new(sim), settings);
+            let name = parameter.name();
+            let outcome = cont.run(|event| match event {
+                continuation::ContinuationEvent::Started { newton } => println!("Converged starting point in {} Newton iterations", newton),
+                continuation::ContinuationEvent::Retry { ds } => println!("Corrector failed, retrying with ds = {:.3e}", ds),
+                continuation::ContinuationEvent::Point { point, newton } => {
+                    let growth = point.growth_rate.map_or(String::new(), |sigma| format!("σ = {:.4e}", sigma));
+                    println!(
+                        "Point {:>3}: {} = {:<12.5e} Nu = {:<10.5} |ψ|max = {:<10.5} Newton {} {}",
+                        point.index, name, point.parameter, point.nusselt, point.max_stream, newton, growth
+                    );
+                    if point.kind != continuation::PointKind::Regular {
+                        println!("  {} detected near {} = {:e}", point.kind.as_str(), name, point.parameter);
+                    }
+                }
+            });
+            if let Err(e) = outcome {
+                println!("Continuation stopped early: {}", e);
+            }
+
+            println!("Saving {} branch points...", cont.points.len());
+            db::save_continuation_points(&pool, run.id, parameter.name(), &cont.points)?;
+            db::set_run_parameters(&pool, run.id, &cont.op.sim.params)?;
+            db::save_simulation_results(&pool, run.id, &cont.op.sim.state)?;
+            if cont.points.len() > 1 {
+                visualization::draw_bifurcation_diagram(&cont.points, parameter.name(), &format!("continuation_{}_nu.png", run.id))?;
+            }
+            visualization::draw_temperature_map(&cont.op.sim.state, &format!("run_{}_temp.png", run.id))?;
+        }
+        Commands::Uq {
+            description,
+            grid_size,
+            steps,
+            prandtl,
+            rayleigh,
+            multi_region,
+            inputs,
+            method,
+            samples,
+            seed,
+            jobs,
+        } => {
+            let pool = db::establish_connection_pool()?;
+            let mut base = simulation::SimParameters::cavity(*grid_size, *prandtl, *rayleigh);
+            base.layout = multi_region.then(regions::RegionLayout::default);
+            base.validate()?;
+            let study = uq::Study {
+                description: description.clone(),
+                base,
+                inputs: inputs.clone(),
+                method: *method,
+                samples: *samples,
+                seed: *seed,
+                steps: *steps,
+            };
+
+            // 1. One run record per member, grouped under the ensemble
+            let stored = study.record(&pool)?;
+            println!("Created ensemble with ID: {} ({} members, {} sampling)", stored.ensemble_id, samples, method.name());
+
+            // 2. Run the members in parallel and store the completed ones and the statistics
+            let jobs = jobs.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+            let dt = stored.members.first().map_or(0.0, |params| params.dt);
+            println!("Running {} members for {} steps of dt = {:.3e} on {} threads...", samples, steps, dt, jobs);
+            let results = stored.run(&pool, jobs, |m, done, outcome| match outcome {
+                Ok(_) => println!("  member {} finished ({}/{})", m, done, samples),
+                Err(reason) => println!("  member {} failed: {} ({}/{})", m, reason, done, samples),
+            })?;
+            let csv = PathBuf::from(format!("uq_{}_members.csv", stored.ensemble_id));
+            uq::write_members_csv(inputs, &stored.values, &results.finals, &csv)?;
+            println!("Member inputs and outputs written to {}", csv.display());
+            if results.failed > 0 {
+                println!("{} of {} members failed and are left out of the statistics", results.failed, samples);
+            }
+            let Some(statistics) = &results.statistics else {
+                anyhow::bail!("fewer than two members completed; no statistics for ensemble {}", stored.ensemble_id);
+            };
+
+            // 3. Report the statistics of the scalar outputs and plot those of the fields
+            println!("Statistics over {} completed members ({} failed):", samples - results.failed, results.failed);
+            uq::print_statistics(&statistics.scalars);
+            let id = stored.ensemble_id;
+            for field in &statistics.fields {
+                let file = field.name.replace(' ', "_");
+                visualization::draw_field_map(&field.mean, &format!("Mean {}", field.name), &format!("uq_{}_{}_mean.png", id, file))?;
+                visualization::draw_field_map(&field.std, &format!("Std. dev. {}", field.name), &format!("uq_{}_{}_std.png", id, file))?;
+            }
+            let [temp, ..] = &statistics.fields;
+            visualization::draw_field_map(&temp.p05, "5th percentile temperature", &format!("uq_{}_temperature_p05.png", id))?;
+            visualization::draw_field_map(&temp.p95, "95th percentile temperature", &format!("uq_{}_temperature_p95.png", id))?;
+        }
+        Commands::Validate { case } => {
+            println!("Running validation case {:?}...", case);
+            let checks = validation::run(*case);
+            anyhow::ensure!(!checks.is_empty(), "validation case {:?} made no checks", case);
+            validation::print_report(&checks);
+            let failed = checks.iter().filter(|c| !c.passed()).count();
+            if failed > 0 {
+                anyhow::bail!("{} of {} validation checks failed", failed, checks.len());
+            }
+            println!("All {} checks passed.", checks.len());
+        }
+        Commands::Materials { material } => {
+            let materials = match material {
+                Some(material) => vec![materials::MaterialProperties::resolve(material)?],
+                None => materials::BUILTIN_MATERIALS.iter().filter_map(|name| materials::MaterialProperties::builtin(name)).collect(),
+            };
+            materials::print_properties(&materials);
+        }
+        Commands::Verify { solution, grids, time_grid, time_steps, t_end } => {
+            anyhow::ensure!(grids.len() >= 2 && time_steps.len() >= 2, "each study needs at least two resolutions");
+            let studies = [
+                verification::space_study(solution, grids, *t_end),
+                verification::time_study(solution, *time_grid, time_steps, *t_end),
+            ];
+            let mut failures = Vec::new();
+            for study in &studies {
+                study.print();
+                failures.extend(study.failures());
+            }
+            if !failures.is_empty() {
+                anyhow::bail!("observed order dropped: {}", failures.join("; "));
+            }
+            println!("All observed orders meet the formal orders of the scheme.");
+        }
+        Commands::Diagnostics { id } => {
+            let pool = db::establish_connection_pool()?;
+            let history = db::get_diagnostics(&pool, *id)?;
+            anyhow::ensure!(!history.is_empty(), "run {} has no diagnostics", id);
+            diagnostics::print_history(&history);
+            visualization::draw_diagnostics(&history, &format!("run_{}_diagnostics.png", id))?;
+        }
+        Commands::Probes { id, csv } => {
+            let pool = db::establish_connection_pool()?;
+            let samples = db::get_probe_samples(&pool, *id)?;
+            anyhow::ensure!(!samples.is_empty(), "run {} has no probe samples", id);
+            match csv {
+                Some(path) => {
+                    probes::write_csv(&samples, path)?;
+                    println!("{} probe samples written to {}", samples.len(), path.display());
+                }
+                None => probes::print_latest(&samples),
+            }
         }
         Commands::List => {
+            let pool = db::establish_connection_pool()?;
             println!("Querying simulation runs from the database...");
-            db::list_simulation_runs(&pool)?;
+            print_runs(&db::load_simulation_runs(&pool)?);
+        }
+        Commands::Lineage { id } => {
+            let pool = db::establish_connection_pool()?;
+            print_lineage(&db::get_run_lineage(&pool, *id)?);
         }
         Commands::Query { id } => {
+            let pool = db::establish_connection_pool()?;
             println!("Querying results for run ID: {}", id);
             let state = db::get_simulation_results(&pool, *id)?;
             println!("Results retrieved. Generating visualization...");
@@ -93,4 +880,187 @@ fn main() -> Result<()> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// Simulation positioned on a stored state, from a checkpoint or from the final fields of a
+// stored run, with the run it came from. Both carry the full set of parameters; runs
+// stored before the parameters were recorded are rebuilt as a default-walled cavity,
+// which is refused when their region map shows another geometry.
+fn load_base_state(
+    pool: Option<&db::DbPool>,
+    id: Option<i32>,
+    checkpoint: Option<&std::path::Path>,
+) -> Result<Option<(simulation::Simulation, Option<i32>)>> {
+    if let Some(path) = checkpoint {
+        let ckpt = checkpoint::Checkpoint::load(path)?;
+        let run_id = ckpt.meta.run_id;
+        return Ok(Some((simulation::Simulation::from_checkpoint(ckpt), run_id)));
+    }
+    let Some(id) = id else {
+        return Ok(None);
+    };
+    let pool = pool.ok_or_else(|| anyhow::anyhow!("loading run {} needs the database", id))?;
+    let run = db::get_simulation_run(pool, id)?;
+    let state = db::get_simulation_results(pool, id)?;
+    let params = match db::get_run_parameters(&run)? {
+        Some(params) => params,
+        None => {
+            if state.region.iter().any(|r| *r != regions::Region::Melt) {
+                anyhow::bail!("run {} was stored without its parameters and has solid regions; start from its checkpoint instead", id);
+            }
+            println!("Warning: run {} was stored without its parameters; assuming a cavity with the default walls", id);
+            let domain = simulation::Domain { width: run.domain_width, periodic: run.periodic };
+            simulation::SimParameters::on_domain(run.grid_size as usize, run.prandtl_number, run.rayleigh_number, domain)
+        }
+    };
+    params.validate()?;
+    let mut sim = simulation::Simulation::new(params);
+    sim.restart_from(state)?;
+    Ok(Some((sim, Some(id))))
+}
+
+// Mark a run that blew up as failed instead of saving its fields.
+fn fail_run(pool: &db::DbPool, run_id: i32, report: &watchdog::BlowUp) -> Result<()> {
+    report.print();
+    db::set_run_status(pool, run_id, "failed", Some(&report.reason))?;
+    anyhow::bail!("run {} blew up at step {}; no results were saved (try a smaller time step or a finer grid)", run_id, report.step)
+}
+
+// Store the final state of a completed run and draw it.
+fn finish_run(pool: &db::DbPool, run_id: i32, sim: &simulation::Simulation) -> Result<()> {
+    // 3. Save the results to the database
+    println!("Saving results to database...");
+    let balance = sim.conservation.latest;
+    let completed = db::CompletedRun {
+        params: &sim.params,
+        state: &sim.state,
+        time: sim.time,
+        growth: sim.growth.as_ref().map(|growth| growth.history.as_slice()),
+        diagnostics: sim.diagnostics.as_ref().map(|log| log.history.as_slice()),
+        probes: sim.probes.as_ref().map(|probes| probes.history.as_slice()),
+        balance: &balance,
+    };
+    db::save_completed_run(pool, run_id, &completed)?;
+    if let Some(growth) = &sim.growth {
+        println!(
+            "Crystal length {:.4}, melt height {:.4} after t = {:.4}",
+            growth.crystal_length,
+            sim.params.layout.as_ref().map_or(0.0, |l| l.melt_height),
+            sim.time
+        );
+    }
+    println!(
+        "Conservation errors: heat {:.2e}, divergence {:.2e}, Poisson {:.2e} (worst {:.2e}, {:.2e}, {:.2e})",
+        balance.heat_imbalance,
+        balance.divergence,
+        balance.poisson_residual,
+        sim.conservation.worst.heat_imbalance,
+        sim.conservation.worst.divergence,
+        sim.conservation.worst.poisson_residual
+    );
+    if balance.flagged() {
+        println!("Warning: run {} is flagged as badly resolved; refine the grid or run longer", run_id);
+    }
+    println!("Results saved successfully.");
+
+    // 4. Generate a visualization
+    let output_file = format!("run_{}_temp.png", run_id);
+    visualization::draw_temperature_map(&sim.state, &output_file)?;
+    visualization::draw_cell_mask(&sim.state, &format!("run_{}_mask.png", run_id))?;
+    if sim.params.solutal.is_some() {
+        visualization::draw_field_map(&sim.state.conc, "Concentration", &format!("run_{}_conc.png", run_id))?;
+    }
+    if let Some(joule) = sim.joule_heating() {
+        visualization::draw_field_map(joule, "Joule heating", &format!("run_{}_joule.png", run_id))?;
+    }
+    if sim.gas.is_some() {
+        visualization::draw_field_map(&sim.state.gas_stream, "Gas stream function", &format!("run_{}_gas_stream.png", run_id))?;
+    }
+    visualization::draw_local_nusselt(&sim.state.temp, &sim.params, &format!("run_{}_nusselt.png", run_id))?;
+    if let Some(log) = sim.diagnostics.as_ref().filter(|log| !log.history.is_empty()) {
+        visualization::draw_diagnostics(&log.history, &format!("run_{}_diagnostics.png", run_id))?;
+    }
+    Ok(())
+}
+
+fn print_progress(step: usize, steps: usize) {
+    println!("Completed step {}/{}", step, steps);
+}
+
+fn print_checkpoint(sim: &simulation::Simulation) {
+    if let Some(settings) = &sim.checkpoint {
+        println!("Checkpoint written to {} at step {}", settings.path.display(), sim.steps_taken);
+    }
+}
+
+fn print_runs(runs: &[models::SimulationRun]) {
+    println!("--- Available Simulation Runs ---");
+    println!(
+        "{:<5} | {:<25} | {:<10} | {:<10} | {:<10} | {:<10} | {:<6} | {:<11}",
+        "ID", "Description", "Grid", "Steps", "Pr", "Ra", "Parent", "Status"
+    );
+    println!("{}", "-".repeat(108));
+    for run in runs {
+        println!(
+            "{:<5} | {:<25} | {:<10} | {:<10} | {:<10.2} | {:<10.1e} | {:<6} | {:<11}",
+            run.id,
+            run.description,
+            run.grid_size,
+            run.time_steps,
+            run.prandtl_number,
+            run.rayleigh_number,
+            run.parent_run_id.map_or("-".to_string(), |p| p.to_string()),
+            run.status
+        );
+    }
+}
+
+// The chain of parent runs a run was restarted from, what is recorded about the run
+// itself, and the runs restarted from it.
+fn print_lineage(lineage: &db::RunLineage) {
+    let run = &lineage.run;
+    println!("--- Lineage of run {} ---", run.id);
+    for parent in &lineage.ancestors {
+        println!(
+            "{:<5} Pr = {:<8.3} Ra = {:<10.2e} {}",
+            parent.id, parent.prandtl_number, parent.rayleigh_number, parent.description
+        );
+    }
+    println!(
+        "{:<5} Pr = {:<8.3} Ra = {:<10.2e} {}  <- this run",
+        run.id, run.prandtl_number, run.rayleigh_number, run.description
+    );
+    if let Some(reason) = &run.failure_reason {
+        println!("      {}: {}", run.status, reason);
+    }
+    if let Some(perturbation) = &run.perturbation {
+        println!("      perturbed with {}", perturbation);
+    }
+    if let (Some(material), Some(radius), Some(height), Some(delta_t)) =
+        (&run.material, run.crucible_radius, run.melt_height, run.delta_t)
+    {
+        println!("      {} melt, R = {} m, H = {} m, dT = {} K", material, radius, height, delta_t);
+    }
+    if let Some(sources) = &run.heat_sources {
+        println!("      heat sources {}", sources);
+    }
+    if let (Some(frequency), Some(current), Some(power)) = (run.coil_frequency, run.coil_current, run.joule_power) {
+        println!("      induction coil, frequency {:e}, current {:e}, Joule heating {:.4e}", frequency, current, power);
+    }
+    if let (Some(inflow), Some(ports)) = (run.gas_inflow, &run.gas_ports) {
+        println!("      purge gas at inflow {}, {}", inflow, ports);
+    }
+    if let (Some(ensemble), Some(sample)) = (run.ensemble_id, &run.ensemble_sample) {
+        println!("      member of ensemble {} with {}", ensemble, sample);
+    }
+    if let (Some(heat), Some(div), Some(res)) = (run.heat_imbalance, run.divergence, run.poisson_residual) {
+        let flag = if run.conservation_flagged == Some(true) { "  (badly resolved)" } else { "" };
+        println!("      conservation errors: heat {:.2e}, divergence {:.2e}, Poisson {:.2e}{}", heat, div, res, flag);
+    }
+    for child in &lineage.children {
+        println!(
+            "  -> {:<5} Pr = {:<8.3} Ra = {:<10.2e} {}",
+            child.id, child.prandtl_number, child.rayleigh_number, child.description
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::regions::Region;
+use crate::simulation::Domain;
+
+// Role of a node in the flow solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellType {
+    Fluid,    // Vorticity, stream function and temperature are all solved
+    Solid,    // Heat conduction only, no flow
+    Boundary, // Solid node next to the fluid; carries the wall vorticity
+}
+
+impl CellType {
+    // Stable numeric id used when storing results.
+    pub fn id(self) -> i16 {
+        match self {
+            CellType::Fluid => 0,
+            CellType::Solid => 1,
+            CellType::Boundary => 2,
+        }
+    }
+
+    pub fn from_id(id: i16) -> Option<Self> {
+        match id {
+            0 => Some(CellType::Fluid),
+            1 => Some(CellType::Solid),
+            2 => Some(CellType::Boundary),
+            _ => None,
+        }
+    }
+}
+
+// Classify every node from the region map. The outer frame is never fluid, and any
+// non-fluid node with a fluid neighbour is a boundary node of the staircase geometry.
+// With periodic x the side columns are ghost copies of the opposite interior columns
+// and take their roles.
+pub fn classify(region: &Array2<Region>, periodic: bool) -> Array2<CellType> {
+    let (ny, nx) = region.dim();
+    let column = |j: usize| match j {
+        0 if periodic => nx - 2,
+        j if periodic && j == nx - 1 => 1,
+        j => j,
+    };
+    let fluid = |i: usize, j: usize| {
+        let j = column(j);
+        i > 0 && j > 0 && i < ny - 1 && j < nx - 1 && region[[i, j]].is_fluid()
+    };
+
+    Array2::from_shape_fn((ny, nx), |(i, j)| {
+        if fluid(i, j) {
+            return CellType::Fluid;
+        }
+        let touches_fluid = (i > 0 && fluid(i - 1, j))
+            || (i + 1 < ny && fluid(i + 1, j))
+            || (j > 0 && fluid(i, j - 1))
+            || (j + 1 < nx && fluid(i, j + 1));
+        if touches_fluid {
+            CellType::Boundary
+        } else {
+            CellType::Solid
+        }
+    })
+}
+
+// Non-fluid nodes cut off from the outer walls. Every solid carries ψ = 0, the value on
+// the walls, which is only right for solids joined to a wall: a floating obstacle would
+// need a circulation of its own. Solids that touch diagonally count as joined. With
+// periodic x only the top and bottom are walls, and the ghost columns are left out.
+pub fn floating_solids(mask: &Array2<CellType>, periodic: bool) -> Vec<(usize, usize)> {
+    let (ny, nx) = mask.dim();
+    let column = |j: usize| match j {
+        0 if periodic => nx - 2,
+        j if periodic && j == nx - 1 => 1,
+        j => j,
+    };
+    let mut reached = Array2::from_elem((ny, nx), false);
+    let mut stack: Vec<(usize, usize)> = mask
+        .indexed_iter()
+        .filter(|((i, j), cell)| {
+            let frame = *i == 0 || *i == ny - 1 || (!periodic && (*j == 0 || *j == nx - 1));
+            frame && **cell != CellType::Fluid
+        })
+        .map(|(node, _)| node)
+        .collect();
+    for &node in &stack {
+        reached[node] = true;
+    }
+    while let Some((i, j)) = stack.pop() {
+        for di in -1..=1isize {
+            for dj in -1..=1isize {
+                let (ii, jj) = (i as isize + di, j as isize + dj);
+                if ii < 0 || jj < 0 || ii >= ny as isize || jj >= nx as isize {
+                    continue;
+                }
+                let node = (ii as usize, column(jj as usize));
+                if mask[node] != CellType::Fluid && !reached[node] {
+                    reached[node] = true;
+                    stack.push(node);
+                }
+            }
+        }
+    }
+    let ghost = |j: usize| periodic && (j == 0 || j == nx - 1);
+    mask.indexed_iter()
+        .filter(|((i, j), cell)| **cell != CellType::Fluid && !reached[[*i, *j]] && !ghost(*j))
+        .map(|(node, _)| node)
+        .collect()
+}
+
+// Geometric primitives in domain coordinates: 0 ≤ x ≤ width, 0 ≤ y ≤ 1.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+    Disk { cx: f64, cy: f64, r: f64 },
+    // Everything below a circular arc through (0, depth), (width / 2, 0) and (width, depth):
+    // the corners of a crucible with a curved bottom.
+    CurvedBottom { depth: f64 },
+    Polygon(Vec<(f64, f64)>), // Closed implicitly, even-odd fill
+}
+
+impl Shape {
+    // Whether (x, y) lies inside the shape in a domain `width` wide.
+    pub fn contains(&self, x: f64, y: f64, width: f64) -> bool {
+        match *self {
+            Shape::Rect { x0, y0, x1, y1 } => x >= x0 && x <= x1 && y >= y0 && y <= y1,
+            Shape::Disk { cx, cy, r } => (x - cx).powi(2) + (y - cy).powi(2) <= r * r,
+            Shape::CurvedBottom { depth } => {
+                let half = 0.5 * width;
+                let yc = (half * half + depth * depth) / (2.0 * depth);
+                y < depth && (x - half).powi(2) + (y - yc).powi(2) > yc * yc
+            }
+            Shape::Polygon(ref points) => {
+                let mut inside = false;
+                let mut prev = match points.last() {
+                    Some(&p) => p,
+                    None => return false,
+                };
+                for &p in points {
+                    if (p.1 > y) != (prev.1 > y) && x < prev.0 + (y - prev.1) * (p.0 - prev.0) / (p.1 - prev.1) {
+                        inside = !inside;
+                    }
+                    prev = p;
+                }
+                inside
+            }
+        }
+    }
+}
+
+// A shape painted into the region map after the layout, e.g. a heat shield or the
+// immersed end of a crystal. Solid obstacles inside the melt must touch a wall, since
+// every solid node carries ψ = 0; see `floating_solids`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Obstacle {
+    pub shape: Shape,
+    pub region: Region,
+}
+
+impl Obstacle {
+    // With periodic x the ghost columns are left to the caller, as for the layout.
+    pub fn paint(&self, region: &mut Array2<Region>, domain: &Domain) {
+        let (ny, nx) = region.dim();
+        let (dx, dy) = domain.spacing(nx, ny);
+        let first = domain.first_column() as f64;
+        // Nodes on the domain walls are sampled just inside it, so shapes that end
+        // exactly on a wall still claim the wall nodes.
+        let inset = 1e-9;
+        for ((i, j), r) in region.indexed_iter_mut() {
+            let x = ((j as f64 - first) * dx).clamp(inset, domain.width - inset);
+            let y = (i as f64 * dy).clamp(inset, 1.0 - inset);
+            if self.shape.contains(x, y, domain.width) {
+                *r = self.region;
+            }
+        }
+    }
+}
+
+// Parses `rect:x0,y0,x1,y1:region`, `disk:cx,cy,r:region` or `bowl:depth:region`.
+impl FromStr for Obstacle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() != 3 {
+            anyhow::bail!("expected shape:values:region, got '{}'", s);
+        }
+        let values = parts[1]
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()?;
+        let shape = match (parts[0], values.as_slice()) {
+            ("rect", &[x0, y0, x1, y1]) => Shape::Rect { x0, y0, x1, y1 },
+            ("disk", &[cx, cy, r]) => Shape::Disk { cx, cy, r },
+            ("bowl", &[depth]) if depth > 0.0 => Shape::CurvedBottom { depth },
+            _ => anyhow::bail!("cannot build shape '{}' from {:?}", parts[0], values),
+        };
+        Ok(Obstacle { shape, region: parts[2].parse()? })
+    }
+}
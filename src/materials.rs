@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Liquid properties at the melting point, SI units. The built-in sets are approximate
+// literature values for process studies, not a substitute for measured data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MaterialProperties {
+    pub name: String,
+    pub density: f64,                  // ρ [kg/m³]
+    pub viscosity: f64,                // μ [Pa s]
+    pub thermal_conductivity: f64,     // k [W/(m K)]
+    pub heat_capacity: f64,            // c_p [J/(kg K)]
+    pub thermal_expansion: f64,        // β [1/K]
+    pub surface_tension_gradient: f64, // dσ/dT [N/(m K)], negative for most melts
+    pub electrical_conductivity: f64,  // σ_e [S/m]
+    pub melting_point: f64,            // [K]
+}
+
+pub const BUILTIN_MATERIALS: [&str; 4] = ["silicon", "germanium", "gaas", "sapphire"];
+
+impl MaterialProperties {
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (name, density, viscosity, k, cp, beta, dsigma, sigma_e, t_melt) = match name {
+            "silicon" | "si" => ("silicon", 2570.0, 8.6e-4, 64.0, 1000.0, 1.4e-4, -2.8e-4, 1.2e6, 1685.0),
+            "germanium" | "ge" => ("germanium", 5600.0, 7.35e-4, 39.0, 380.0, 1.08e-4, -1.1e-4, 1.5e6, 1211.0),
+            "gaas" => ("gaas", 5710.0, 2.8e-3, 17.8, 434.0, 1.9e-4, -1.8e-4, 7.9e5, 1511.0),
+            "sapphire" | "al2o3" => ("sapphire", 3000.0, 5.75e-2, 2.05, 1260.0, 1.8e-5, -3.5e-5, 15.0, 2327.0),
+            _ => return None,
+        };
+        Some(MaterialProperties {
+            name: name.to_string(),
+            density,
+            viscosity,
+            thermal_conductivity: k,
+            heat_capacity: cp,
+            thermal_expansion: beta,
+            surface_tension_gradient: dsigma,
+            electrical_conductivity: sigma_e,
+            melting_point: t_melt,
+        })
+    }
+
+    // A built-in name or the path of a TOML file with the same fields.
+    pub fn resolve(name_or_path: &str) -> Result<Self> {
+        if let Some(material) = Self::builtin(&name_or_path.to_lowercase()) {
+            return Ok(material);
+        }
+        let path = Path::new(name_or_path);
+        anyhow::ensure!(
+            path.extension().and_then(|e| e.to_str()) == Some("toml"),
+            "unknown material '{}' (built in: {}, or a .toml file)",
+            name_or_path,
+            BUILTIN_MATERIALS.join(", ")
+        );
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading material file {}", path.display()))?;
+        Ok(toml::from_str(&text)?)
+    }
+
+    pub fn kinematic_viscosity(&self) -> f64 {
+        self.viscosity / self.density
+    }
+
+    pub fn thermal_diffusivity(&self) -> f64 {
+        self.thermal_conductivity / (self.density * self.heat_capacity)
+    }
+}
+
+pub fn print_properties(materials: &[MaterialProperties]) {
+    println!(
+        "{:<10} | {:<9} | {:<9} | {:<7} | {:<7} | {:<9} | {:<10} | {:<9} | {:<7} | Pr",
+        "Material", "rho", "mu", "k", "c_p", "beta", "dsigma/dT", "sigma_e", "T_m"
+    );
+    println!("{}", "-".repeat(108));
+    for m in materials {
+        println!(
+            "{:<10} | {:<9.1} | {:<9.3e} | {:<7.2} | {:<7.0} | {:<9.2e} | {:<10.2e} | {:<9.2e} | {:<7.0} | {:.4}",
+            m.name,
+            m.density,
+            m.viscosity,
+            m.thermal_conductivity,
+            m.heat_capacity,
+            m.thermal_expansion,
+            m.surface_tension_gradient,
+            m.electrical_conductivity,
+            m.melting_point,
+            m.kinematic_viscosity() / m.thermal_diffusivity()
+        );
+    }
+}
+
+// Dimensional Czochralski process inputs, SI units with rotation rates in rpm.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInputs {
+    pub crucible_radius: f64,   // R [m]
+    pub melt_height: f64,       // H [m], the length scale of the simulated domain
+    pub crystal_radius: f64,    // R_s [m]
+    pub delta_t: f64,           // Crucible wall to melting point difference ΔT [K]
+    pub crystal_rotation: f64,  // Ω_s [rpm]
+    pub crucible_rotation: f64, // Ω_c [rpm]
+    pub magnetic_field: f64,    // B [T]
+    pub gravity: f64,           // g [m/s²]
+}
+
+// Dimensionless groups of a material and process. Each group uses the length of the
+// mechanism it describes, so they do not share one scale: Gr, Ra and Ma the melt height H,
+// which is also the length scale of the simulated domain; Re the crystal radius R_s; Ta
+// and Ha the crucible radius R.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DimensionlessGroups {
+    pub prandtl: f64,   // Pr = ν / α
+    pub grashof: f64,   // Gr = g β ΔT H³ / ν²
+    pub rayleigh: f64,  // Ra = Gr Pr
+    pub marangoni: f64, // Ma = |dσ/dT| ΔT H / (μ α)
+    pub reynolds: f64,  // Re = Ω_s R_s² / ν, crystal rotation
+    pub taylor: f64,    // Ta = (2 Ω_c R² / ν)², crucible rotation
+    pub hartmann: f64,  // Ha = B R √(σ_e / μ), field across the crucible
+}
+
+impl DimensionlessGroups {
+    pub fn new(material: &MaterialProperties, process: &ProcessInputs) -> Self {
+        let nu = material.kinematic_viscosity();
+        let alpha = material.thermal_diffusivity();
+        let rpm = |n: f64| n * 2.0 * std::f64::consts::PI / 60.0;
+        let h = process.melt_height;
+        let r = process.crucible_radius;
+
+        let prandtl = nu / alpha;
+        let grashof = process.gravity * material.thermal_expansion * process.delta_t * h.powi(3) / (nu * nu);
+        DimensionlessGroups {
+            prandtl,
+            grashof,
+            rayleigh: grashof * prandtl,
+            marangoni: material.surface_tension_gradient.abs() * process.delta_t * h / (material.viscosity * alpha),
+            reynolds: rpm(process.crystal_rotation) * process.crystal_radius.powi(2) / nu,
+            taylor: (2.0 * rpm(process.crucible_rotation) * r * r / nu).powi(2),
+            hartmann: process.magnetic_field * r * (material.electrical_conductivity / material.viscosity).sqrt(),
+        }
+    }
+
+    pub fn print(&self, material: &MaterialProperties) {
+        println!("--- Dimensionless groups for {} ---", material.name);
+        println!("Pr = {:.4e}   Gr = {:.4e}   Ra = {:.4e}", self.prandtl, self.grashof, self.rayleigh);
+        println!("Ma = {:.4e}   Re = {:.4e}   Ta = {:.4e}   Ha = {:.4e}", self.marangoni, self.reynolds, self.taylor, self.hartmann);
+    }
+}
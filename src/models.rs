@@ -1,8 +1,9 @@
-use crate::schema::{results, simulation_runs};
+use crate::schema::{continuation_points, diagnostics, ensemble_fields, ensemble_statistics, ensembles, growth_history, probe_samples, results, simulation_runs};
 use diesel::prelude::*;
 use chrono::NaiveDateTime;
+use serde::Serialize;
 
-#[derive(Queryable, Identifiable, Selectable, Debug)]
+#[derive(Queryable, Identifiable, Selectable, Debug, Serialize)]
 #[diesel(table_name = simulation_runs)]
 pub struct SimulationRun {
     pub id: i32,
@@ -12,6 +13,41 @@ pub struct SimulationRun {
     pub prandtl_number: f64,
     pub rayleigh_number: f64,
     pub created_at: NaiveDateTime,
+    pub parent_run_id: Option<i32>,
+    pub perturbation: Option<String>,    // Initial perturbation spec, see perturbation.rs
+    pub perturbation_seed: Option<i64>,
+    pub material: Option<String>,         // Dimensional process, see materials.rs
+    pub crucible_radius: Option<f64>,
+    pub melt_height: Option<f64>,
+    pub crystal_radius: Option<f64>,
+    pub delta_t: Option<f64>,
+    pub crystal_rotation: Option<f64>,
+    pub crucible_rotation: Option<f64>,
+    pub magnetic_field: Option<f64>,
+    pub gravity: Option<f64>,
+    pub grashof_number: Option<f64>,
+    pub marangoni_number: Option<f64>,
+    pub reynolds_number: Option<f64>,
+    pub taylor_number: Option<f64>,
+    pub hartmann_number: Option<f64>,
+    pub heat_imbalance: Option<f64>,      // Final conservation errors, see conservation.rs
+    pub divergence: Option<f64>,
+    pub poisson_residual: Option<f64>,
+    pub conservation_flagged: Option<bool>,
+    pub status: String,                   // running, interrupted, completed or failed
+    pub failure_reason: Option<String>,
+    pub domain_width: f64,                // Width over height, see simulation::Domain
+    pub periodic: bool,
+    pub heat_sources: Option<String>,     // Volumetric source specs, see heating.rs
+    pub coil_frequency: Option<f64>,      // Induction coil, see induction.rs
+    pub coil_current: Option<f64>,
+    pub joule_power: Option<f64>,         // ∫Q dA of the Joule heating at the start
+    pub gas_inflow: Option<f64>,          // Purge gas inlet speed, see gas.rs
+    pub gas_ports: Option<String>,
+    pub ensemble_id: Option<i32>,         // Uncertainty quantification ensemble, see uq.rs
+    pub ensemble_sample: Option<String>,  // The member's input values
+    pub parameters: Option<String>,       // Full SimParameters as JSON, for rebuilding the run
+    pub final_time: Option<f64>,          // Dimensionless time reached by a completed run
 }
 
 #[derive(Insertable)]
@@ -22,6 +58,109 @@ pub struct NewSimulationRun<'a> {
     pub time_steps: i32,
     pub prandtl_number: f64,
     pub rayleigh_number: f64,
+    pub parent_run_id: Option<i32>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = simulation_runs)]
+pub struct RunProcess<'a> {
+    pub material: &'a str,
+    pub crucible_radius: f64,
+    pub melt_height: f64,
+    pub crystal_radius: f64,
+    pub delta_t: f64,
+    pub crystal_rotation: f64,
+    pub crucible_rotation: f64,
+    pub magnetic_field: f64,
+    pub gravity: f64,
+    pub grashof_number: f64,
+    pub marangoni_number: f64,
+    pub reynolds_number: f64,
+    pub taylor_number: f64,
+    pub hartmann_number: f64,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = simulation_runs)]
+pub struct RunConservation {
+    pub heat_imbalance: f64,
+    pub divergence: f64,
+    pub poisson_residual: f64,
+    pub conservation_flagged: bool,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = simulation_runs)]
+pub struct RunHeating {
+    pub heat_sources: Option<String>,
+    pub coil_frequency: Option<f64>,
+    pub coil_current: Option<f64>,
+    pub joule_power: Option<f64>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = simulation_runs)]
+pub struct RunGasFlow {
+    pub gas_inflow: f64,
+    pub gas_ports: String,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = simulation_runs)]
+pub struct RunEnsembleMember {
+    pub ensemble_id: i32,
+    pub ensemble_sample: String,
+}
+
+#[derive(Queryable, Identifiable, Selectable, Debug, Serialize)]
+#[diesel(table_name = ensembles)]
+pub struct Ensemble {
+    pub id: i32,
+    pub description: String,
+    pub method: String, // mc or lhs
+    pub members: i32,
+    pub seed: i64,
+    pub inputs: String, // Input distributions, see uq.rs
+    pub time_steps: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = ensembles)]
+pub struct NewEnsemble<'a> {
+    pub description: &'a str,
+    pub method: &'a str,
+    pub members: i32,
+    pub seed: i64,
+    pub inputs: &'a str,
+    pub time_steps: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = ensemble_statistics)]
+pub struct NewEnsembleStatistic<'a> {
+    pub ensemble_id: i32,
+    pub output: &'a str,
+    pub completed: i32,
+    pub failed: i32,
+    pub mean: f64,
+    pub std: f64,
+    pub p05: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = ensemble_fields)]
+pub struct NewEnsembleFieldPoint<'a> {
+    pub ensemble_id: i32,
+    pub statistic: &'a str,
+    pub x: i32,
+    pub y: i32,
+    pub temperature: f64,
+    pub stream_function: f64,
+    pub u_velocity: f64,
+    pub v_velocity: f64,
 }
 
 #[derive(Insertable)]
@@ -33,4 +172,78 @@ pub struct NewResultPoint {
     pub temperature: f64,
     pub u_velocity: f64,
     pub v_velocity: f64,
-}
\ No newline at end of file
+    pub region: i16,
+    pub cell_type: i16,
+    pub vorticity: Option<f64>,
+    pub stream_function: Option<f64>,
+    pub concentration: Option<f64>,
+    pub gas_vorticity: Option<f64>,
+    pub gas_stream_function: Option<f64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = growth_history)]
+pub struct NewGrowthRecord {
+    pub run_id: i32,
+    pub step: i32,
+    pub sim_time: f64,
+    pub crystal_length: f64,
+    pub melt_height: f64,
+}
+#[derive(Insertable)]
+#[diesel(table_name = continuation_points)]
+pub struct NewContinuationPoint<'a> {
+    pub run_id: i32,
+    pub point_index: i32,
+    pub parameter: &'a str,
+    pub parameter_value: f64,
+    pub nusselt: f64,
+    pub max_stream: f64,
+    pub arclength: f64,
+    pub growth_rate: Option<f64>,
+    pub frequency: Option<f64>,
+    pub kind: &'a str,
+}
+
+#[derive(Insertable, Queryable, Selectable)]
+#[diesel(table_name = diagnostics)]
+pub struct DiagnosticsRecord {
+    pub run_id: i32,
+    pub step: i32,
+    pub sim_time: f64,
+    pub nu_bottom: f64,
+    pub nu_top: f64,
+    pub nu_left: f64,
+    pub nu_right: f64,
+    pub kinetic_energy: f64,
+    pub enstrophy: f64,
+    pub max_stream: f64,
+    pub max_u: f64,
+    pub max_u_x: f64,
+    pub max_u_y: f64,
+    pub max_v: f64,
+    pub max_v_x: f64,
+    pub max_v_y: f64,
+    pub heat_in: f64,
+    pub heat_out: f64,
+    pub storage_rate: f64,
+    pub heat_imbalance: f64,
+    pub divergence: f64,
+    pub poisson_residual: f64,
+    pub heat_generated: f64,
+}
+
+#[derive(Insertable, Queryable, Selectable)]
+#[diesel(table_name = probe_samples)]
+pub struct ProbeRecord {
+    pub run_id: i32,
+    pub probe: String,
+    pub step: i32,
+    pub sim_time: f64,
+    pub x: f64,
+    pub y: f64,
+    pub temperature: f64,
+    pub u_velocity: f64,
+    pub v_velocity: f64,
+    pub vorticity: f64,
+}
@@ -0,0 +1,158 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::rng::Rng;
+use crate::simulation::{wrap_columns, Domain, SimState};
+
+// Field the perturbation is added to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PerturbedField {
+    Vorticity,
+    Temperature,
+    Concentration,
+}
+
+// Shape of the perturbation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PerturbationKind {
+    Random { seed: u64 },                          // Uniform noise in [−1, 1]
+    Fourier { kx: u32, ky: u32 },                  // sin(kx π x / W) sin(ky π y), 2 kx with periodic x
+    Blobs { count: u32, radius: f64, seed: u64 },  // Gaussian bumps of random sign
+}
+
+// Reproducible initial perturbation for leaving unstable symmetric branches.
+// Written as `field:kind:key=value,...`, e.g. `temp:random:amp=0.01,seed=42`,
+// `vort:fourier:amp=5,kx=2,ky=1` or `temp:blobs:amp=0.05,count=3,radius=0.1,seed=7`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Perturbation {
+    pub field: PerturbedField,
+    pub kind: PerturbationKind,
+    pub amplitude: f64,
+}
+
+impl Perturbation {
+    pub fn seed(&self) -> Option<u64> {
+        match self.kind {
+            PerturbationKind::Random { seed } | PerturbationKind::Blobs { seed, .. } => Some(seed),
+            PerturbationKind::Fourier { .. } => None,
+        }
+    }
+
+    // Add the perturbation to the fluid nodes; walls and solids keep their values. Modes
+    // and blob centres span the domain width W; with periodic x the ghost columns are
+    // copied from the perturbed interior, and Fourier modes take kx whole wavelengths
+    // over the period so they stay continuous across it.
+    pub fn apply(&self, state: &mut SimState, domain: &Domain) {
+        let (ny, nx) = state.temp.dim();
+        let (dx, dy) = domain.spacing(nx, ny);
+        let first = domain.first_column();
+        let waves = if domain.periodic { 2.0 } else { 1.0 };
+
+        let blobs: Vec<(f64, f64, f64)> = match self.kind {
+            PerturbationKind::Blobs { count, seed, .. } => {
+                let mut rng = Rng::new(seed);
+                (0..count)
+                    .map(|_| {
+                        let sign = if rng.uniform() < 0.5 { -1.0 } else { 1.0 };
+                        (rng.uniform() * domain.width, rng.uniform(), sign)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let mut rng = match self.kind {
+            PerturbationKind::Random { seed } => Some(Rng::new(seed)),
+            _ => None,
+        };
+
+        for i in 0..ny {
+            for j in first..nx - first {
+                if !state.is_fluid(i, j) {
+                    continue;
+                }
+                let (x, y) = ((j - first) as f64 * dx, i as f64 * dy);
+                let shape = match self.kind {
+                    PerturbationKind::Random { .. } => rng.as_mut().map_or(0.0, |r| r.range(-1.0, 1.0)),
+                    PerturbationKind::Fourier { kx, ky } => (waves * kx as f64 * PI * x / domain.width).sin() * (ky as f64 * PI * y).sin(),
+                    PerturbationKind::Blobs { radius, .. } => blobs
+                        .iter()
+                        .map(|&(bx, by, sign)| sign * (-((x - bx).powi(2) + (y - by).powi(2)) / (radius * radius)).exp())
+                        .sum(),
+                };
+                let field = match self.field {
+                    PerturbedField::Vorticity => &mut state.vort,
+                    PerturbedField::Temperature => &mut state.temp,
+                    PerturbedField::Concentration => &mut state.conc,
+                };
+                field[[i, j]] += self.amplitude * shape;
+            }
+        }
+        if domain.periodic {
+            for field in [&mut state.vort, &mut state.temp, &mut state.conc] {
+                wrap_columns(field);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Perturbation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = match self.field {
+            PerturbedField::Vorticity => "vort",
+            PerturbedField::Temperature => "temp",
+            PerturbedField::Concentration => "conc",
+        };
+        match self.kind {
+            PerturbationKind::Random { seed } => write!(f, "{}:random:amp={},seed={}", field, self.amplitude, seed),
+            PerturbationKind::Fourier { kx, ky } => write!(f, "{}:fourier:amp={},kx={},ky={}", field, self.amplitude, kx, ky),
+            PerturbationKind::Blobs { count, radius, seed } => write!(
+                f,
+                "{}:blobs:amp={},count={},radius={},seed={}",
+                field, self.amplitude, count, radius, seed
+            ),
+        }
+    }
+}
+
+impl FromStr for Perturbation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(3, ':').collect();
+        if parts.len() < 2 {
+            anyhow::bail!("expected field:kind[:key=value,...], got '{}'", s);
+        }
+        let field = match parts[0] {
+            "vort" | "vorticity" => PerturbedField::Vorticity,
+            "temp" | "temperature" => PerturbedField::Temperature,
+            "conc" | "concentration" => PerturbedField::Concentration,
+            other => anyhow::bail!("unknown perturbed field '{}'", other),
+        };
+
+        let mut amplitude = 1e-3;
+        let (mut seed, mut kx, mut ky, mut count, mut radius) = (0u64, 1u32, 1u32, 3u32, 0.1);
+        for option in parts.get(2).into_iter().flat_map(|o| o.split(',')).filter(|o| !o.is_empty()) {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("expected key=value, got '{}'", option))?;
+            match key {
+                "amp" => amplitude = value.parse()?,
+                "seed" => seed = value.parse()?,
+                "kx" => kx = value.parse()?,
+                "ky" => ky = value.parse()?,
+                "count" => count = value.parse()?,
+                "radius" => radius = value.parse()?,
+                other => anyhow::bail!("unknown perturbation option '{}'", other),
+            }
+        }
+
+        let kind = match parts[1] {
+            "random" => PerturbationKind::Random { seed },
+            "fourier" => PerturbationKind::Fourier { kx, ky },
+            "blobs" => PerturbationKind::Blobs { count, radius, seed },
+            other => anyhow::bail!("unknown perturbation kind '{}'", other),
+        };
+        Ok(Perturbation { field, kind, amplitude })
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::simulation::{Domain, SimParameters, SimState};
+
+// A named point, e.g. a thermocouple in the crucible, in domain coordinates like the
+// case-file shapes: 0 ≤ x ≤ width and 0 ≤ y ≤ 1 in units of the domain height.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Probe {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+// `name:x,y`
+impl FromStr for Probe {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, point) = s.split_once(':').ok_or_else(|| anyhow::anyhow!("expected name:x,y, got '{}'", s))?;
+        let (x, y) = parse_point(point)?;
+        Ok(Probe { name: name.to_string(), x, y })
+    }
+}
+
+// Evenly spaced points along `path`, both ends included, sampled as probes named
+// `<name>[k]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonitorLine {
+    pub name: String,
+    #[serde(flatten)]
+    pub path: LinePath,
+    pub points: usize,
+}
+
+// Where a monitor line runs, in domain coordinates. The presets span the domain whatever
+// its width.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LinePath {
+    Segment { start: [f64; 2], end: [f64; 2] },
+    Across { height: f64 }, // From the left to the right wall
+    VerticalCenterline,
+    #[serde(skip)]
+    MeltSurface, // Across at the melt surface height of the layout the probe set is made for
+}
+
+const DEFAULT_LINE_POINTS: usize = 41;
+
+// `name:x0,y0:x1,y1[:points]`, or one of the presets `vertical-centerline`,
+// `horizontal-centerline` and `surface[:height]` (the melt surface of the layout by default).
+impl FromStr for MonitorLine {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let line = |path: LinePath| MonitorLine { name: parts[0].to_string(), path, points: DEFAULT_LINE_POINTS };
+        match parts.as_slice() {
+            ["vertical-centerline"] => Ok(line(LinePath::VerticalCenterline)),
+            ["horizontal-centerline"] => Ok(line(LinePath::Across { height: 0.5 })),
+            ["surface"] => Ok(line(LinePath::MeltSurface)),
+            ["surface", height] => {
+                let height: f64 = height.parse()?;
+                anyhow::ensure!((0.0..=1.0).contains(&height), "surface height {} is outside the domain", height);
+                Ok(line(LinePath::Across { height }))
+            }
+            [_, start, end] | [_, start, end, _] => {
+                let ((x0, y0), (x1, y1)) = (parse_point(start)?, parse_point(end)?);
+                let mut line = line(LinePath::Segment { start: [x0, y0], end: [x1, y1] });
+                if let Some(points) = parts.get(3) {
+                    line.points = points.parse()?;
+                    anyhow::ensure!(line.points >= 2, "a monitor line needs at least 2 points");
+                }
+                Ok(line)
+            }
+            _ => anyhow::bail!("expected name:x0,y0:x1,y1[:points] or a preset line, got '{}'", s),
+        }
+    }
+}
+
+impl MonitorLine {
+    // Ends of the line on a domain `width` wide whose melt surface, if it has one, is at
+    // `surface`.
+    pub fn ends(&self, width: f64, surface: Option<f64>) -> Result<([f64; 2], [f64; 2])> {
+        Ok(match self.path {
+            LinePath::Segment { start, end } => (start, end),
+            LinePath::Across { height } => ([0.0, height], [width, height]),
+            LinePath::VerticalCenterline => ([0.5 * width, 0.0], [0.5 * width, 1.0]),
+            LinePath::MeltSurface => {
+                let height = surface.with_context(|| format!("line {} follows the melt surface, which needs a region layout; give its height as surface:<height>", self.name))?;
+                ([0.0, height], [width, height])
+            }
+        })
+    }
+
+    pub fn probes(&self, width: f64, surface: Option<f64>) -> Result<Vec<Probe>> {
+        let (start, end) = self.ends(width, surface)?;
+        Ok((0..self.points)
+            .map(|k| {
+                let s = k as f64 / (self.points as f64 - 1.0);
+                Probe {
+                    name: format!("{}[{}]", self.name, k),
+                    x: start[0] + s * (end[0] - start[0]),
+                    y: start[1] + s * (end[1] - start[1]),
+                }
+            })
+            .collect())
+    }
+}
+
+// The width is only known with the domain, so x is checked against it in `ProbeSet::new`.
+fn parse_point(s: &str) -> Result<(f64, f64)> {
+    let (x, y) = s.split_once(',').ok_or_else(|| anyhow::anyhow!("expected x,y, got '{}'", s))?;
+    let (x, y): (f64, f64) = (x.parse()?, y.parse()?);
+    anyhow::ensure!(x >= 0.0 && (0.0..=1.0).contains(&y), "point ({}, {}) is outside the domain", x, y);
+    Ok((x, y))
+}
+
+// Bilinear interpolation of a nodal field at (x, y) in domain coordinates. With periodic
+// x, x = 0 is the first interior column and x = width its ghost copy on the far side.
+pub fn bilinear(field: &Array2<f64>, domain: &Domain, x: f64, y: f64) -> f64 {
+    let (ny, nx) = field.dim();
+    let (dx, dy) = domain.spacing(nx, ny);
+    let fx = x.clamp(0.0, domain.width) / dx + domain.first_column() as f64;
+    let fy = y.clamp(0.0, 1.0) / dy;
+    let (j, i) = ((fx as usize).min(nx - 2), (fy as usize).min(ny - 2));
+    let (sx, sy) = (fx - j as f64, fy - i as f64);
+    (1.0 - sy) * ((1.0 - sx) * field[[i, j]] + sx * field[[i, j + 1]]) + sy * ((1.0 - sx) * field[[i + 1, j]] + sx * field[[i + 1, j + 1]])
+}
+
+// T, u, v and ω at one probe and time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProbeSample {
+    pub probe: String,
+    pub step: usize,
+    pub time: f64,
+    pub x: f64,
+    pub y: f64,
+    pub temp: f64,
+    pub u: f64,
+    pub v: f64,
+    pub vort: f64,
+}
+
+// Probes and lines sampled every `interval` steps of `Simulation::run`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProbeSet {
+    pub interval: usize,
+    pub probes: Vec<Probe>, // Individual probes and the points of all lines
+    pub history: Vec<ProbeSample>,
+}
+
+impl ProbeSet {
+    // Refuses points outside the domain, which would read the nearest wall instead. The
+    // `surface` line runs at the initial melt surface height of the layout of `params`.
+    pub fn new(interval: usize, probes: &[Probe], lines: &[MonitorLine], params: &SimParameters) -> Result<Self> {
+        let domain = &params.domain;
+        let surface = params.layout.as_ref().map(|layout| layout.melt_height);
+        let mut all = probes.to_vec();
+        for line in lines {
+            all.extend(line.probes(domain.width, surface)?);
+        }
+        let inside = |p: &Probe| (0.0..=domain.width).contains(&p.x) && (0.0..=1.0).contains(&p.y);
+        if let Some(probe) = all.iter().find(|p| !inside(p)) {
+            anyhow::bail!("probe {} at ({}, {}) is outside the domain, 0 <= x <= {} and 0 <= y <= 1", probe.name, probe.x, probe.y, domain.width);
+        }
+        Ok(ProbeSet { interval, probes: all, history: Vec::new() })
+    }
+
+    pub fn sample(&mut self, state: &SimState, domain: &Domain, step: usize, time: f64) {
+        for probe in &self.probes {
+            self.history.push(ProbeSample {
+                probe: probe.name.clone(),
+                step,
+                time,
+                x: probe.x,
+                y: probe.y,
+                temp: bilinear(&state.temp, domain, probe.x, probe.y),
+                u: bilinear(&state.u, domain, probe.x, probe.y),
+                v: bilinear(&state.v, domain, probe.x, probe.y),
+                vort: bilinear(&state.vort, domain, probe.x, probe.y),
+            });
+        }
+    }
+}
+
+pub fn write_csv(samples: &[ProbeSample], path: &Path) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path).with_context(|| format!("creating {}", path.display()))?);
+    writeln!(out, "probe,step,time,x,y,temperature,u,v,vorticity")?;
+    for s in samples {
+        writeln!(out, "{},{},{:e},{},{},{:e},{:e},{:e},{:e}", csv_field(&s.probe), s.step, s.time, s.x, s.y, s.temp, s.u, s.v, s.vort)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+// A probe name as a CSV field: quoted, with its quotes doubled, when it holds a comma,
+// a quote or a line break (RFC 4180).
+fn csv_field(name: &str) -> Cow<'_, str> {
+    if name.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", name.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+// Latest sample of each probe.
+pub fn print_latest(samples: &[ProbeSample]) {
+    let Some(last) = samples.last() else {
+        println!("No probe samples");
+        return;
+    };
+    println!("--- Probes at step {} (t = {:.6}) ---", last.step, last.time);
+    println!("{:<28} | {:<7} | {:<7} | {:<10} | {:<10} | {:<10} | vorticity", "Probe", "x", "y", "T", "u", "v");
+    println!("{}", "-".repeat(100));
+    for s in samples.iter().filter(|s| s.step == last.step) {
+        println!("{:<28} | {:<7.4} | {:<7.4} | {:<10.5} | {:<10.4e} | {:<10.4e} | {:.4e}", s.probe, s.x, s.y, s.temp, s.u, s.v, s.vort);
+    }
+}
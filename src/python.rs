@@ -0,0 +1,314 @@
+use ndarray::Array2;
+use numpy::{PyArray2, ToPyArray};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::regions::RegionLayout;
+use crate::simulation::{Domain, RunOutcome, SimParameters, SimState, Simulation};
+use crate::solver::Solver;
+
+// Python extension module, built with maturin and the `python` feature:
+//
+//   params = cz.SimParameters(grid_size=41, ra=1e5, pr=0.71)
+//   sim = cz.Simulation(params)
+//   sim.run(2000)
+//   sim.state.temp, sim.diagnostics()
+//   cz.Database().runs()
+//
+// Fields are copied into new NumPy arrays of shape (ny, nx), indexed [y, x].
+//
+// The crate links no interpreter, so tests/python.rs tests the plain Rust functions the
+// classes call (cavity_parameters, set_checked, run_steps, region_ids, mask_ids, and the
+// db loaders behind Database). The NumPy copies and the JSON dicts themselves are only
+// exercised from Python.
+
+// Structured values go through JSON into plain dicts and lists.
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+// Parameters of `SimParameters(...)`: a cavity heated by the default wall conditions, with
+// the default multi-region furnace around the melt if asked for. Kept free of Python types
+// so that it can be tested without an interpreter.
+pub fn cavity_parameters(grid_size: usize, ra: f64, pr: f64, dt: f64, width: f64, periodic: bool, multi_region: bool) -> anyhow::Result<SimParameters> {
+    anyhow::ensure!(grid_size >= 3 && width > 0.0, "need grid_size >= 3 and a positive width");
+    let domain = Domain { width, periodic };
+    let params = SimParameters {
+        dt,
+        layout: multi_region.then(RegionLayout::default),
+        ..SimParameters::on_domain(grid_size, pr, ra, domain)
+    };
+    params.validate()?;
+    Ok(params)
+}
+
+// The setters of `SimParameters`: the change is kept only if the parameters still pass
+// `SimParameters::validate`, as the C interface checks them.
+pub fn set_checked(params: &mut SimParameters, set: impl FnOnce(&mut SimParameters)) -> anyhow::Result<()> {
+    let mut changed = params.clone();
+    set(&mut changed);
+    changed.validate()?;
+    *params = changed;
+    Ok(())
+}
+
+// `Simulation.run(steps)`: the watchdog turns a blow-up into an error.
+pub fn run_steps(sim: &mut Simulation, steps: usize) -> anyhow::Result<()> {
+    match sim.run(steps)? {
+        RunOutcome::BlewUp(report) => anyhow::bail!("blew up at step {}: {}", report.step, report.reason),
+        RunOutcome::Completed | RunOutcome::Interrupted => Ok(()),
+    }
+}
+
+// Region ids: 0 melt, 1 crystal, 2 crucible, 3 susceptor, 4 gas, 5 heat shield.
+pub fn region_ids(state: &SimState) -> Array2<i16> {
+    state.region.mapv(|r| r.id())
+}
+
+// Cell types: 0 fluid, 1 solid, 2 boundary.
+pub fn mask_ids(state: &SimState) -> Array2<i16> {
+    state.mask.mapv(|c| c.id())
+}
+
+#[pyclass(name = "SimParameters", module = "cz_cfd_simulator")]
+#[derive(Clone)]
+pub struct PySimParameters {
+    inner: SimParameters,
+}
+
+#[pymethods]
+impl PySimParameters {
+    // See `cavity_parameters`. Anything else: from_json.
+    #[new]
+    #[pyo3(signature = (grid_size=41, ra=1e4, pr=0.71, dt=1e-4, width=1.0, periodic=false, multi_region=false))]
+    fn new(grid_size: usize, ra: f64, pr: f64, dt: f64, width: f64, periodic: bool, multi_region: bool) -> PyResult<Self> {
+        let inner = cavity_parameters(grid_size, ra, pr, dt, width, periodic, multi_region).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PySimParameters { inner })
+    }
+
+    // The full parameter set in the JSON form of checkpoints and case studies.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner: SimParameters = serde_json::from_str(json).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        inner.validate()?;
+        Ok(PySimParameters { inner })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(&self.inner).map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn nx(&self) -> usize {
+        self.inner.nx
+    }
+
+    #[getter]
+    fn ny(&self) -> usize {
+        self.inner.ny
+    }
+
+    #[getter]
+    fn dt(&self) -> f64 {
+        self.inner.dt
+    }
+
+    #[setter]
+    fn set_dt(&mut self, dt: f64) -> PyResult<()> {
+        Ok(set_checked(&mut self.inner, |params| params.dt = dt)?)
+    }
+
+    #[getter]
+    fn ra(&self) -> f64 {
+        self.inner.ra
+    }
+
+    #[setter]
+    fn set_ra(&mut self, ra: f64) -> PyResult<()> {
+        Ok(set_checked(&mut self.inner, |params| params.ra = ra)?)
+    }
+
+    #[getter]
+    fn pr(&self) -> f64 {
+        self.inner.pr
+    }
+
+    #[setter]
+    fn set_pr(&mut self, pr: f64) -> PyResult<()> {
+        Ok(set_checked(&mut self.inner, |params| params.pr = pr)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SimParameters(nx={}, ny={}, ra={:e}, pr={}, dt={:e})", self.inner.nx, self.inner.ny, self.inner.ra, self.inner.pr, self.inner.dt)
+    }
+}
+
+// A copy of the fields at one time.
+#[pyclass(name = "SimState", module = "cz_cfd_simulator")]
+pub struct PySimState {
+    inner: SimState,
+}
+
+#[pymethods]
+impl PySimState {
+    #[getter]
+    fn temp<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.temp.to_pyarray(py)
+    }
+
+    #[getter]
+    fn vort<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.vort.to_pyarray(py)
+    }
+
+    #[getter]
+    fn stream<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.stream.to_pyarray(py)
+    }
+
+    #[getter]
+    fn u<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.u.to_pyarray(py)
+    }
+
+    #[getter]
+    fn v<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.v.to_pyarray(py)
+    }
+
+    #[getter]
+    fn conc<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.conc.to_pyarray(py)
+    }
+
+    #[getter]
+    fn gas_vort<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.gas_vort.to_pyarray(py)
+    }
+
+    #[getter]
+    fn gas_stream<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.inner.gas_stream.to_pyarray(py)
+    }
+
+    #[getter]
+    fn region<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<i16>> {
+        region_ids(&self.inner).to_pyarray(py)
+    }
+
+    #[getter]
+    fn mask<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<i16>> {
+        mask_ids(&self.inner).to_pyarray(py)
+    }
+
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        self.inner.temp.dim()
+    }
+}
+
+#[pyclass(name = "Simulation", module = "cz_cfd_simulator")]
+pub struct PySimulation {
+    inner: Simulation,
+}
+
+#[pymethods]
+impl PySimulation {
+    #[new]
+    fn new(params: &PySimParameters) -> PyResult<Self> {
+        params.inner.validate()?;
+        let inner = Simulation::new(params.inner.clone());
+        inner.check_geometry()?;
+        Ok(PySimulation { inner })
+    }
+
+    fn step(&mut self) {
+        Solver::step(&mut self.inner);
+    }
+
+    // Advance `steps` steps with the watchdog on, without holding the GIL. Raises if the
+    // run blows up.
+    fn run(&mut self, py: Python<'_>, steps: usize) -> PyResult<()> {
+        let sim = &mut self.inner;
+        py.detach(|| run_steps(sim, steps)).map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn state(&self) -> PySimState {
+        PySimState { inner: self.inner.state.clone() }
+    }
+
+    #[getter]
+    fn params(&self) -> PySimParameters {
+        PySimParameters { inner: self.inner.params.clone() }
+    }
+
+    #[getter]
+    fn time(&self) -> f64 {
+        self.inner.time
+    }
+
+    #[getter]
+    fn steps_taken(&self) -> usize {
+        self.inner.steps_taken
+    }
+
+    fn stable_time_step(&self) -> f64 {
+        self.inner.stable_time_step()
+    }
+
+    // Nusselt numbers, kinetic energy, extrema and the latest conservation errors.
+    fn diagnostics(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_python(py, &Solver::diagnostics(&self.inner))
+    }
+}
+
+// Read-only access to the runs recorded by the CLI, through DATABASE_URL.
+#[cfg(feature = "db")]
+#[pyclass(name = "Database", module = "cz_cfd_simulator")]
+pub struct PyDatabase {
+    pool: crate::db::DbPool,
+}
+
+#[cfg(feature = "db")]
+#[pymethods]
+impl PyDatabase {
+    #[new]
+    fn new() -> PyResult<Self> {
+        Ok(PyDatabase { pool: crate::db::establish_connection_pool()? })
+    }
+
+    // Every run record as a dict, oldest first.
+    fn runs(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_python(py, &crate::db::load_simulation_runs(&self.pool)?)
+    }
+
+    fn run(&self, py: Python<'_>, run_id: i32) -> PyResult<Py<PyAny>> {
+        to_python(py, &crate::db::get_simulation_run(&self.pool, run_id)?)
+    }
+
+    // The final fields of a completed run.
+    fn results(&self, run_id: i32) -> PyResult<PySimState> {
+        Ok(PySimState { inner: crate::db::get_simulation_results(&self.pool, run_id)? })
+    }
+
+    fn diagnostics(&self, py: Python<'_>, run_id: i32) -> PyResult<Py<PyAny>> {
+        to_python(py, &crate::db::get_diagnostics(&self.pool, run_id)?)
+    }
+
+    fn probe_samples(&self, py: Python<'_>, run_id: i32) -> PyResult<Py<PyAny>> {
+        to_python(py, &crate::db::get_probe_samples(&self.pool, run_id)?)
+    }
+}
+
+#[pymodule]
+fn cz_cfd_simulator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySimParameters>()?;
+    m.add_class::<PySimState>()?;
+    m.add_class::<PySimulation>()?;
+    #[cfg(feature = "db")]
+    m.add_class::<PyDatabase>()?;
+    Ok(())
+}
@@ -0,0 +1,380 @@
+use ndarray::Array2;
+use std::f64::consts::PI;
+
+use crate::boundary::{emissive_power, RadiationParameters, ThermalBc, ThermalBoundary, Wall};
+use crate::regions::Region;
+use crate::simulation::Domain;
+
+// Azimuths of the ring-to-ring integration, graded towards φ = 0 where nearby rings see
+// each other most.
+const AZIMUTHS: usize = 64;
+
+// Pieces each profile is split into for the ring-to-ring integration, and the finer split
+// for pairs closer than NEAR element lengths, where the kernel varies fastest.
+const PIECES: usize = 2;
+const NEAR_PIECES: usize = 8;
+const NEAR: f64 = 3.0;
+
+// Surface-to-surface radiation across the gas between the radiating wall nodes and, if it
+// emits, the free melt surface. Only gas is transparent: the melt and the solids are
+// opaque. A wall element is the stretch of wall of a radiating wall node, the elements
+// next to a corner reaching into it so that they tile the walls; a surface element is a
+// face between a melt node and a gas neighbour. Two elements exchange radiation if both
+// face gas, they face each other and the path between them stays in gas.
+//
+// In the plane the view factors come from Hottel's crossed-strings rule, exact between
+// elements that see each other, with the line between their midpoints deciding whether
+// they do. An axisymmetric domain is read like the induction grid, as a diametral section
+// with the axis at its centre line: every element stands for the ring it sweeps about the
+// axis and the right half decides what is gas. The ring-to-ring factors are integrated
+// over both profiles and the azimuth, each azimuth shadowed by the chord between the
+// profile midpoints. An element and its mirror image share their ring and half its area.
+//
+// Whatever an element does not see of the others is assumed to look onto the
+// environment at its own T_env.
+pub struct RadiationModel {
+    nodes: Vec<(usize, usize)>, // Wall node, or the melt node under a surface face
+    emissivity: Vec<f64>,
+    t_env: Vec<f64>,
+    elements: Vec<Element>,
+    walls: usize, // Wall elements, ahead of the surface elements
+    spacing: (f64, f64),
+    pub view_factors: Array2<f64>,
+    pub areas: Vec<f64>, // Element lengths, or their share of the ring areas
+}
+
+impl RadiationModel {
+    pub fn new(boundary: &ThermalBoundary, domain: &Domain, region: &Array2<Region>) -> Self {
+        let (ny, nx) = region.dim();
+        let spacing = domain.spacing(nx, ny);
+        let (dx, dy) = spacing;
+        let first = domain.first_column();
+        let position = |(i, j): (usize, usize)| ((j as f64 - first as f64) * dx, i as f64 * dy);
+        // Stretch of the wall or face through a node, reaching the corners from the
+        // nodes next to them
+        let along_x = |j: usize, x: f64| {
+            let reach = !domain.periodic;
+            (if reach && j == 1 { 0.0 } else { x - 0.5 * dx }, if reach && j == nx - 2 { domain.width } else { x + 0.5 * dx })
+        };
+        let along_y = |i: usize, y: f64| (if i == 1 { 0.0 } else { y - 0.5 * dy }, if i == ny - 2 { 1.0 } else { y + 0.5 * dy });
+
+        let mut nodes = Vec::new();
+        let mut emissivity = Vec::new();
+        let mut t_env = Vec::new();
+        let mut elements = Vec::new();
+        let mut open = Vec::new(); // Whether the element faces gas
+        for n in boundary.nodes(domain, nx, ny) {
+            if let ThermalBc::Radiative { emissivity: e, t_env: te } = n.bc {
+                let (i, j) = n.node;
+                let (x, y) = position(n.node);
+                let element = match n.wall {
+                    Wall::Bottom | Wall::Top => {
+                        let (x0, x1) = along_x(j, x);
+                        let normal = if n.wall == Wall::Bottom { (0.0, 1.0) } else { (0.0, -1.0) };
+                        Element { start: (x0, y), end: (x1, y), normal }
+                    }
+                    Wall::Left | Wall::Right => {
+                        let (y0, y1) = along_y(i, y);
+                        let normal = if n.wall == Wall::Left { (1.0, 0.0) } else { (-1.0, 0.0) };
+                        Element { start: (x, y0), end: (x, y1), normal }
+                    }
+                };
+                nodes.push(n.node);
+                open.push(region[n.inner] == Region::Gas);
+                emissivity.push(e);
+                t_env.push(te);
+                elements.push(element);
+            }
+        }
+        let walls = elements.len();
+
+        if let Some(surface) = boundary.radiation.melt_surface {
+            let frame = |i: usize, j: usize| i == 0 || j == 0 || i == ny - 1 || j == nx - 1;
+            for ((i, j), r) in region.indexed_iter() {
+                if *r != Region::Melt || frame(i, j) {
+                    continue;
+                }
+                let (x, y) = position((i, j));
+                for (di, dj) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    let (ii, jj) = (i.wrapping_add_signed(di), j.wrapping_add_signed(dj));
+                    if frame(ii, jj) || region[[ii, jj]] != Region::Gas {
+                        continue;
+                    }
+                    let element = if di != 0 {
+                        let (x0, x1) = along_x(j, x);
+                        let y = y + 0.5 * di as f64 * dy;
+                        Element { start: (x0, y), end: (x1, y), normal: (0.0, di as f64) }
+                    } else {
+                        let (y0, y1) = along_y(i, y);
+                        let x = x + 0.5 * dj as f64 * dx;
+                        Element { start: (x, y0), end: (x, y1), normal: (dj as f64, 0.0) }
+                    };
+                    nodes.push((i, j));
+                    open.push(true);
+                    emissivity.push(surface.emissivity);
+                    t_env.push(surface.t_env);
+                    elements.push(element);
+                }
+            }
+        }
+
+        // Without the exchange every element sees only its environment
+        let open: Vec<bool> = open.into_iter().map(|o| o && boundary.radiation.surface_to_surface).collect();
+        let grid = Grid { region, spacing, first };
+        let (view_factors, areas) = if boundary.radiation.axisymmetric {
+            ring_view_factors(&elements, &open, &grid, 0.5 * domain.width)
+        } else {
+            planar_view_factors(&elements, &open, &grid)
+        };
+
+        RadiationModel { nodes, emissivity, t_env, elements, walls, spacing, view_factors, areas }
+    }
+
+    // Irradiation G of every element: the radiating wall nodes in the order of
+    // `ThermalBoundary::nodes`, then the surface faces. It follows from the radiosity
+    // balance J_i = ε_i E_b,i + (1 − ε_i) G_i with G_i = Σ_j F_ij J_j + (1 − Σ_j F_ij) E_b,env,i,
+    // solved by Gauss–Seidel.
+    pub fn irradiation(&self, temp: &Array2<f64>, rad: &RadiationParameters) -> Vec<f64> {
+        let m = self.nodes.len();
+        let eb: Vec<f64> = self.nodes.iter().map(|&n| emissive_power(temp[n], rad)).collect();
+        let env: Vec<f64> = (0..m)
+            .map(|a| {
+                let seen: f64 = self.view_factors.row(a).sum();
+                (1.0 - seen).max(0.0) * emissive_power(self.t_env[a], rad)
+            })
+            .collect();
+
+        let mut radiosity = eb.clone();
+        for _ in 0..200 {
+            let mut change: f64 = 0.0;
+            for a in 0..m {
+                let g = env[a] + self.view_factors.row(a).dot(&ndarray::ArrayView1::from(&radiosity));
+                let j_new = self.emissivity[a] * eb[a] + (1.0 - self.emissivity[a]) * g;
+                change = change.max((j_new - radiosity[a]).abs());
+                radiosity[a] = j_new;
+            }
+            if change < 1e-10 {
+                break;
+            }
+        }
+
+        (0..m)
+            .map(|a| env[a] + self.view_factors.row(a).dot(&ndarray::ArrayView1::from(&radiosity)))
+            .collect()
+    }
+
+    // Net radiation ε (E_b − G) leaving each element, per unit area.
+    pub fn net_emission(&self, temp: &Array2<f64>, rad: &RadiationParameters) -> Vec<f64> {
+        let irradiation = self.irradiation(temp, rad);
+        (0..self.nodes.len()).map(|a| self.emissivity[a] * (emissive_power(temp[self.nodes[a]], rad) - irradiation[a])).collect()
+    }
+
+    // Heating of the melt nodes under the surface faces by the radiation they absorb less
+    // what they emit, per unit volume: the net loss over each face is drawn from its
+    // node's Δx Δy. None if the surface does not radiate.
+    pub fn surface_heat(&self, temp: &Array2<f64>, rad: &RadiationParameters) -> Option<Array2<f64>> {
+        if self.nodes.len() == self.walls {
+            return None;
+        }
+        let (dx, dy) = self.spacing;
+        let net = self.net_emission(temp, rad);
+        let mut heat = Array2::zeros(temp.dim());
+        for a in self.walls..self.nodes.len() {
+            heat[self.nodes[a]] -= net[a] * self.elements[a].length() / (dx * dy);
+        }
+        Some(heat)
+    }
+}
+
+// A straight radiating element with the unit normal pointing into the gas, in (x, y) on
+// the grid or in (r, z) as the profile of a ring.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Element {
+    start: (f64, f64),
+    end: (f64, f64),
+    normal: (f64, f64),
+}
+
+impl Element {
+    fn length(&self) -> f64 {
+        distance(self.start, self.end)
+    }
+
+    fn midpoint(&self) -> (f64, f64) {
+        (0.5 * (self.start.0 + self.end.0), 0.5 * (self.start.1 + self.end.1))
+    }
+
+    // Midpoint moved off the element into the gas, where paths to other elements start.
+    fn launch(&self, (dx, dy): (f64, f64)) -> (f64, f64) {
+        let (x, y) = self.midpoint();
+        (x + 1e-6 * dx * self.normal.0, y + 1e-6 * dy * self.normal.1)
+    }
+
+    // Whether the two elements are turned towards each other.
+    fn faces(&self, other: &Element) -> bool {
+        let (p, q) = (self.midpoint(), other.midpoint());
+        let d = (q.0 - p.0, q.1 - p.1);
+        d.0 * self.normal.0 + d.1 * self.normal.1 > 0.0 && d.0 * other.normal.0 + d.1 * other.normal.1 < 0.0
+    }
+
+    // Profile of the ring swept about the axis at x = `axis`. A horizontal element across
+    // the axis sweeps a disk.
+    fn ring(&self, axis: f64) -> Element {
+        let r = |x: f64| (x - axis).abs();
+        let (x0, x1) = (self.start.0, self.end.0);
+        if (x0 - axis) * (x1 - axis) < 0.0 {
+            return Element { start: (0.0, self.start.1), end: (r(x0).max(r(x1)), self.end.1), normal: self.normal };
+        }
+        let side = if self.midpoint().0 < axis { -1.0 } else { 1.0 };
+        Element { start: (r(x0), self.start.1), end: (r(x1), self.end.1), normal: (side * self.normal.0, self.normal.1) }
+    }
+
+    fn same_ring(&self, other: &Element) -> bool {
+        let close = |p: (f64, f64), q: (f64, f64)| distance(p, q) < 1e-9;
+        let ends = (close(self.start, other.start) && close(self.end, other.end)) || (close(self.start, other.end) && close(self.end, other.start));
+        ends && close(self.normal, other.normal)
+    }
+
+    // Points and lengths of the profile cut into `pieces` equal parts.
+    fn pieces(&self, pieces: usize) -> impl Iterator<Item = ((f64, f64), f64)> + '_ {
+        let ds = self.length() / pieces as f64;
+        (0..pieces).map(move |k| {
+            let t = (k as f64 + 0.5) / pieces as f64;
+            ((self.start.0 + t * (self.end.0 - self.start.0), self.start.1 + t * (self.end.1 - self.start.1)), ds)
+        })
+    }
+}
+
+// The region map as seen by the rays: gas is transparent, and so is the outer frame, which
+// paths touch only at their ends.
+struct Grid<'a> {
+    region: &'a Array2<Region>,
+    spacing: (f64, f64),
+    first: usize, // Column at x = 0
+}
+
+impl Grid<'_> {
+    fn transparent(&self, x: f64, y: f64) -> bool {
+        let (ny, nx) = self.region.dim();
+        let (dx, dy) = self.spacing;
+        let j = (x / dx).round().max(0.0) as usize + self.first;
+        let i = (y / dy).round().max(0.0) as usize;
+        i == 0 || j == 0 || i >= ny - 1 || j >= nx - 1 || self.region[[i, j]] == Region::Gas
+    }
+
+    // Whether the straight path from p to q stays in gas, sampled every quarter spacing.
+    fn clear(&self, p: (f64, f64), q: (f64, f64)) -> bool {
+        self.clear_along(|t| (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1)), distance(p, q))
+    }
+
+    // Whether the chord from the point at radius and height p, azimuth 0, to the one at q,
+    // azimuth φ, stays in gas, read off the right half of the section.
+    fn clear_chord(&self, p: (f64, f64), q: (f64, f64), phi: f64, axis: f64) -> bool {
+        let (qx, qy) = (q.0 * phi.cos(), q.0 * phi.sin());
+        let length = ((qx - p.0).powi(2) + qy.powi(2) + (q.1 - p.1).powi(2)).sqrt();
+        self.clear_along(
+            |t| {
+                let (x, y) = (p.0 + t * (qx - p.0), t * qy);
+                (axis + (x * x + y * y).sqrt(), p.1 + t * (q.1 - p.1))
+            },
+            length,
+        )
+    }
+
+    fn clear_along(&self, point: impl Fn(f64) -> (f64, f64), length: f64) -> bool {
+        let samples = ((4.0 * length / self.spacing.0.min(self.spacing.1)).ceil() as usize).max(1);
+        (0..=samples).all(|k| {
+            let (x, y) = point(k as f64 / samples as f64);
+            self.transparent(x, y)
+        })
+    }
+}
+
+fn distance(p: (f64, f64), q: (f64, f64)) -> f64 {
+    ((p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)).sqrt()
+}
+
+// Crossed-strings view factors between the `open` elements in the plane, and the lengths
+// of all of them.
+fn planar_view_factors(elements: &[Element], open: &[bool], grid: &Grid) -> (Array2<f64>, Vec<f64>) {
+    let m = elements.len();
+    let mut view_factors = Array2::zeros((m, m));
+    for a in 0..m {
+        for b in 0..m {
+            let (ea, eb) = (&elements[a], &elements[b]);
+            if open[a] && open[b] && a != b && ea.faces(eb) && grid.clear(ea.launch(grid.spacing), eb.launch(grid.spacing)) {
+                view_factors[[a, b]] = crossed_strings(ea, eb);
+            }
+        }
+    }
+    (view_factors, elements.iter().map(Element::length).collect())
+}
+
+// View factor from element `a` to element `b`: (crossed − uncrossed strings) / (2 L_a).
+fn crossed_strings(a: &Element, b: &Element) -> f64 {
+    let crossed = distance(a.start, b.end) + distance(a.end, b.start);
+    let uncrossed = distance(a.start, b.start) + distance(a.end, b.end);
+    (crossed - uncrossed).abs() / (2.0 * a.length())
+}
+
+// Ring-to-ring view factors between the rings the `open` elements sweep about the axis at
+// x = `axis`, and the elements' shares of the ring areas. What an element sees of a ring
+// it shares with its mirror image is split evenly between the two. Each pair of rings is
+// integrated once, the reverse factor following from reciprocity.
+fn ring_view_factors(elements: &[Element], open: &[bool], grid: &Grid, axis: f64) -> (Array2<f64>, Vec<f64>) {
+    let rings: Vec<Element> = elements.iter().map(|e| e.ring(axis)).collect();
+    let first: Vec<usize> = rings.iter().map(|a| rings.iter().position(|b| a.same_ring(b)).unwrap()).collect();
+    let sharing: Vec<f64> = (0..rings.len()).map(|a| first.iter().filter(|&&f| f == first[a]).count() as f64).collect();
+    let ring_areas: Vec<f64> = rings.iter().map(|ring| 2.0 * PI * ring.midpoint().0 * ring.length()).collect();
+
+    let m = elements.len();
+    let mut between_rings = Array2::from_elem((m, m), f64::NAN);
+    let mut view_factors = Array2::zeros((m, m));
+    for a in (0..m).filter(|&a| open[a] && ring_areas[a] > 1e-14) {
+        for b in (0..m).filter(|&b| open[b] && ring_areas[b] > 1e-14) {
+            let (u, v) = (first[a], first[b]);
+            if between_rings[[u, v]].is_nan() {
+                let f = ring_factor(&rings[u], &rings[v], grid, axis);
+                between_rings[[u, v]] = f;
+                between_rings[[v, u]] = f * ring_areas[u] / ring_areas[v];
+            }
+            view_factors[[a, b]] = between_rings[[u, v]] / sharing[b];
+        }
+    }
+    let areas = ring_areas.iter().zip(&sharing).map(|(area, n)| area / n).collect();
+    (view_factors, areas)
+}
+
+// F_ab = 1/(r_a L_a) ∫ r_a ds_a ∫ r_b ds_b ∫ cos θ_a cos θ_b / (π s²) dφ over the profiles
+// of ring a at azimuth 0 and ring b, cosines taken only where both face the chord.
+// The azimuth runs over φ = π t², t uniform, and is doubled for the mirror half turn.
+fn ring_factor(a: &Element, b: &Element, grid: &Grid, axis: f64) -> f64 {
+    let near = distance(a.midpoint(), b.midpoint()) < NEAR * a.length().max(b.length());
+    let pieces = if near { NEAR_PIECES } else { PIECES };
+    let (p, q) = (a.launch(grid.spacing), b.launch(grid.spacing));
+    let (na, nb) = (a.normal, b.normal);
+
+    let mut integral = 0.0;
+    for k in 0..AZIMUTHS {
+        let t = (k as f64 + 0.5) / AZIMUTHS as f64;
+        let (phi, weight) = (PI * t * t, 4.0 * PI * t / AZIMUTHS as f64);
+        let (cos, sin) = (phi.cos(), phi.sin());
+        let mut sum = 0.0;
+        for ((ra, za), dsa) in a.pieces(pieces) {
+            for ((rb, zb), dsb) in b.pieces(pieces) {
+                let d = (rb * cos - ra, rb * sin, zb - za);
+                let s2 = d.0 * d.0 + d.1 * d.1 + d.2 * d.2;
+                let from_a = d.0 * na.0 + d.2 * na.1;
+                let into_b = -(d.0 * nb.0 * cos + d.1 * nb.0 * sin + d.2 * nb.1);
+                if from_a > 0.0 && into_b > 0.0 {
+                    sum += ra * dsa * rb * dsb * from_a * into_b / (PI * s2 * s2);
+                }
+            }
+        }
+        // Only azimuths that contribute are worth tracing
+        if sum > 0.0 && grid.clear_chord(p, q, phi, axis) {
+            integral += weight * sum;
+        }
+    }
+    integral / (a.midpoint().0 * a.length())
+}
@@ -0,0 +1,224 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::mask::{Obstacle, Shape};
+use crate::simulation::Domain;
+
+// Material regions of the furnace cross-section. Only the melt carries convection;
+// every other region conducts heat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Region {
+    Melt,
+    Crystal,
+    Crucible,
+    Susceptor,
+    Gas, // Ambient gas above the melt, treated as a conductor
+    HeatShield,
+}
+
+impl Region {
+    pub fn is_fluid(self) -> bool {
+        self == Region::Melt
+    }
+
+    // Stable numeric id used when storing results.
+    pub fn id(self) -> i16 {
+        match self {
+            Region::Melt => 0,
+            Region::Crystal => 1,
+            Region::Crucible => 2,
+            Region::Susceptor => 3,
+            Region::Gas => 4,
+            Region::HeatShield => 5,
+        }
+    }
+
+    pub fn from_id(id: i16) -> Option<Self> {
+        match id {
+            0 => Some(Region::Melt),
+            1 => Some(Region::Crystal),
+            2 => Some(Region::Crucible),
+            3 => Some(Region::Susceptor),
+            4 => Some(Region::Gas),
+            5 => Some(Region::HeatShield),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "melt" => Ok(Region::Melt),
+            "crystal" => Ok(Region::Crystal),
+            "crucible" => Ok(Region::Crucible),
+            "susceptor" => Ok(Region::Susceptor),
+            "gas" => Ok(Region::Gas),
+            "heat-shield" | "heat_shield" => Ok(Region::HeatShield),
+            other => anyhow::bail!("unknown region '{}'", other),
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Region::Melt => "melt",
+            Region::Crystal => "crystal",
+            Region::Crucible => "crucible",
+            Region::Susceptor => "susceptor",
+            Region::Gas => "gas",
+            Region::HeatShield => "heat-shield",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Thermal properties relative to the melt: k / k_melt and ρc / (ρc)_melt.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    pub conductivity: f64,
+    pub heat_capacity: f64,
+}
+
+impl Material {
+    pub const MELT: Material = Material { conductivity: 1.0, heat_capacity: 1.0 };
+}
+
+// Cross-section of a crucible setup filling the domain, all lengths in units of the domain
+// height. From the outside in: susceptor and crucible walls along the sides and bottom, the
+// melt up to `melt_height`, and above it the crystal (centred, `crystal_width` wide,
+// `crystal_length` long) in gas.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RegionLayout {
+    pub susceptor_thickness: f64,
+    pub crucible_thickness: f64,
+    pub melt_height: f64, // Free surface height above the domain bottom
+    pub crystal_width: f64,
+    #[serde(default)]
+    pub crystal_length: Option<f64>, // From the melt surface up; to the top of the domain if None
+    pub crystal: Material,
+    pub crucible: Material,
+    pub susceptor: Material,
+    pub gas: Material,
+    pub heat_shield: Material,
+}
+
+impl Default for RegionLayout {
+    // Silicon in a quartz crucible on a graphite susceptor, properties relative to liquid Si.
+    // The gas diffusivity is capped at the melt's so the explicit step stays stable;
+    // its heat capacity is irrelevant on the melt time scale.
+    fn default() -> Self {
+        RegionLayout {
+            susceptor_thickness: 0.05,
+            crucible_thickness: 0.05,
+            melt_height: 0.75,
+            crystal_width: 0.4,
+            crystal_length: None,
+            crystal: Material { conductivity: 0.35, heat_capacity: 0.9 },
+            crucible: Material { conductivity: 0.04, heat_capacity: 1.0 },
+            susceptor: Material { conductivity: 0.6, heat_capacity: 1.4 },
+            gas: Material { conductivity: 0.001, heat_capacity: 0.001 },
+            heat_shield: Material { conductivity: 0.8, heat_capacity: 1.2 },
+        }
+    }
+}
+
+impl RegionLayout {
+    // Region containing the point (x, y) of a domain `width` wide.
+    pub fn region_at(&self, x: f64, y: f64, width: f64) -> Region {
+        let s = self.susceptor_thickness;
+        let c = s + self.crucible_thickness;
+        let x_wall = x.min(width - x);
+
+        if x_wall < s || y < s {
+            return Region::Susceptor;
+        }
+        if x_wall < c || y < c {
+            return Region::Crucible;
+        }
+        if y <= self.melt_height {
+            return Region::Melt;
+        }
+        let crystal_top = self.crystal_length.map_or(1.0, |length| self.melt_height + length);
+        if (x - 0.5 * width).abs() <= 0.5 * self.crystal_width && y <= crystal_top {
+            Region::Crystal
+        } else {
+            Region::Gas
+        }
+    }
+
+    pub fn material(&self, region: Region) -> Material {
+        match region {
+            Region::Melt => Material::MELT,
+            Region::Crystal => self.crystal,
+            Region::Crucible => self.crucible,
+            Region::Susceptor => self.susceptor,
+            Region::Gas => self.gas,
+            Region::HeatShield => self.heat_shield,
+        }
+    }
+
+    // Inner width of the crucible, between its side walls.
+    pub fn crucible_width(&self, width: f64) -> f64 {
+        width - 2.0 * (self.susceptor_thickness + self.crucible_thickness)
+    }
+
+    // Rasterize the layout onto an (ny, nx) grid of the domain. With periodic x the ghost
+    // columns are left to the caller, which copies them from the interior ones.
+    pub fn rasterize(&self, nx: usize, ny: usize, domain: &Domain) -> Array2<Region> {
+        let (dx, dy) = domain.spacing(nx, ny);
+        let first = domain.first_column() as f64;
+        Array2::from_shape_fn((ny, nx), |(i, j)| self.region_at((j as f64 - first) * dx, i as f64 * dy, domain.width))
+    }
+}
+
+// Furnace parts the run command adds to the layout, painted in this order: a rounded
+// crucible bottom of depth `curved_bottom`, the end of the crystal dipped `immersed_crystal`
+// into the melt and a heat shield on each side of the crystal, 0.1 wide and 0.05 clear of
+// it and of the melt. Without a layout the melt fills the domain and the crystal is 0.4
+// wide.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FurnaceParts {
+    pub curved_bottom: Option<f64>,
+    pub immersed_crystal: Option<f64>,
+    pub heat_shield: bool,
+}
+
+impl FurnaceParts {
+    pub fn obstacles(&self, layout: Option<&RegionLayout>, width: f64) -> Vec<Obstacle> {
+        let mut shapes = Vec::new();
+        if let Some(depth) = self.curved_bottom {
+            shapes.push(Obstacle { shape: Shape::CurvedBottom { depth }, region: Region::Crucible });
+        }
+        let (surface, half_width) = layout.map_or((1.0, 0.2), |l| (l.melt_height, 0.5 * l.crystal_width));
+        let centre = 0.5 * width;
+        if let Some(depth) = self.immersed_crystal {
+            shapes.push(Obstacle {
+                shape: Shape::Rect { x0: centre - half_width, y0: surface - depth, x1: centre + half_width, y1: 1.0 },
+                region: Region::Crystal,
+            });
+        }
+        if self.heat_shield {
+            for (x0, x1) in [(centre - half_width - 0.15, centre - half_width - 0.05), (centre + half_width + 0.05, centre + half_width + 0.15)] {
+                shapes.push(Obstacle { shape: Shape::Rect { x0, y0: surface + 0.05, x1, y1: 1.0 }, region: Region::HeatShield });
+            }
+        }
+        shapes
+    }
+}
+
+// Conductivity at the face between two cells: the harmonic mean keeps the heat flux
+// continuous across a material interface.
+pub fn face_conductivity(k1: f64, k2: f64) -> f64 {
+    if k1 == k2 {
+        k1
+    } else {
+        2.0 * k1 * k2 / (k1 + k2)
+    }
+}
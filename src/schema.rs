@@ -1,5 +1,119 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    continuation_points (id) {
+        id -> Int8,
+        run_id -> Int4,
+        point_index -> Int4,
+        parameter -> Text,
+        parameter_value -> Float8,
+        nusselt -> Float8,
+        max_stream -> Float8,
+        arclength -> Float8,
+        growth_rate -> Nullable<Float8>,
+        frequency -> Nullable<Float8>,
+        kind -> Text,
+    }
+}
+
+diesel::table! {
+    diagnostics (id) {
+        id -> Int8,
+        run_id -> Int4,
+        step -> Int4,
+        sim_time -> Float8,
+        nu_bottom -> Float8,
+        nu_top -> Float8,
+        nu_left -> Float8,
+        nu_right -> Float8,
+        kinetic_energy -> Float8,
+        enstrophy -> Float8,
+        max_stream -> Float8,
+        max_u -> Float8,
+        max_u_x -> Float8,
+        max_u_y -> Float8,
+        max_v -> Float8,
+        max_v_x -> Float8,
+        max_v_y -> Float8,
+        heat_in -> Float8,
+        heat_out -> Float8,
+        storage_rate -> Float8,
+        heat_imbalance -> Float8,
+        divergence -> Float8,
+        poisson_residual -> Float8,
+        heat_generated -> Float8,
+    }
+}
+
+diesel::table! {
+    ensemble_fields (id) {
+        id -> Int8,
+        ensemble_id -> Int4,
+        statistic -> Text,
+        x -> Int4,
+        y -> Int4,
+        temperature -> Float8,
+        stream_function -> Float8,
+        u_velocity -> Float8,
+        v_velocity -> Float8,
+    }
+}
+
+diesel::table! {
+    ensemble_statistics (id) {
+        id -> Int8,
+        ensemble_id -> Int4,
+        output -> Text,
+        completed -> Int4,
+        failed -> Int4,
+        mean -> Float8,
+        std -> Float8,
+        p05 -> Float8,
+        p50 -> Float8,
+        p95 -> Float8,
+    }
+}
+
+diesel::table! {
+    ensembles (id) {
+        id -> Int4,
+        description -> Text,
+        method -> Text,
+        members -> Int4,
+        seed -> Int8,
+        inputs -> Text,
+        time_steps -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    growth_history (id) {
+        id -> Int8,
+        run_id -> Int4,
+        step -> Int4,
+        sim_time -> Float8,
+        crystal_length -> Float8,
+        melt_height -> Float8,
+    }
+}
+
+diesel::table! {
+    probe_samples (id) {
+        id -> Int8,
+        run_id -> Int4,
+        probe -> Text,
+        step -> Int4,
+        sim_time -> Float8,
+        x -> Float8,
+        y -> Float8,
+        temperature -> Float8,
+        u_velocity -> Float8,
+        v_velocity -> Float8,
+        vorticity -> Float8,
+    }
+}
+
 diesel::table! {
     results (id) {
         id -> Int8,
@@ -9,6 +123,13 @@ diesel::table! {
         temperature -> Float8,
         u_velocity -> Float8,
         v_velocity -> Float8,
+        region -> Int2,
+        cell_type -> Int2,
+        vorticity -> Nullable<Float8>,
+        stream_function -> Nullable<Float8>,
+        concentration -> Nullable<Float8>,
+        gas_vorticity -> Nullable<Float8>,
+        gas_stream_function -> Nullable<Float8>,
     }
 }
 
@@ -21,12 +142,61 @@ diesel::table! {
         prandtl_number -> Float8,
         rayleigh_number -> Float8,
         created_at -> Timestamp,
+        parent_run_id -> Nullable<Int4>,
+        perturbation -> Nullable<Text>,
+        perturbation_seed -> Nullable<Int8>,
+        material -> Nullable<Text>,
+        crucible_radius -> Nullable<Float8>,
+        melt_height -> Nullable<Float8>,
+        crystal_radius -> Nullable<Float8>,
+        delta_t -> Nullable<Float8>,
+        crystal_rotation -> Nullable<Float8>,
+        crucible_rotation -> Nullable<Float8>,
+        magnetic_field -> Nullable<Float8>,
+        gravity -> Nullable<Float8>,
+        grashof_number -> Nullable<Float8>,
+        marangoni_number -> Nullable<Float8>,
+        reynolds_number -> Nullable<Float8>,
+        taylor_number -> Nullable<Float8>,
+        hartmann_number -> Nullable<Float8>,
+        heat_imbalance -> Nullable<Float8>,
+        divergence -> Nullable<Float8>,
+        poisson_residual -> Nullable<Float8>,
+        conservation_flagged -> Nullable<Bool>,
+        status -> Text,
+        failure_reason -> Nullable<Text>,
+        domain_width -> Float8,
+        periodic -> Bool,
+        heat_sources -> Nullable<Text>,
+        coil_frequency -> Nullable<Float8>,
+        coil_current -> Nullable<Float8>,
+        joule_power -> Nullable<Float8>,
+        gas_inflow -> Nullable<Float8>,
+        gas_ports -> Nullable<Text>,
+        ensemble_id -> Nullable<Int4>,
+        ensemble_sample -> Nullable<Text>,
+        parameters -> Nullable<Text>,
+        final_time -> Nullable<Float8>,
     }
 }
 
+diesel::joinable!(continuation_points -> simulation_runs (run_id));
+diesel::joinable!(diagnostics -> simulation_runs (run_id));
+diesel::joinable!(ensemble_fields -> ensembles (ensemble_id));
+diesel::joinable!(ensemble_statistics -> ensembles (ensemble_id));
+diesel::joinable!(growth_history -> simulation_runs (run_id));
+diesel::joinable!(probe_samples -> simulation_runs (run_id));
 diesel::joinable!(results -> simulation_runs (run_id));
+diesel::joinable!(simulation_runs -> ensembles (ensemble_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    continuation_points,
+    diagnostics,
+    ensemble_fields,
+    ensemble_statistics,
+    ensembles,
+    growth_history,
+    probe_samples,
     results,
     simulation_runs,
 );
\ No newline at end of file
@@ -1,21 +1,221 @@
-use ndarray::{Array, Array2, Axis};
+use ndarray::{Array, Array2};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::boundary::{ThermalBoundary, Wall};
+use crate::checkpoint::{self, Checkpoint, CheckpointMeta};
+use crate::conservation::{self, ConservationMonitor};
+use crate::diagnostics::{self, DiagnosticsLog, WALLS};
+use crate::gas::{self, GasFlow, GasNode, GasParameters};
+use crate::growth::{Growth, GrowthParameters};
+use crate::heating::{HeatSource, HeatSourceField};
+use crate::induction::{self, EddyCurrentSolver, InductionParameters};
+use crate::mask::{classify, floating_solids, CellType, Obstacle};
+use crate::perturbation::Perturbation;
+use crate::probes::ProbeSet;
+use crate::radiation::RadiationModel;
+use crate::regions::{face_conductivity, Region, RegionLayout};
+use crate::solutal::SolutalParameters;
+use crate::verification::ManufacturedSources;
+use crate::watchdog::{BlowUp, CflStats, Snapshot, Watchdog};
 
 // Holds the parameters for a simulation run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimParameters {
     pub nx: usize, // Number of grid points in x
     pub ny: usize, // Number of grid points in y
     pub dt: f64,   // Time step
     pub pr: f64,   // Prandtl number
     pub ra: f64,   // Rayleigh number
+    pub thermal: ThermalBoundary, // Wall temperature conditions
+    pub layout: Option<RegionLayout>, // Solid regions around the melt; melt only if None
+    pub growth: Option<GrowthParameters>, // Crystal pulling; requires a layout
+    pub obstacles: Vec<Obstacle>, // Shapes painted over the layout, in order
+    #[serde(default)]
+    pub solutal: Option<SolutalParameters>, // Thermosolutal convection; temperature only if None
+    #[serde(default)]
+    pub domain: Domain,
+    #[serde(default)]
+    pub heat_sources: Vec<HeatSource>, // Volumetric heating, summed
+    #[serde(default)]
+    pub induction: Option<InductionParameters>, // Joule heating by an RF coil; none if None
+    #[serde(default)]
+    pub gas: Option<GasParameters>, // Purge gas flow in the gas region; gas at rest if None
+}
+
+impl SimParameters {
+    // Square melt-only cavity on a grid_size² grid with the default wall temperatures and
+    // a small, stable time step. Other settings are filled in with struct update syntax,
+    // e.g. `SimParameters { layout: Some(..), ..SimParameters::cavity(41, 0.71, 1e4) }`.
+    pub fn cavity(grid_size: usize, pr: f64, ra: f64) -> Self {
+        SimParameters {
+            nx: grid_size,
+            ny: grid_size,
+            dt: 0.0001,
+            pr,
+            ra,
+            thermal: ThermalBoundary::default(),
+            layout: None,
+            growth: None,
+            obstacles: Vec::new(),
+            solutal: None,
+            domain: Domain::default(),
+            heat_sources: Vec::new(),
+            induction: None,
+            gas: None,
+        }
+    }
+
+    // The cavity above stretched to `domain`, with grid_size points over the height.
+    pub fn on_domain(grid_size: usize, pr: f64, ra: f64, domain: Domain) -> Self {
+        SimParameters { nx: domain.nx(grid_size), domain, ..SimParameters::cavity(grid_size, pr, ra) }
+    }
+
+    // Grid spacing (Δx, Δy) of the domain.
+    pub fn spacing(&self) -> (f64, f64) {
+        self.domain.spacing(self.nx, self.ny)
+    }
+
+    // Refuse parameters the solver cannot run, for those built outside the CLI such as
+    // through the C and Python interfaces: NaN would pass every comparison the solver makes
+    // and fill the fields with it, and a zero or non-finite width or an nx that does not
+    // match the domain gives a wrong or NaN spacing.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let (ra, pr, dt) = (self.ra, self.pr, self.dt);
+        anyhow::ensure!(
+            ra >= 0.0 && ra.is_finite() && pr > 0.0 && pr.is_finite() && dt > 0.0 && dt.is_finite(),
+            "need finite Ra >= 0, Pr > 0 and dt > 0, got Ra = {}, Pr = {}, dt = {}",
+            ra,
+            pr,
+            dt
+        );
+        let width = self.domain.width;
+        anyhow::ensure!(width > 0.0 && width.is_finite(), "need a finite, positive domain width, got {}", width);
+        anyhow::ensure!(self.ny >= 3, "need ny >= 3, got {}", self.ny);
+        let nx = self.domain.nx(self.ny);
+        anyhow::ensure!(
+            self.nx == nx,
+            "a domain {} wide with ny = {} has nx = {}{}, got {}",
+            width,
+            self.ny,
+            nx,
+            if self.domain.periodic { " including the two ghost columns" } else { "" },
+            self.nx
+        );
+        self.thermal.validate()
+    }
+
+    // Region map from the layout (all melt without one) with the obstacles painted on top.
+    pub fn regions(&self) -> Array2<Region> {
+        let mut region = match &self.layout {
+            Some(layout) => layout.rasterize(self.nx, self.ny, &self.domain),
+            None => Array::from_elem((self.ny, self.nx), Region::Melt),
+        };
+        for obstacle in &self.obstacles {
+            obstacle.paint(&mut region, &self.domain);
+        }
+        if self.domain.periodic {
+            wrap_columns(&mut region);
+        }
+        region
+    }
+
+    // Largest stable forward Euler step with the fields at rest, from the parameters alone:
+    // what `Simulation::stable_time_step` gives for a new simulation, without building one.
+    pub fn stable_time_step(&self) -> f64 {
+        let region = self.regions();
+        let default_layout = RegionLayout::default();
+        let layout = self.layout.as_ref().unwrap_or(&default_layout);
+        let conduction = region.iter().map(|r| {
+            let material = layout.material(*r);
+            material.conductivity / material.heat_capacity
+        });
+        let (dx, dy) = self.spacing();
+        let inflow = self.gas.as_ref().map_or(0.0, |gas| GasFlow::new(gas, &region, (dx, dy)).fastest_port() * (1.0 / dx + 1.0 / dy));
+        stable_step(self.fastest_diffusivity(conduction), (dx, dy), inflow)
+    }
+
+    // Largest of Pr, 1/Le, the gas viscosity and the thermal diffusivities k/ρc of the
+    // materials, the diffusivities of vorticity, solute and heat.
+    fn fastest_diffusivity(&self, conduction: impl Iterator<Item = f64>) -> f64 {
+        let mut diffusivity = self.pr.max(1.0);
+        if let Some(gas) = &self.gas {
+            diffusivity = diffusivity.max(gas.viscosity);
+        }
+        if let Some(solutal) = &self.solutal {
+            diffusivity = diffusivity.max(1.0 / solutal.lewis);
+        }
+        conduction.fold(diffusivity, f64::max)
+    }
+}
+
+// Δt with Δt (2 D (1/Δx² + 1/Δy²) + a) = 1, the forward Euler limit for the diffusivity D
+// and the advective rate a = max |u|/Δx + |v|/Δy.
+fn stable_step(diffusivity: f64, (dx, dy): (f64, f64), advection: f64) -> f64 {
+    1.0 / (2.0 * diffusivity * (1.0 / (dx * dx) + 1.0 / (dy * dy)) + advection)
+}
+
+// Width of the domain in units of its height, the length scale of Ra, and whether the
+// side boundaries are periodic instead of walls.
+//
+// With periodic x the layer is bounded only by the bottom and top walls, where ψ = 0:
+// the stream function is fixed up to the constant of the bottom wall, and setting the
+// same value on the top wall fixes the net horizontal flux through the layer to zero,
+// as if a mean pressure gradient held the mean flow at rest.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Domain {
+    pub width: f64,
+    pub periodic: bool,
+}
+
+impl Default for Domain {
+    fn default() -> Self {
+        Domain { width: 1.0, periodic: false }
+    }
+}
+
+impl Domain {
+    // Grid points in x for square cells with ny points over the height.
+    pub fn nx(&self, ny: usize) -> usize {
+        let columns = ((ny as f64 - 1.0) * self.width).round().max(2.0) as usize;
+        if self.periodic { columns + 2 } else { columns + 1 }
+    }
+
+    // Grid spacing (Δx, Δy) of an nx × ny grid. With periodic x the first and last columns
+    // are ghost copies of the last and first interior ones, so nx − 2 columns span the width.
+    pub fn spacing(&self, nx: usize, ny: usize) -> (f64, f64) {
+        let columns = if self.periodic { nx - 2 } else { nx - 1 };
+        (self.width / columns as f64, 1.0 / (ny as f64 - 1.0))
+    }
+
+    // Column at x = 0: the first one, or the one after the ghost column with periodic x.
+    pub fn first_column(&self) -> usize {
+        if self.periodic { 1 } else { 0 }
+    }
+}
+
+// Refresh the ghost columns of a field that is periodic in x.
+pub fn wrap_columns<T: Clone>(field: &mut Array2<T>) {
+    let nx = field.ncols();
+    let (first, last) = (field.column(1).to_owned(), field.column(nx - 2).to_owned());
+    field.column_mut(0).assign(&last);
+    field.column_mut(nx - 1).assign(&first);
 }
 
 // Holds the state of the simulation at a given time.
+#[derive(Clone)]
 pub struct SimState {
     pub temp: Array2<f64>,        // Temperature
     pub vort: Array2<f64>,        // Vorticity
     pub stream: Array2<f64>,      // Stream function
     pub u: Array2<f64>,           // X-velocity
     pub v: Array2<f64>,           // Y-velocity
+    pub conc: Array2<f64>,        // Solute concentration, zero without a solutal model
+    pub gas_vort: Array2<f64>,    // Vorticity of the purge gas, zero without a gas flow
+    pub gas_stream: Array2<f64>,  // Stream function of the purge gas
+    pub region: Array2<Region>,   // Material region of each node
+    pub mask: Array2<CellType>,   // Fluid/solid/boundary role of each node
 }
 
 impl SimState {
@@ -26,122 +226,776 @@ impl SimState {
             stream: Array::zeros((ny, nx)),
             u: Array::zeros((ny, nx)),
             v: Array::zeros((ny, nx)),
+            conc: Array::zeros((ny, nx)),
+            gas_vort: Array::zeros((ny, nx)),
+            gas_stream: Array::zeros((ny, nx)),
+            region: Array::from_elem((ny, nx), Region::Melt),
+            mask: classify(&Array::from_elem((ny, nx), Region::Melt), false),
         }
     }
+
+    pub fn is_fluid(&self, i: usize, j: usize) -> bool {
+        self.mask[[i, j]] == CellType::Fluid
+    }
+
+    // ω = ∂v/∂x − ∂u/∂y with central differences on the fluid nodes; with periodic x the
+    // ghost columns are copied from the interior ones afterwards.
+    pub fn vorticity_from_velocity(&self, domain: &Domain) -> Array2<f64> {
+        let (ny, nx) = self.u.dim();
+        let (dx, dy) = domain.spacing(nx, ny);
+        let mut vort = Array::zeros((ny, nx));
+        for i in 1..ny-1 {
+            for j in 1..nx-1 {
+                if self.is_fluid(i, j) {
+                    vort[[i, j]] = (self.v[[i, j+1]] - self.v[[i, j-1]]) / (2.0*dx)
+                                 - (self.u[[i+1, j]] - self.u[[i-1, j]]) / (2.0*dy);
+                }
+            }
+        }
+        if domain.periodic {
+            wrap_columns(&mut vort);
+        }
+        vort
+    }
+}
+
+// Time derivatives of the transported fields at the interior nodes.
+pub struct Tendencies {
+    pub vort: Array2<f64>,
+    pub temp: Array2<f64>,
+    pub conc: Array2<f64>,
+}
+
+// Where and how often `Simulation::run` writes checkpoints.
+pub struct CheckpointSettings {
+    pub path: PathBuf,
+    pub interval: usize, // Steps between checkpoints; 0 writes only on SIGINT/SIGTERM
+    pub run_id: Option<i32>,
+}
+
+// How a call to `Simulation::run` ended.
+#[derive(Debug, PartialEq)]
+pub enum RunOutcome {
+    Completed,
+    Interrupted, // Stopped by a signal after writing a checkpoint
+    BlewUp(BlowUp), // Stopped by the watchdog before the fields became unusable
 }
 
 // Main simulation controller.
 pub struct Simulation {
     pub params: SimParameters,
     pub state: SimState,
+    pub time: f64,       // Elapsed dimensionless time
+    pub steps_taken: usize,
+    pub growth: Option<Growth>, // Crystal length and melt level history
+    pub checkpoint: Option<CheckpointSettings>,
+    pub diagnostics: Option<DiagnosticsLog>, // Time series of global measures, if enabled
+    pub probes: Option<ProbeSet>,             // Time series at probe points and along lines
+    pub conservation: ConservationMonitor,    // Heat, mass and stream function balance of each step
+    pub watchdog: Watchdog,                   // Stops the run when the fields blow up
+    pub sources: Option<ManufacturedSources>, // Manufactured solution forcing, verification only
+    pub gas: Option<GasFlow>,                 // Purge gas flow on the current geometry, if enabled
+    gas_dt: f64, // Step limit of the gas flow on the current geometry, infinite without one
     dx: f64,
     dy: f64,
+    radiation: Option<RadiationModel>, // Surface-to-surface exchange, if enabled
+    eddy_currents: Option<EddyCurrentSolver>, // Induction coil field, if enabled
+    heat_source: HeatSourceField, // Volumetric heating on the current geometry
+    conductivity: Array2<f64>,  // k / k_melt per node
+    heat_capacity: Array2<f64>, // ρc / (ρc)_melt per node
 }
 
 impl Simulation {
     pub fn new(params: SimParameters) -> Self {
-        let mut sim = Simulation {
-            dx: 1.0 / (params.nx as f64 - 1.0),
-            dy: 1.0 / (params.ny as f64 - 1.0),
-            state: SimState::new(params.nx, params.ny),
-            params,
-        };
+        let mut sim = Simulation::uninitialized(params);
         sim.initialize_conditions();
         sim
     }
 
+    // All parts sized for `params`, with the fields at rest and no geometry yet.
+    fn uninitialized(params: SimParameters) -> Self {
+        Simulation {
+            dx: params.spacing().0,
+            dy: params.spacing().1,
+            state: SimState::new(params.nx, params.ny),
+            radiation: None,
+            eddy_currents: params
+                .induction
+                .as_ref()
+                .map(|induction| EddyCurrentSolver::new(induction, params.ny, params.spacing(), &params.domain)),
+            heat_source: HeatSourceField::default(),
+            conductivity: Array::ones((params.ny, params.nx)),
+            heat_capacity: Array::ones((params.ny, params.nx)),
+            time: 0.0,
+            steps_taken: 0,
+            growth: None,
+            checkpoint: None,
+            diagnostics: None,
+            probes: None,
+            conservation: ConservationMonitor::default(),
+            watchdog: Watchdog::default(),
+            sources: None,
+            gas: None,
+            gas_dt: f64::INFINITY,
+            params,
+        }
+    }
+
+    // Use the fields of a stored state as the initial condition, e.g. to continue a run
+    // at a different Ra or Pr. Geometry and boundary conditions stay those of `params`;
+    // nodes that are not fluid in the new geometry start at rest.
+    pub fn restart_from(&mut self, state: SimState) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            state.temp.dim() == self.state.temp.dim(),
+            "stored state is {:?} but the simulation grid is {:?}",
+            state.temp.dim(),
+            self.state.temp.dim()
+        );
+        self.state.temp = state.temp;
+        self.state.vort = state.vort;
+        self.state.stream = state.stream;
+        self.state.u = state.u;
+        self.state.v = state.v;
+        self.state.conc = state.conc;
+        self.state.gas_vort = state.gas_vort;
+        self.state.gas_stream = state.gas_stream;
+        for ((i, j), cell) in self.state.mask.indexed_iter() {
+            if *cell != CellType::Fluid {
+                self.state.stream[[i, j]] = 0.0;
+                self.state.u[[i, j]] = 0.0;
+                self.state.v[[i, j]] = 0.0;
+            }
+        }
+        self.apply_wall_conditions();
+        Ok(())
+    }
+
+    // Seed the initial fields with a reproducible perturbation to leave symmetric states.
+    pub fn perturb(&mut self, perturbation: &Perturbation) {
+        perturbation.apply(&mut self.state, &self.params.domain);
+        self.apply_wall_conditions();
+    }
+
     // Set initial and boundary conditions.
     fn initialize_conditions(&mut self) {
-        let (ny, nx) = self.state.temp.dim();
-        // Boundary Conditions: Hot bottom/sides, cold top (the "crystal")
-        self.state.temp.row_mut(0).fill(1.0); // Bottom wall
-        self.state.temp.row_mut(ny - 1).fill(0.0); // Top wall
-        self.state.temp.column_mut(0).fill(1.0); // Left wall
-        self.state.temp.column_mut(nx-1).fill(1.0); // Right wall
+        // Regions, cell mask and material properties
+        self.state.region = self.params.regions();
+        self.update_materials();
+
+        // Crystal growth starts from the initial melt level
+        if let (Some(growth), Some(layout)) = (self.params.growth, &self.params.layout) {
+            let mut growth = Growth::new(growth, layout);
+            growth.record(0, 0.0, layout);
+            self.growth = Some(growth);
+        }
+
+        // Boundary Conditions: by default hot bottom/sides, cold top (the "crystal"),
+        // and the same pattern for the concentration
+        self.apply_wall_conditions();
+    }
+
+    // Refresh the cell mask, per-node material properties and heat sources from the region
+    // map. Obstacle regions fall back to the default layout's materials when there is no
+    // layout. The eddy currents are solved again, warm started, as the conductors moved,
+    // and the gas flow takes the new shape of the gas region.
+    fn update_materials(&mut self) {
+        let joule = self.eddy_currents.as_mut().map(|solver| solver.solve(&self.state.region));
+        self.set_materials(joule);
+        if let Some(gas) = &self.gas {
+            // The gas diffuses momentum faster than the melt and enters fast, so a gas run
+            // keeps to half its stable step, which the outlet speeds of the new shape set
+            self.gas_dt = 0.5 * self.stable_time_step();
+            // Nodes the gas left lose its flow, and gas walls carry no velocity
+            for ((i, j), node) in gas.nodes.indexed_iter() {
+                match node {
+                    GasNode::Outside => {
+                        self.state.gas_vort[[i, j]] = 0.0;
+                        self.state.gas_stream[[i, j]] = 0.0;
+                    }
+                    GasNode::Wall | GasNode::Surface => {
+                        self.state.u[[i, j]] = 0.0;
+                        self.state.v[[i, j]] = 0.0;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Everything that follows from the region map alone, given the Joule heating.
+    fn set_materials(&mut self, joule: Option<Array2<f64>>) {
+        self.state.mask = classify(&self.state.region, self.params.domain.periodic);
+        let default_layout = RegionLayout::default();
+        let layout = self.params.layout.as_ref().unwrap_or(&default_layout);
+        for ((i, j), region) in self.state.region.indexed_iter() {
+            let material = layout.material(*region);
+            self.conductivity[[i, j]] = material.conductivity;
+            self.heat_capacity[[i, j]] = material.heat_capacity;
+        }
+        self.heat_source = HeatSourceField::new(&self.params.heat_sources, &self.state.region, &self.params.domain, joule);
+        self.gas = self.params.gas.as_ref().map(|params| GasFlow::new(params, &self.state.region, (self.dx, self.dy)));
+        // Solids and melt shadow radiating walls and the melt surface moves, so the view
+        // factors follow the geometry
+        let thermal = &self.params.thermal;
+        let rad = &thermal.radiation;
+        self.radiation = (rad.surface_to_surface || rad.melt_surface.is_some())
+            .then(|| RadiationModel::new(thermal, &self.params.domain, &self.state.region));
+    }
+
+    // Reject geometries the flow solve gets wrong: solids inside the melt that touch no
+    // wall would be held at the walls' ψ = 0 instead of carrying their own circulation,
+    // and purge gas needs a gas region with a way out for every inlet.
+    pub fn check_geometry(&self) -> anyhow::Result<()> {
+        if let Some(gas) = &self.gas {
+            gas.check()?;
+        }
+        let floating = floating_solids(&self.state.mask, self.params.domain.periodic);
+        if let Some(&(i, j)) = floating.first() {
+            anyhow::bail!(
+                "{} solid nodes, e.g. (i = {}, j = {}) in the {}, do not touch a wall; obstacles in the melt must be joined to a wall",
+                floating.len(),
+                i,
+                j,
+                self.state.region[[i, j]]
+            );
+        }
+        Ok(())
+    }
+
+    // Joule heating of the induction coil on the current geometry, if there is a coil.
+    pub fn joule_heating(&self) -> Option<&Array2<f64>> {
+        self.heat_source.joule.as_ref()
+    }
+
+    // ∫ Q dA of the Joule heating over the interior nodes.
+    pub fn joule_power(&self) -> Option<f64> {
+        self.joule_heating().map(|joule| conservation::generated_heat(joule, (self.dx, self.dy)))
+    }
+
+    // Print the total and peak Joule heating and the solver effort, if there is a coil.
+    pub fn print_induction(&self) {
+        if let (Some(params), Some(solver), Some(joule)) = (&self.params.induction, &self.eddy_currents, &self.heat_source.joule) {
+            induction::print_summary(params, joule, &self.params.domain, (self.dx, self.dy), solver.iterations);
+        }
+    }
+
+    // Impose the wall temperature conditions, including the nonlinear radiative ones,
+    // the gas inlet and outlet temperatures and the wall concentrations.
+    pub fn apply_wall_conditions(&mut self) {
+        self.params.thermal.apply(&mut self.state.temp, &self.params.domain, self.radiation.as_ref());
+        if let Some(gas) = &self.gas {
+            gas.apply_ports(&mut self.state.temp);
+        }
+        if let Some(solutal) = &self.params.solutal {
+            solutal.walls.apply(&mut self.state.conc, &self.params.domain, None);
+        }
+        if let Some(sources) = &self.sources {
+            sources.apply_walls(&mut self.state.temp, self.time);
+        }
+        if self.params.domain.periodic {
+            wrap_columns(&mut self.state.temp);
+            wrap_columns(&mut self.state.conc);
+        }
+    }
+
+    // Heating of the melt under its free surface by radiation, if the surface radiates.
+    fn surface_radiation(&self) -> Option<Array2<f64>> {
+        self.radiation.as_ref().and_then(|model| model.surface_heat(&self.state.temp, &self.params.thermal.radiation))
+    }
+
+    // The step `step` takes: `params.dt`, or half the stable step of the current gas
+    // region when that is smaller.
+    pub fn time_step(&self) -> f64 {
+        self.params.dt.min(self.gas_dt)
     }
 
     // Perform one time step.
     pub fn step(&mut self) {
         let (ny, nx) = self.state.temp.dim();
-        let dx = self.dx;
-        let dy = self.dy;
-        let dt = self.params.dt;
+        let dt = self.time_step();
 
-        let mut temp_new = self.state.temp.clone();
-        let mut vort_new = self.state.vort.clone();
+        let spacing = (self.dx, self.dy);
+        let walls: &[Wall] = if self.params.domain.periodic { &[Wall::Bottom, Wall::Top] } else { &WALLS };
+        let mut wall_heat = conservation::wall_heat(&self.state.temp, &self.conductivity, spacing, walls);
+        if let Some(gas) = &self.gas {
+            // Enthalpy carried through the gas ports
+            let (heat_in, heat_out) = gas.port_heat(&self.state.temp, &self.heat_capacity);
+            wall_heat = (wall_heat.0 + heat_in, wall_heat.1 + heat_out);
+        }
+        let mut generated = self.heat_source.at(self.time).map_or(0.0, |q| conservation::generated_heat(&q, spacing));
+        // The radiosity solve is the costliest part of a radiating step, so it runs once
+        // and feeds both the heat balance and the tendencies
+        let radiation = self.surface_radiation();
+        if let Some(q) = &radiation {
+            generated += conservation::generated_heat(q, spacing);
+        }
 
         // 1. Solve Stream Function (Poisson equation: ∇²ψ = -ω) using Jacobi iteration
-        for _ in 0..50 { // Iterate to converge
+        self.solve_stream_function(50);
+        let poisson_residual = conservation::poisson_residual(&self.state.stream, &self.stream_forcing(), &self.state, spacing);
+
+        // 2. Update Velocities (u = ∂ψ/∂y, v = -∂ψ/∂x)
+        self.update_velocities();
+
+        // 3. Update Boundary Vorticity (Thom's formula for no-slip walls)
+        self.update_wall_vorticity();
+
+        // The gas flow, with the melt surface shear it exerts
+        if let Some(gas) = &self.gas {
+            gas.solve_stream_function(&mut self.state, 50);
+            gas.update_velocities(&mut self.state);
+            gas.update_wall_vorticity(&mut self.state);
+        }
+        let divergence = conservation::divergence(&self.state, self.gas.as_ref(), spacing);
+
+        // 4. Time-step Vorticity, Temperature and Concentration (Advection-Diffusion equations)
+        let rates = self.tendencies_with(radiation.as_ref());
+        let gas_rate = self.gas.as_ref().map(|gas| gas.vorticity_rate(&self.state, self.params.ra * self.params.pr));
+        for i in 1..ny-1 {
+            for j in 1..nx-1 {
+                // Update using Forward Euler
+                self.state.vort[[i, j]] += dt * rates.vort[[i, j]];
+                self.state.temp[[i, j]] += dt * rates.temp[[i, j]];
+                self.state.conc[[i, j]] += dt * rates.conc[[i, j]];
+            }
+        }
+        if let Some(rate) = gas_rate {
+            self.state.gas_vort.scaled_add(dt, &rate);
+        }
+
+        self.time += dt;
+        self.steps_taken += 1;
+
+        // 5. Update wall temperatures and concentrations from the new interior fields
+        self.apply_wall_conditions();
+        let energy = conservation::stored_energy(&self.state.temp, &self.heat_capacity, spacing);
+        self.conservation.update(wall_heat, generated, energy, dt, divergence, poisson_residual);
+
+        // 6. Pull the crystal and lower the melt surface
+        self.advance_growth();
+    }
+
+    fn fastest_diffusivity(&self) -> f64 {
+        let conduction = self.conductivity.iter().zip(self.heat_capacity.iter()).map(|(k, c)| k / c);
+        self.params.fastest_diffusivity(conduction)
+    }
+
+    // Largest stable forward Euler step for the current velocities and material properties:
+    // Δt (2 D (1/Δx² + 1/Δy²) + |u|/Δx + |v|/Δy) ≤ 1 for the fastest diffusivity D, with
+    // |u| and |v| at least the fastest gas inlet speed while the gas flow builds up.
+    pub fn stable_time_step(&self) -> f64 {
+        let (dx, dy) = (self.dx, self.dy);
+        let inflow = self.gas.as_ref().map_or(0.0, |gas| gas.fastest_port() * (1.0 / dx + 1.0 / dy));
+        let advection = self
+            .state
+            .u
+            .iter()
+            .zip(self.state.v.iter())
+            .fold(inflow, |m, (u, v)| m.max(u.abs() / dx + v.abs() / dy));
+        stable_step(self.fastest_diffusivity(), (dx, dy), advection)
+    }
+
+    pub fn cfl_stats(&self) -> CflStats {
+        let (dx, dy) = (self.dx, self.dy);
+        let dt = self.time_step();
+        let max_u = self.state.u.iter().fold(0.0f64, |m, u| m.max(u.abs()));
+        let max_v = self.state.v.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let advection = self
+            .state
+            .u
+            .iter()
+            .zip(self.state.v.iter())
+            .fold(0.0f64, |m, (u, v)| m.max(u.abs() / dx + v.abs() / dy));
+        let diffusivity = self.fastest_diffusivity();
+        CflStats {
+            dt,
+            stable_dt: self.stable_time_step(),
+            advective: dt * advection,
+            diffusive: 2.0 * diffusivity * dt * (1.0 / (dx * dx) + 1.0 / (dy * dy)),
+            max_u,
+            max_v,
+        }
+    }
+
+    // Right-hand side ω − S_ψ of −∇²ψ, with the manufactured source if one is set.
+    fn stream_forcing(&self) -> Array2<f64> {
+        match &self.sources {
+            Some(sources) => &self.state.vort - &sources.stream(self.time),
+            None => self.state.vort.clone(),
+        }
+    }
+
+    // Fixed number of Jacobi sweeps for ∇²ψ = -ω, warm started from the current ψ.
+    pub fn solve_stream_function(&mut self, sweeps: usize) {
+        let (ny, nx) = self.state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        let forcing = self.stream_forcing();
+        for _ in 0..sweeps { // Iterate to converge
             let stream_old = self.state.stream.clone();
             for i in 1..ny - 1 {
                 for j in 1..nx - 1 {
+                    if !self.state.is_fluid(i, j) {
+                        continue; // ψ = 0 in solids
+                    }
                     self.state.stream[[i, j]] = ((stream_old[[i, j+1]] + stream_old[[i, j-1]]) * dy*dy +
-                                                (stream_old[[i+1, j]] + stream_old[[i-1, j]]) * dx*dx -
-                                                self.state.vort[[i, j]] * dx*dx*dy*dy) /
+                                                (stream_old[[i+1, j]] + stream_old[[i-1, j]]) * dx*dx +
+                                                forcing[[i, j]] * dx*dx*dy*dy) /
                                                 (2.0 * (dx*dx + dy*dy));
                 }
             }
+            if self.params.domain.periodic {
+                wrap_columns(&mut self.state.stream);
+            }
         }
+    }
 
-        // 2. Update Velocities (u = ∂ψ/∂y, v = -∂ψ/∂x)
+    // Solve ∇²ψ = -ω to a tolerance on the largest update with SOR. Used where the flow
+    // must be a function of ω alone, e.g. when linearizing the steady equations.
+    pub fn solve_stream_function_converged(&mut self, tol: f64) {
+        let (ny, nx) = self.state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        let h = dx.max(dy);
+        let forcing = self.stream_forcing();
+        let omega = 2.0 / (1.0 + (std::f64::consts::PI * h).sin()); // Optimal for the square
+        for _ in 0..100 * nx.max(ny) {
+            let mut max_update: f64 = 0.0;
+            for i in 1..ny - 1 {
+                for j in 1..nx - 1 {
+                    if !self.state.is_fluid(i, j) {
+                        continue;
+                    }
+                    let gauss_seidel = ((self.state.stream[[i, j+1]] + self.state.stream[[i, j-1]]) * dy*dy +
+                                        (self.state.stream[[i+1, j]] + self.state.stream[[i-1, j]]) * dx*dx +
+                                        forcing[[i, j]] * dx*dx*dy*dy) /
+                                        (2.0 * (dx*dx + dy*dy));
+                    let update = omega * (gauss_seidel - self.state.stream[[i, j]]);
+                    self.state.stream[[i, j]] += update;
+                    max_update = max_update.max(update.abs());
+                }
+            }
+            if self.params.domain.periodic {
+                wrap_columns(&mut self.state.stream);
+            }
+            if max_update < tol {
+                return;
+            }
+        }
+    }
+
+    // Velocities on the fluid nodes from the stream function.
+    pub fn update_velocities(&mut self) {
+        let (ny, nx) = self.state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
         for i in 1..ny-1 {
             for j in 1..nx-1 {
+                if !self.state.is_fluid(i, j) {
+                    continue;
+                }
                 self.state.u[[i,j]] = (self.state.stream[[i+1, j]] - self.state.stream[[i-1, j]]) / (2.0*dy);
                 self.state.v[[i,j]] = -(self.state.stream[[i, j+1]] - self.state.stream[[i, j-1]]) / (2.0*dx);
             }
         }
+        if self.params.domain.periodic {
+            wrap_columns(&mut self.state.u);
+            wrap_columns(&mut self.state.v);
+        }
+    }
 
-        // 3. Update Boundary Vorticity (Thom's formula for no-slip walls)
-        for j in 1..nx-1 {
-            // Bottom wall
-            self.state.vort[[0, j]] = -2.0 * self.state.stream[[1, j]] / (dy*dy);
-            // Top wall
-            self.state.vort[[ny-1, j]] = -2.0 * self.state.stream[[ny-2, j]] / (dy*dy);
+    // Every boundary node of the mask, whether on the outer walls or on a staircase
+    // obstacle, takes the mean of Thom's formula over its fluid neighbours.
+    pub fn update_wall_vorticity(&mut self) {
+        let (ny, nx) = self.state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        for i in 0..ny {
+            for j in 0..nx {
+                if self.state.mask[[i, j]] != CellType::Boundary {
+                    continue;
+                }
+                let mut wall_vort = 0.0;
+                let mut fluid_neighbours = 0;
+                let neighbours = [
+                    (i + 1 < ny).then(|| (i + 1, j, dy*dy)),
+                    (i > 0).then(|| (i - 1, j, dy*dy)),
+                    (j + 1 < nx).then(|| (i, j + 1, dx*dx)),
+                    (j > 0).then(|| (i, j - 1, dx*dx)),
+                ];
+                for (ii, jj, h2) in neighbours.into_iter().flatten() {
+                    if self.state.is_fluid(ii, jj) {
+                        wall_vort += -2.0 * self.state.stream[[ii, jj]] / h2;
+                        fluid_neighbours += 1;
+                    }
+                }
+                self.state.vort[[i, j]] = wall_vort / fluid_neighbours as f64;
+            }
         }
-        for i in 1..ny-1 {
-            // Left wall
-            self.state.vort[[i, 0]] = -2.0 * self.state.stream[[i, 1]] / (dx*dx);
-            // Right wall
-            self.state.vort[[i, nx-1]] = -2.0 * self.state.stream[[i, nx-2]] / (dx*dx);
+        if self.params.domain.periodic {
+            wrap_columns(&mut self.state.vort);
         }
+    }
 
-        // 4. Time-step Vorticity and Temperature (Advection-Diffusion equations)
+    // Time derivatives of ω, T and C at the interior nodes for the current velocities and
+    // wall vorticity; zero on the walls and, for ω and C, in solids.
+    pub fn tendencies(&self) -> Tendencies {
+        self.tendencies_with(self.surface_radiation().as_ref())
+    }
+
+    // The tendencies with the surface radiation heating already computed.
+    fn tendencies_with(&self, radiation: Option<&Array2<f64>>) -> Tendencies {
+        let (ny, nx) = self.state.temp.dim();
+        let (dx, dy) = (self.dx, self.dy);
+        let mut vort_rate = Array::zeros((ny, nx));
+        let mut temp_rate = Array::zeros((ny, nx));
+        let mut conc_rate = Array::zeros((ny, nx));
+
+        let k = &self.conductivity;
         for i in 1..ny-1 {
             for j in 1..nx-1 {
+                // Conduction in flux form with face conductivities, continuous across regions
+                let (ke, kw) = (face_conductivity(k[[i,j]], k[[i,j+1]]), face_conductivity(k[[i,j]], k[[i,j-1]]));
+                let (kn, ks) = (face_conductivity(k[[i,j]], k[[i+1,j]]), face_conductivity(k[[i,j]], k[[i-1,j]]));
+                let temp_cond = (ke * (self.state.temp[[i, j+1]] - self.state.temp[[i,j]]) - kw * (self.state.temp[[i,j]] - self.state.temp[[i,j-1]]))/(dx*dx)
+                              + (kn * (self.state.temp[[i+1, j]] - self.state.temp[[i,j]]) - ks * (self.state.temp[[i,j]] - self.state.temp[[i-1,j]]))/(dy*dy);
+
+                if !self.state.is_fluid(i, j) {
+                    // Solids only conduct heat, the purge gas also carries it
+                    temp_rate[[i, j]] = temp_cond / self.heat_capacity[[i, j]];
+                    if self.gas.as_ref().is_some_and(|gas| gas.is_fluid(i, j)) {
+                        temp_rate[[i, j]] -= gas::upwind_advection(&self.state.temp, self.state.u[[i, j]], self.state.v[[i, j]], i, j, dx, dy);
+                    }
+                    continue;
+                }
+
                 // Advection terms (using simple upwinding for stability)
                 let u = self.state.u[[i,j]];
                 let v = self.state.v[[i,j]];
 
                 let vort_adv_x = if u > 0.0 { u * (self.state.vort[[i,j]] - self.state.vort[[i,j-1]]) / dx } else { u * (self.state.vort[[i,j+1]] - self.state.vort[[i,j]]) / dx };
-                let vort_adv_y = if v > 0.0 { v * (self.state.vort[[i,j]] - self.state.vort[[i-1,j]]) / dy } else { v * (self.state.vort[[i,j+1]] - self.state.vort[[i,j]]) / dy };
+                let vort_adv_y = if v > 0.0 { v * (self.state.vort[[i,j]] - self.state.vort[[i-1,j]]) / dy } else { v * (self.state.vort[[i+1,j]] - self.state.vort[[i,j]]) / dy };
 
                 let temp_adv_x = if u > 0.0 { u * (self.state.temp[[i,j]] - self.state.temp[[i,j-1]]) / dx } else { u * (self.state.temp[[i,j+1]] - self.state.temp[[i,j]]) / dx };
-                let temp_adv_y = if v > 0.0 { v * (self.state.temp[[i,j]] - self.state.temp[[i-1,j]]) / dy } else { v * (self.state.temp[[i,j+1]] - self.state.temp[[i,j]]) / dy };
-
+                let temp_adv_y = if v > 0.0 { v * (self.state.temp[[i,j]] - self.state.temp[[i-1,j]]) / dy } else { v * (self.state.temp[[i+1,j]] - self.state.temp[[i,j]]) / dy };
 
                 // Diffusion terms
                 let vort_diff = self.params.pr * ( (self.state.vort[[i, j+1]] - 2.0*self.state.vort[[i,j]] + self.state.vort[[i,j-1]])/(dx*dx) + (self.state.vort[[i+1, j]] - 2.0*self.state.vort[[i,j]] + self.state.vort[[i-1, j]])/(dy*dy) );
-                let temp_diff = (self.state.temp[[i, j+1]] - 2.0*self.state.temp[[i,j]] + self.state.temp[[i,j-1]])/(dx*dx) + (self.state.temp[[i+1, j]] - 2.0*self.state.temp[[i,j]] + self.state.temp[[i-1, j]])/(dy*dy);
-                
-                // Buoyancy term for vorticity
-                let buoyancy = self.params.ra * self.params.pr * (self.state.temp[[i, j+1]] - self.state.temp[[i, j-1]]) / (2.0 * dx);
 
-                // Update using Forward Euler
-                vort_new[[i, j]] = self.state.vort[[i, j]] + dt * (vort_diff - vort_adv_x - vort_adv_y + buoyancy);
-                temp_new[[i, j]] = self.state.temp[[i, j]] + dt * (temp_diff - temp_adv_x - temp_adv_y);
+                // Buoyancy term for vorticity, thermal plus solutal
+                let mut buoyancy = self.params.ra * self.params.pr * (self.state.temp[[i, j+1]] - self.state.temp[[i, j-1]]) / (2.0 * dx);
+
+                if let Some(solutal) = &self.params.solutal {
+                    // Solids are impermeable: a solid neighbour mirrors the node's own value,
+                    // while the outer walls carry their prescribed concentration
+                    let c = &self.state.conc;
+                    let outer = |ii: usize, jj: usize| ii == 0 || jj == 0 || ii == ny - 1 || jj == nx - 1;
+                    let side = |ii: usize, jj: usize| if self.state.is_fluid(ii, jj) || outer(ii, jj) { c[[ii, jj]] } else { c[[i, j]] };
+                    let (ce, cw, cn, cs) = (side(i, j+1), side(i, j-1), side(i+1, j), side(i-1, j));
+
+                    let conc_diff = ((ce - 2.0*c[[i,j]] + cw)/(dx*dx) + (cn - 2.0*c[[i,j]] + cs)/(dy*dy)) / solutal.lewis;
+                    let conc_adv_x = if u > 0.0 { u * (c[[i,j]] - cw) / dx } else { u * (ce - c[[i,j]]) / dx };
+                    let conc_adv_y = if v > 0.0 { v * (c[[i,j]] - cs) / dy } else { v * (cn - c[[i,j]]) / dy };
+                    conc_rate[[i, j]] = conc_diff - conc_adv_x - conc_adv_y;
+
+                    buoyancy += self.params.ra * self.params.pr * solutal.buoyancy_ratio * (ce - cw) / (2.0 * dx);
+                }
+
+                vort_rate[[i, j]] = vort_diff - vort_adv_x - vort_adv_y + buoyancy;
+                temp_rate[[i, j]] = temp_cond - temp_adv_x - temp_adv_y;
+            }
+        }
+        // Volumetric heating, in the solids as well as in the melt
+        if let Some(q) = self.heat_source.at(self.time) {
+            for i in 1..ny-1 {
+                for j in 1..nx-1 {
+                    temp_rate[[i, j]] += q[[i, j]] / self.heat_capacity[[i, j]];
+                }
+            }
+        }
+        // Radiation leaving or reaching the melt through its free surface
+        if let Some(q) = radiation {
+            for i in 1..ny-1 {
+                for j in 1..nx-1 {
+                    temp_rate[[i, j]] += q[[i, j]] / self.heat_capacity[[i, j]];
+                }
+            }
+        }
+        let mut rates = Tendencies { vort: vort_rate, temp: temp_rate, conc: conc_rate };
+        if let Some(sources) = &self.sources {
+            sources.add_to(&mut rates, self.time);
+        }
+        rates
+    }
+
+    // Move the crystal/melt geometry forward by one step. The grid is fixed, so the
+    // domain is remeshed by re-rasterizing the layout; melt cells passed by the falling
+    // surface become solid (or gas) and lose their flow variables.
+    fn advance_growth(&mut self) {
+        let dt = self.time_step();
+        let (Some(growth), Some(layout)) = (self.growth.as_mut(), self.params.layout.as_mut()) else {
+            return;
+        };
+        let moved = growth.advance(layout, dt, self.params.domain.width);
+        if self.steps_taken.is_multiple_of(growth.params.log_interval.max(1)) {
+            growth.record(self.steps_taken, self.time, layout);
+        }
+        if !moved {
+            return;
+        }
+
+        let region = self.params.regions();
+        if region == self.state.region {
+            return;
+        }
+        let mask = classify(&region, self.params.domain.periodic);
+        for ((i, j), cell) in mask.indexed_iter() {
+            if self.state.is_fluid(i, j) && *cell != CellType::Fluid {
+                self.state.vort[[i, j]] = 0.0;
+                self.state.stream[[i, j]] = 0.0;
+                self.state.u[[i, j]] = 0.0;
+                self.state.v[[i, j]] = 0.0;
+            }
+        }
+        self.state.region = region;
+        self.update_materials();
+    }
+
+    // Rebuild a simulation exactly as it was when the checkpoint was written. Nothing is
+    // solved again: the eddy currents continue from the stored potential, so the next
+    // warm-started solve is the one the uninterrupted run would have made.
+    pub fn from_checkpoint(ckpt: Checkpoint) -> Self {
+        let meta = ckpt.meta;
+        let mut sim = Simulation::uninitialized(meta.params);
+        sim.state = ckpt.state;
+        let joule = sim.eddy_currents.as_mut().map(|solver| {
+            for (a, &[re, im]) in solver.potential.iter_mut().zip(&meta.eddy_potential) {
+                *a = Complex64::new(re, im);
             }
+            solver.joule_heating(&sim.state.region)
+        });
+        sim.set_materials(joule);
+        sim.gas_dt = meta.gas_dt;
+        sim.time = meta.time;
+        sim.steps_taken = meta.steps_taken;
+        sim.watchdog.interval = meta.watchdog_interval;
+        sim.watchdog.peaks = meta.watchdog_peaks;
+        sim.watchdog.dump_prefix = meta.dump_prefix;
+        if let (Some(params), Some(crystal_length)) = (sim.params.growth, meta.crystal_length) {
+            sim.growth = Some(Growth { params, crystal_length, history: meta.growth_history });
         }
-        self.state.vort = vort_new;
-        self.state.temp = temp_new;
+        sim.diagnostics = meta.diagnostics;
+        sim.probes = meta.probes;
+        sim.conservation = meta.conservation;
+        sim
+    }
+
+    fn checkpoint_meta(&self, target_steps: usize) -> CheckpointMeta {
+        CheckpointMeta {
+            run_id: self.checkpoint.as_ref().and_then(|settings| settings.run_id),
+            target_steps,
+            steps_taken: self.steps_taken,
+            time: self.time,
+            params: self.params.clone(),
+            gas_dt: self.gas_dt,
+            crystal_length: self.growth.as_ref().map(|g| g.crystal_length),
+            growth_history: self.growth.as_ref().map_or(Vec::new(), |g| g.history.clone()),
+            eddy_potential: self.eddy_currents.as_ref().map_or(Vec::new(), |solver| solver.potential.iter().map(|a| [a.re, a.im]).collect()),
+            watchdog_interval: self.watchdog.interval,
+            watchdog_peaks: self.watchdog.peaks,
+            dump_prefix: self.watchdog.dump_prefix.clone(),
+            diagnostics: self.diagnostics.clone(),
+            probes: self.probes.clone(),
+            conservation: self.conservation.clone(),
+            blow_up: None,
+        }
+    }
+
+    pub fn write_checkpoint(&self, target_steps: usize) -> anyhow::Result<()> {
+        let Some(settings) = &self.checkpoint else {
+            return Ok(());
+        };
+        Checkpoint::save(&self.checkpoint_meta(target_steps), &self.state, &settings.path)
+    }
+
+    // Stop on a failed watchdog check: report the step limits of the last good and first
+    // bad states and dump both in the checkpoint format, if a dump prefix is set.
+    fn blow_up(&self, reason: String, target_steps: usize) -> anyhow::Result<BlowUp> {
+        let last_good = self.watchdog.last_good.as_ref();
+        let mut report = BlowUp {
+            step: self.steps_taken,
+            time: self.time,
+            reason,
+            last_good_step: last_good.map_or(0, |s| s.steps_taken),
+            last_good: last_good.map(|s| s.cfl),
+            first_bad: self.cfl_stats(),
+            dumps: Vec::new(),
+        };
+        let Some(prefix) = &self.watchdog.dump_prefix else {
+            return Ok(report);
+        };
+
+        let bad_path = PathBuf::from(format!("{}_first_bad.ckpt", prefix));
+        let good_path = PathBuf::from(format!("{}_last_good.ckpt", prefix));
+        report.dumps.push(bad_path.clone());
+        if last_good.is_some() {
+            report.dumps.push(good_path.clone());
+        }
+        let mut meta = self.checkpoint_meta(target_steps);
+        meta.blow_up = Some(report.clone());
+        Checkpoint::save(&meta, &self.state, &bad_path)?;
+        if let Some(snapshot) = last_good {
+            meta.steps_taken = snapshot.steps_taken;
+            meta.time = snapshot.time;
+            Checkpoint::save(&meta, &snapshot.state, &good_path)?;
+        }
+        Ok(report)
     }
 
     // Run the full simulation.
-    pub fn run(&mut self, time_steps: usize) {
+    pub fn run(&mut self, time_steps: usize) -> anyhow::Result<RunOutcome> {
+        self.run_with_progress(time_steps, |_, _| {})
+    }
+
+    // Run the full simulation, handing `progress` the step and the number of steps every
+    // 100 steps. An interrupted run has written its checkpoint before it returns.
+    pub fn run_with_progress(&mut self, time_steps: usize, mut progress: impl FnMut(usize, usize)) -> anyhow::Result<RunOutcome> {
+        let target_steps = self.steps_taken + time_steps;
         for step in 0..time_steps {
             self.step();
             if step % 100 == 0 {
-                println!("Completed step {}/{}", step, time_steps);
+                progress(step, time_steps);
+            }
+            if self.watchdog.due(self.steps_taken) {
+                if let Some(reason) = self.watchdog.check(&self.state) {
+                    return Ok(RunOutcome::BlewUp(self.blow_up(reason, target_steps)?));
+                }
+                let spacing = self.checkpoint.as_ref().map_or(0, |settings| settings.interval);
+                if self.watchdog.snapshot_due(self.steps_taken, spacing) {
+                    self.watchdog.last_good =
+                        Some(Snapshot { state: self.state.clone(), steps_taken: self.steps_taken, time: self.time, cfl: self.cfl_stats() });
+                }
+            }
+            if let Some(log) = &mut self.diagnostics {
+                if log.interval > 0 && (self.steps_taken.is_multiple_of(log.interval) || self.steps_taken == target_steps) {
+                    let mut sample = diagnostics::sample(&self.state, &self.params, self.steps_taken, self.time);
+                    sample.balance = self.conservation.latest;
+                    log.history.push(sample);
+                }
+            }
+            if let Some(probes) = &mut self.probes {
+                if probes.interval > 0 && (self.steps_taken.is_multiple_of(probes.interval) || self.steps_taken == target_steps) {
+                    probes.sample(&self.state, &self.params.domain, self.steps_taken, self.time);
+                }
+            }
+
+            let Some(settings) = &self.checkpoint else {
+                continue;
+            };
+            if checkpoint::stop_requested() {
+                self.write_checkpoint(target_steps)?;
+                return Ok(RunOutcome::Interrupted);
+            }
+            if settings.interval > 0 && self.steps_taken.is_multiple_of(settings.interval) {
+                self.write_checkpoint(target_steps)?;
             }
         }
+        Ok(RunOutcome::Completed)
     }
 }
\ No newline at end of file
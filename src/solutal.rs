@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::{ThermalBc, ThermalBoundary};
+
+// Second buoyancy-driving scalar for thermosolutal convection: the melt composition,
+// scaled with the wall concentration difference ΔC like temperature is with ΔT. It
+// diffuses 1/Le times as fast as heat and drives the flow through
+//   ∂ω/∂t = ... + Ra Pr (∂T/∂x + N ∂C/∂x).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SolutalParameters {
+    pub lewis: f64,          // Le = α / D
+    pub buoyancy_ratio: f64, // N = β_C ΔC / (β_T ΔT); negative when solute opposes heat
+    pub walls: ThermalBoundary, // Fixed concentration or impermeable (adiabatic) segments
+}
+
+impl SolutalParameters {
+    pub fn new(lewis: f64, buoyancy_ratio: f64, walls: ThermalBoundary) -> anyhow::Result<Self> {
+        anyhow::ensure!(lewis > 0.0, "the Lewis number must be positive");
+        anyhow::ensure!(
+            walls.segments.iter().all(|s| !matches!(s.bc, ThermalBc::Radiative { .. })),
+            "concentration walls can only be fixed or impermeable (adiabatic)"
+        );
+        Ok(SolutalParameters { lewis, buoyancy_ratio, walls })
+    }
+}
@@ -0,0 +1,89 @@
+use crate::diagnostics::{self, DiagnosticsSample};
+use crate::simulation::{SimParameters, SimState, Simulation};
+use crate::watchdog::Watchdog;
+
+// A time stepper for the melt flow problem. `Simulation` is the finite difference
+// vorticity–stream function solver; other discretizations can implement this to run under
+// the UQ ensemble driver (`uq::run_ensemble`) and the field statistics. The run command
+// stays specific to `Simulation`, whose checkpoints, crystal growth and probes it drives.
+pub trait Solver {
+    // Set up the grid, geometry and initial and boundary conditions for `params`,
+    // discarding any previous state.
+    fn initialize(&mut self, params: SimParameters);
+
+    // Advance the state by one time step of `params.dt`, or less where the solver needs
+    // a smaller one.
+    fn step(&mut self);
+
+    fn state(&self) -> &SimState;
+
+    // Elapsed dimensionless time.
+    fn time(&self) -> f64;
+
+    // Steps taken since `initialize`.
+    fn steps_taken(&self) -> usize;
+
+    // The checks `advance_watched` runs on the state.
+    fn watchdog(&mut self) -> &mut Watchdog;
+
+    // Global measures of the current state: Nusselt numbers, kinetic energy, extrema.
+    fn diagnostics(&self) -> DiagnosticsSample;
+
+    fn advance(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    // Advance quietly with the watchdog checks of `Simulation::run`, for embedded and
+    // ensemble use. Returns why the state became unusable, if it did.
+    fn advance_watched(&mut self, steps: usize) -> Option<String> {
+        for _ in 0..steps {
+            self.step();
+            let steps_taken = self.steps_taken();
+            if !self.watchdog().due(steps_taken) {
+                continue;
+            }
+            // Taken out for the check, which reads the state
+            let mut watchdog = std::mem::take(self.watchdog());
+            let reason = watchdog.check(self.state());
+            *self.watchdog() = watchdog;
+            if let Some(reason) = reason {
+                return Some(format!("step {}: {}", steps_taken, reason));
+            }
+        }
+        None
+    }
+}
+
+impl Solver for Simulation {
+    fn initialize(&mut self, params: SimParameters) {
+        *self = Simulation::new(params);
+    }
+
+    fn step(&mut self) {
+        Simulation::step(self);
+    }
+
+    fn state(&self) -> &SimState {
+        &self.state
+    }
+
+    fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn steps_taken(&self) -> usize {
+        self.steps_taken
+    }
+
+    fn watchdog(&mut self) -> &mut Watchdog {
+        &mut self.watchdog
+    }
+
+    fn diagnostics(&self) -> DiagnosticsSample {
+        let mut sample = diagnostics::sample(&self.state, &self.params, self.steps_taken, self.time);
+        sample.balance = self.conservation.latest;
+        sample
+    }
+}
@@ -0,0 +1,202 @@
+use ndarray::{Array, Array2};
+use num_complex::Complex64;
+
+use crate::linalg::{self, Arnoldi};
+use crate::rng::Rng;
+use crate::simulation::Simulation;
+
+// Tolerance on the SOR updates of ψ when the flow has to be a function of ω alone.
+const STREAM_TOLERANCE: f64 = 1e-13;
+
+// The semi-discrete steady equations F(x) = (∂T/∂t, ∂ω/∂t, ∂C/∂t) as a function of the
+// unknowns x = (T at interior nodes, ω and, with a solutal model, C at interior fluid
+// nodes). Wall values, wall vorticity and ψ are slaved to x through the boundary
+// conditions and a converged Poisson solve.
+pub struct SteadyOperator {
+    pub sim: Simulation,
+    base_stream: Array2<f64>, // Warm start for ψ, the stream function of the base state
+    temp_nodes: Vec<(usize, usize)>,
+    vort_nodes: Vec<(usize, usize)>,
+    conc_nodes: Vec<(usize, usize)>,
+}
+
+impl SteadyOperator {
+    pub fn new(sim: Simulation) -> Self {
+        let (ny, nx) = sim.state.temp.dim();
+        let interior: Vec<(usize, usize)> = (1..ny - 1).flat_map(|i| (1..nx - 1).map(move |j| (i, j))).collect();
+        let vort_nodes: Vec<(usize, usize)> = interior.iter().copied().filter(|&(i, j)| sim.state.is_fluid(i, j)).collect();
+        let conc_nodes = if sim.params.solutal.is_some() { vort_nodes.clone() } else { Vec::new() };
+        SteadyOperator {
+            base_stream: sim.state.stream.clone(),
+            temp_nodes: interior,
+            vort_nodes,
+            conc_nodes,
+            sim,
+        }
+    }
+
+    // Number of unknowns.
+    pub fn len(&self) -> usize {
+        self.temp_nodes.len() + self.vort_nodes.len() + self.conc_nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Unknowns of the current simulation state.
+    pub fn state(&self) -> Vec<f64> {
+        let temp = self.temp_nodes.iter().map(|&n| self.sim.state.temp[n]);
+        let vort = self.vort_nodes.iter().map(|&n| self.sim.state.vort[n]);
+        let conc = self.conc_nodes.iter().map(|&n| self.sim.state.conc[n]);
+        temp.chain(vort).chain(conc).collect()
+    }
+
+    // Load x into the simulation and make the slaved fields consistent with it.
+    pub fn load(&mut self, x: &[f64]) {
+        let (temp, rest) = x.split_at(self.temp_nodes.len());
+        let (vort, conc) = rest.split_at(self.vort_nodes.len());
+        for (&n, &value) in self.temp_nodes.iter().zip(temp) {
+            self.sim.state.temp[n] = value;
+        }
+        for (&n, &value) in self.vort_nodes.iter().zip(vort) {
+            self.sim.state.vort[n] = value;
+        }
+        for (&n, &value) in self.conc_nodes.iter().zip(conc) {
+            self.sim.state.conc[n] = value;
+        }
+        self.sim.apply_wall_conditions();
+        self.sim.state.stream.assign(&self.base_stream);
+        self.sim.solve_stream_function_converged(STREAM_TOLERANCE);
+        self.sim.update_velocities();
+        self.sim.update_wall_vorticity();
+    }
+
+    // Make x the base state: later evaluations warm start ψ from its stream function.
+    pub fn set_base(&mut self, x: &[f64]) {
+        self.load(x);
+        self.base_stream.assign(&self.sim.state.stream);
+    }
+
+    pub fn residual(&mut self, x: &[f64]) -> Vec<f64> {
+        self.load(x);
+        let rates = self.sim.tendencies();
+        let temp = self.temp_nodes.iter().map(|&n| rates.temp[n]);
+        let vort = self.vort_nodes.iter().map(|&n| rates.vort[n]);
+        let conc = self.conc_nodes.iter().map(|&n| rates.conc[n]);
+        temp.chain(vort).chain(conc).collect()
+    }
+
+    // Jacobian-vector product J v ≈ (F(x + εv) − F(x)) / ε for a base with F(x) = fx.
+    pub fn jvp(&mut self, x: &[f64], fx: &[f64], v: &[f64]) -> Vec<f64> {
+        let v_norm = linalg::norm(v);
+        if v_norm == 0.0 {
+            return vec![0.0; v.len()];
+        }
+        let eps = f64::EPSILON.sqrt() * (1.0 + linalg::norm(x) / (x.len() as f64).sqrt()) / v_norm;
+        let mut shifted = x.to_vec();
+        linalg::axpy(&mut shifted, eps, v);
+        let f_shifted = self.residual(&shifted);
+        f_shifted.iter().zip(fx).map(|(a, b)| (a - b) / eps).collect()
+    }
+
+    // Temperature, vorticity and stream function of a perturbation vector. ψ solves
+    // ∇²ψ = −ω for the interior vorticity, which is linear in the perturbation.
+    pub fn fields(&mut self, x: &[f64]) -> (Array2<f64>, Array2<f64>, Array2<f64>) {
+        let dim = self.sim.state.temp.dim();
+        let mut temp = Array::zeros(dim);
+        let mut vort = Array::zeros(dim);
+        let (t, rest) = x.split_at(self.temp_nodes.len());
+        let w = &rest[..self.vort_nodes.len()];
+        for (&n, &value) in self.temp_nodes.iter().zip(t) {
+            temp[n] = value;
+        }
+        for (&n, &value) in self.vort_nodes.iter().zip(w) {
+            vort[n] = value;
+        }
+
+        let saved_vort = std::mem::replace(&mut self.sim.state.vort, vort.clone());
+        let saved_stream = std::mem::replace(&mut self.sim.state.stream, Array::zeros(dim));
+        self.sim.solve_stream_function_converged(STREAM_TOLERANCE * linalg::norm(w).max(1e-30));
+        let stream = std::mem::replace(&mut self.sim.state.stream, saved_stream);
+        self.sim.state.vort = saved_vort;
+        (temp, vort, stream)
+    }
+}
+
+// Settings of the eigenvalue computation.
+pub struct StabilitySettings {
+    pub horizon: f64,       // Time τ of the linearized propagator exp(Jτ)
+    pub krylov_dim: usize,  // Arnoldi basis size
+    pub modes: usize,       // Number of leading modes reported
+    pub seed: u64,          // Seed of the random Arnoldi start vector
+}
+
+// Eigenvalue λ = σ + iω of the Jacobian with its mode. Only one of each complex
+// conjugate pair is kept (ω ≥ 0).
+pub struct Eigenmode {
+    pub growth_rate: f64,    // σ, positive for unstable modes
+    pub angular_frequency: f64, // ω
+    pub residual: f64,       // Arnoldi residual estimate relative to |μ|
+    pub vector: Vec<Complex64>,
+}
+
+impl Eigenmode {
+    pub fn frequency(&self) -> f64 {
+        self.angular_frequency / (2.0 * std::f64::consts::PI)
+    }
+}
+
+// Leading eigenvalues of the Jacobian of the steady equations at the operator's current
+// state. The Jacobian itself is dominated by large negative diffusive eigenvalues, so
+// Arnoldi is run on the linearized time stepper over τ, B = (I + Δt J)^m with m = τ/Δt,
+// whose largest multipliers μ belong to the eigenvalues with the largest real part:
+// λ = (μ^{1/m} − 1) / Δt. Δt is kept inside the forward Euler stability limit of the base
+// state, independently of the simulation's own step, so that no stable eigenvalue is
+// mapped to a multiplier outside the unit circle.
+pub fn leading_modes(op: &mut SteadyOperator, settings: &StabilitySettings) -> Vec<Eigenmode> {
+    let x0 = op.state();
+    op.set_base(&x0);
+    let steps = (settings.horizon / (0.9 * op.sim.stable_time_step())).ceil().max(1.0) as usize;
+    let dt = settings.horizon / steps as f64;
+    let f0 = op.residual(&x0);
+
+    let propagate = |op: &mut SteadyOperator, v: &[f64]| {
+        let mut v = v.to_vec();
+        for _ in 0..steps {
+            let jv = op.jvp(&x0, &f0, &v);
+            linalg::axpy(&mut v, dt, &jv);
+        }
+        v
+    };
+
+    let mut rng = Rng::new(settings.seed);
+    let start: Vec<f64> = (0..op.len()).map(|_| rng.range(-1.0, 1.0)).collect();
+    let arnoldi = Arnoldi::new(&start, settings.krylov_dim, |v| propagate(op, v));
+
+    let k = arnoldi.dim();
+    let mut multipliers = linalg::hessenberg_eigenvalues(&arnoldi.h[..k]);
+    // Real multipliers come out of the complex QR with roundoff-sized imaginary parts
+    for mu in multipliers.iter_mut() {
+        if mu.im.abs() <= 1e-10 * mu.norm() {
+            mu.im = 0.0;
+        }
+    }
+    multipliers.retain(|mu| mu.im >= 0.0 && mu.norm() > 0.0);
+    multipliers.sort_by(|a, b| b.norm().total_cmp(&a.norm()));
+    multipliers.truncate(settings.modes);
+
+    multipliers
+        .into_iter()
+        .map(|mu| {
+            let lambda = ((mu.ln() / steps as f64).exp() - 1.0) / dt;
+            let (vector, residual) = arnoldi.ritz_pair(mu);
+            Eigenmode {
+                growth_rate: lambda.re,
+                angular_frequency: lambda.im,
+                residual: residual / mu.norm(),
+                vector,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,571 @@
+use anyhow::{Context, Result};
+use ndarray::Array2;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use crate::boundary::{BoundarySegment, ThermalBc, Wall};
+use crate::diagnostics::DiagnosticsSample;
+use crate::rng::Rng;
+use crate::simulation::{SimParameters, SimState};
+use crate::solver::Solver;
+
+// Probability distribution of an uncertain input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    Normal { mean: f64, sd: f64 },
+    Uniform { lo: f64, hi: f64 },
+    LogNormal { mu: f64, sigma: f64 }, // ln X is normal with mean μ and deviation σ
+}
+
+impl Distribution {
+    // Value below which a fraction `p` of the distribution lies, 0 < p < 1.
+    pub fn quantile(&self, p: f64) -> f64 {
+        match *self {
+            Distribution::Normal { mean, sd } => mean + sd * normal_quantile(p),
+            Distribution::Uniform { lo, hi } => lo + (hi - lo) * p,
+            Distribution::LogNormal { mu, sigma } => (mu + sigma * normal_quantile(p)).exp(),
+        }
+    }
+}
+
+// Parses `normal:<mean>,<sd>` (sd may be given as a percentage of the mean, `normal:1e4,10%`),
+// `uniform:<lo>,<hi>` or `lognormal:<mu>,<sigma>` with μ and σ those of ln X.
+impl FromStr for Distribution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, args) = s.split_once(':').ok_or_else(|| anyhow::anyhow!("expected <distribution>:<a>,<b>, got '{}'", s))?;
+        let (a, b) = args.split_once(',').ok_or_else(|| anyhow::anyhow!("'{}' needs two parameters", kind))?;
+        let a: f64 = a.parse()?;
+        let distribution = match kind {
+            "normal" => {
+                let sd = match b.strip_suffix('%') {
+                    Some(percent) => a.abs() * percent.parse::<f64>()? / 100.0,
+                    None => b.parse()?,
+                };
+                Distribution::Normal { mean: a, sd }
+            }
+            "uniform" => Distribution::Uniform { lo: a, hi: b.parse()? },
+            "lognormal" => Distribution::LogNormal { mu: a, sigma: b.parse()? },
+            other => anyhow::bail!("unknown distribution '{}' (normal, uniform, lognormal)", other),
+        };
+        match distribution {
+            Distribution::Normal { sd, .. } if sd < 0.0 => anyhow::bail!("negative standard deviation in '{}'", s),
+            Distribution::Uniform { lo, hi } if hi < lo => anyhow::bail!("empty range in '{}'", s),
+            Distribution::LogNormal { sigma, .. } if sigma < 0.0 => anyhow::bail!("negative σ in '{}'", s),
+            _ => Ok(distribution),
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distribution::Normal { mean, sd } => write!(f, "normal:{},{}", mean, sd),
+            Distribution::Uniform { lo, hi } => write!(f, "uniform:{},{}", lo, hi),
+            Distribution::LogNormal { mu, sigma } => write!(f, "lognormal:{},{}", mu, sigma),
+        }
+    }
+}
+
+// Inverse of the standard normal CDF (Acklam's rational approximation, relative error
+// below 1.2e-9), which is ample for sampling.
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.50662827745924];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    let tail = |q: f64| (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0);
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+// Model input a distribution is put on. Ma is not one: the melt surface has no
+// thermocapillary stress, so the Marangoni number does not enter the model and `ma` is
+// refused when parsed, as in `continuation`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UncertainParameter {
+    Prandtl,
+    Rayleigh,
+    WallTemperature(Wall), // Fixed temperature of the whole wall
+}
+
+impl UncertainParameter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UncertainParameter::Prandtl => "pr",
+            UncertainParameter::Rayleigh => "ra",
+            UncertainParameter::WallTemperature(Wall::Bottom) => "t-bottom",
+            UncertainParameter::WallTemperature(Wall::Top) => "t-top",
+            UncertainParameter::WallTemperature(Wall::Left) => "t-left",
+            UncertainParameter::WallTemperature(Wall::Right) => "t-right",
+        }
+    }
+
+    // Later segments override earlier ones, so a wall temperature replaces whatever the
+    // base parameters put on that wall.
+    pub fn set(&self, params: &mut SimParameters, value: f64) {
+        match self {
+            UncertainParameter::Prandtl => params.pr = value,
+            UncertainParameter::Rayleigh => params.ra = value,
+            UncertainParameter::WallTemperature(wall) => params.thermal.segments.push(BoundarySegment::whole(*wall, ThermalBc::Fixed(value))),
+        }
+    }
+}
+
+impl FromStr for UncertainParameter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pr" | "prandtl" => Ok(UncertainParameter::Prandtl),
+            "ra" | "rayleigh" => Ok(UncertainParameter::Rayleigh),
+            "ma" | "marangoni" => {
+                anyhow::bail!("Ma is not supported as an uncertain input: the melt surface has no thermocapillary stress, so there is no Marangoni number in this model (pr, ra, t-<wall>)")
+            }
+            other => match other.strip_prefix("t-") {
+                Some(wall) => Ok(UncertainParameter::WallTemperature(wall.parse()?)),
+                None => anyhow::bail!("'{}' is not an uncertain input of this model (pr, ra, t-<wall>)", other),
+            },
+        }
+    }
+}
+
+// One input and its distribution, `<parameter>=<distribution>`, e.g. `ra=normal:1e5,10%`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UncertainInput {
+    pub parameter: UncertainParameter,
+    pub distribution: Distribution,
+}
+
+impl FromStr for UncertainInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (parameter, distribution) = s.split_once('=').ok_or_else(|| anyhow::anyhow!("expected <parameter>=<distribution>, got '{}'", s))?;
+        Ok(UncertainInput { parameter: parameter.parse()?, distribution: distribution.parse()? })
+    }
+}
+
+impl fmt::Display for UncertainInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.parameter.name(), self.distribution)
+    }
+}
+
+// How the members' input values are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sampling {
+    MonteCarlo,     // Independent draws
+    LatinHypercube, // One draw in each of n equal-probability strata of every input
+}
+
+impl Sampling {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sampling::MonteCarlo => "mc",
+            Sampling::LatinHypercube => "lhs",
+        }
+    }
+
+    // Input values of `members` members, one row per member in the order of `inputs`.
+    pub fn sample(&self, inputs: &[UncertainInput], members: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut rng = Rng::new(seed);
+        // Keep the probabilities off 0 and 1, where unbounded distributions diverge
+        let open = |rng: &mut Rng| rng.uniform().clamp(1e-12, 1.0 - 1e-12);
+        let mut probabilities = vec![vec![0.0; inputs.len()]; members];
+        for k in 0..inputs.len() {
+            match self {
+                Sampling::MonteCarlo => {
+                    for row in probabilities.iter_mut() {
+                        row[k] = open(&mut rng);
+                    }
+                }
+                Sampling::LatinHypercube => {
+                    let mut strata: Vec<usize> = (0..members).collect();
+                    for m in (1..members).rev() {
+                        strata.swap(m, (rng.next_u64() % (m as u64 + 1)) as usize);
+                    }
+                    for (row, stratum) in probabilities.iter_mut().zip(strata) {
+                        row[k] = (stratum as f64 + open(&mut rng)) / members as f64;
+                    }
+                }
+            }
+        }
+        probabilities
+            .iter()
+            .map(|row| row.iter().zip(inputs).map(|(p, input)| input.distribution.quantile(*p)).collect())
+            .collect()
+    }
+}
+
+impl FromStr for Sampling {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mc" | "monte-carlo" => Ok(Sampling::MonteCarlo),
+            "lhs" | "latin-hypercube" => Ok(Sampling::LatinHypercube),
+            other => anyhow::bail!("unknown sampling method '{}' (mc, lhs)", other),
+        }
+    }
+}
+
+// Fraction of the members' stable time step they are run at, leaving room for the
+// advective limit to tighten as the flow develops.
+pub const STEP_FRACTION: f64 = 0.5;
+
+// Parameters of a member: the base with the member's input values applied.
+pub fn member_parameters(base: &SimParameters, inputs: &[UncertainInput], values: &[f64]) -> SimParameters {
+    let mut params = base.clone();
+    for (input, value) in inputs.iter().zip(values) {
+        input.parameter.set(&mut params, *value);
+    }
+    params
+}
+
+// Time step every member is run at, so that all of them reach the same time in the same
+// number of steps and their fields can be compared node by node: a fraction of the
+// smallest stable step of the members that are not rejected. Pr sets the diffusive
+// limit, so it is that of the member with the largest Pr. None if all are rejected.
+pub fn ensemble_time_step(members: &[SimParameters]) -> Option<f64> {
+    members
+        .iter()
+        .filter(|params| rejection(params).is_none())
+        .map(SimParameters::stable_time_step)
+        .min_by(f64::total_cmp)
+        .map(|dt| STEP_FRACTION * dt)
+}
+
+// The members of a study at their common time step.
+pub fn study_members(base: &SimParameters, inputs: &[UncertainInput], values: &[Vec<f64>]) -> Vec<SimParameters> {
+    let mut members: Vec<SimParameters> = values.iter().map(|row| member_parameters(base, inputs, row)).collect();
+    let dt = ensemble_time_step(&members).unwrap_or(base.dt);
+    for params in &mut members {
+        params.dt = dt;
+    }
+    members
+}
+
+// Why a member's inputs are outside the model, which an unbounded distribution such as a
+// normal one can draw: the diffusivity Pr has to be positive and Ra must not be negative.
+pub fn rejection(params: &SimParameters) -> Option<String> {
+    if params.pr <= 0.0 {
+        Some(format!("Pr = {:.3e} is not positive", params.pr))
+    } else if params.ra < 0.0 {
+        Some(format!("Ra = {:.3e} is negative", params.ra))
+    } else {
+        None
+    }
+}
+
+// Run every member for `steps` steps on `jobs` threads, which take the next member as
+// they finish one, each in a solver made by `new`. The fields are checked by the solver's
+// watchdog and a member that blows up is returned as its reason; one whose inputs
+// are outside the model is returned as its `rejection`, without being run. The workers
+// send their outcomes back, and `report` sees each member on the calling thread as it
+// finishes, with the number finished so far.
+pub fn run_ensemble<S: Solver + Send>(
+    members: Vec<SimParameters>,
+    steps: usize,
+    jobs: usize,
+    new: impl Fn(SimParameters) -> S + Sync,
+    mut report: impl FnMut(usize, usize, &Result<S, String>),
+) -> Vec<Result<S, String>> {
+    let count = members.len();
+    let next = AtomicUsize::new(0);
+    let mut outcomes: Vec<Option<Result<S, String>>> = (0..count).map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.clamp(1, count.max(1)) {
+            let sender = sender.clone();
+            let (next, members, new) = (&next, &members, &new);
+            scope.spawn(move || loop {
+                let m = next.fetch_add(1, Ordering::Relaxed);
+                let Some(params) = members.get(m) else {
+                    break;
+                };
+                let outcome = match rejection(params) {
+                    Some(reason) => Err(reason),
+                    None => {
+                        let mut solver = new(params.clone());
+                        match solver.advance_watched(steps) {
+                            Some(reason) => Err(reason),
+                            None => Ok(solver),
+                        }
+                    }
+                };
+                if sender.send((m, outcome)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        for (done, (m, outcome)) in receiver.iter().enumerate() {
+            report(m, done + 1, &outcome);
+            outcomes[m] = Some(outcome);
+        }
+    });
+    outcomes.into_iter().map(|o| o.expect("every member is run")).collect()
+}
+
+// Mean, sample standard deviation and percentiles of a set of values.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SummaryStats {
+    pub mean: f64,
+    pub std: f64,
+    pub p05: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+impl SummaryStats {
+    pub fn of(values: &[f64]) -> Self {
+        let n = values.len();
+        if n == 0 {
+            return SummaryStats::default();
+        }
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let var = if n > 1 { values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64 } else { 0.0 };
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        SummaryStats { mean, std: var.sqrt(), p05: percentile(&sorted, 0.05), p50: percentile(&sorted, 0.5), p95: percentile(&sorted, 0.95) }
+    }
+}
+
+// Linear interpolation between the order statistics of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let x = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (x.floor() as usize, x.ceil() as usize);
+    sorted[lo] + (x - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+// Node-wise statistics of one field over the completed members.
+pub struct FieldStatistics {
+    pub name: &'static str,
+    pub mean: Array2<f64>,
+    pub std: Array2<f64>,
+    pub p05: Array2<f64>,
+    pub p50: Array2<f64>,
+    pub p95: Array2<f64>,
+}
+
+impl FieldStatistics {
+    pub fn of(name: &'static str, fields: &[&Array2<f64>]) -> Self {
+        let dim = fields.first().map_or((0, 0), |f| f.dim());
+        let mut stats = FieldStatistics {
+            name,
+            mean: Array2::zeros(dim),
+            std: Array2::zeros(dim),
+            p05: Array2::zeros(dim),
+            p50: Array2::zeros(dim),
+            p95: Array2::zeros(dim),
+        };
+        let mut values = Vec::with_capacity(fields.len());
+        for ((i, j), _) in fields.first().into_iter().flat_map(|f| f.indexed_iter()) {
+            values.clear();
+            values.extend(fields.iter().map(|f| f[[i, j]]));
+            let s = SummaryStats::of(&values);
+            stats.mean[[i, j]] = s.mean;
+            stats.std[[i, j]] = s.std;
+            stats.p05[[i, j]] = s.p05;
+            stats.p50[[i, j]] = s.p50;
+            stats.p95[[i, j]] = s.p95;
+        }
+        stats
+    }
+}
+
+// Node-wise statistics of a field, by name, as `FieldStatistics::get` takes them.
+pub const FIELD_STATISTICS: [&str; 5] = ["mean", "std", "p05", "p50", "p95"];
+
+impl FieldStatistics {
+    pub fn get(&self, statistic: &str) -> Result<&Array2<f64>> {
+        match statistic {
+            "mean" => Ok(&self.mean),
+            "std" => Ok(&self.std),
+            "p05" => Ok(&self.p05),
+            "p50" => Ok(&self.p50),
+            "p95" => Ok(&self.p95),
+            other => anyhow::bail!("unknown statistic '{}' ({})", other, FIELD_STATISTICS.join(", ")),
+        }
+    }
+}
+
+// Statistics of T, ψ, u and v over the completed members.
+pub fn field_statistics<S: Solver>(members: &[&S]) -> [FieldStatistics; 4] {
+    let fields = |field: fn(&SimState) -> &Array2<f64>| members.iter().map(|solver| field(solver.state())).collect::<Vec<_>>();
+    [
+        FieldStatistics::of("temperature", &fields(|state| &state.temp)),
+        FieldStatistics::of("stream function", &fields(|state| &state.stream)),
+        FieldStatistics::of("u", &fields(|state| &state.u)),
+        FieldStatistics::of("v", &fields(|state| &state.v)),
+    ]
+}
+
+// Scalar outputs of a member, by name, in the order of `SCALAR_OUTPUTS`.
+pub const SCALAR_OUTPUTS: [&str; 9] = ["nu_bottom", "nu_top", "nu_left", "nu_right", "kinetic_energy", "enstrophy", "max_stream", "max_u", "max_v"];
+
+pub fn scalar_outputs(sample: &DiagnosticsSample) -> [f64; 9] {
+    let [bottom, top, left, right] = sample.nusselt;
+    [bottom, top, left, right, sample.kinetic_energy, sample.enstrophy, sample.max_stream, sample.max_u.0, sample.max_v.0]
+}
+
+// Statistics of every scalar output over the completed members.
+pub fn scalar_statistics(samples: &[DiagnosticsSample]) -> Vec<(&'static str, SummaryStats)> {
+    let outputs: Vec<[f64; 9]> = samples.iter().map(scalar_outputs).collect();
+    SCALAR_OUTPUTS
+        .iter()
+        .enumerate()
+        .map(|(k, name)| (*name, SummaryStats::of(&outputs.iter().map(|o| o[k]).collect::<Vec<_>>())))
+        .collect()
+}
+
+pub fn print_statistics(stats: &[(&str, SummaryStats)]) {
+    println!("{:<15} | {:<12} | {:<12} | {:<12} | {:<12} | {:<12}", "Output", "Mean", "Std", "P5", "P50", "P95");
+    println!("{}", "-".repeat(90));
+    for (name, s) in stats {
+        println!("{:<15} | {:<12.5e} | {:<12.5e} | {:<12.5e} | {:<12.5e} | {:<12.5e}", name, s.mean, s.std, s.p05, s.p50, s.p95);
+    }
+}
+
+// One row per member: its input values, whether it completed and its scalar outputs.
+pub fn write_members_csv(
+    inputs: &[UncertainInput],
+    values: &[Vec<f64>],
+    outputs: &[Option<DiagnosticsSample>],
+    path: &Path,
+) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path).with_context(|| format!("creating {}", path.display()))?);
+    let names: Vec<&str> = inputs.iter().map(|i| i.parameter.name()).chain(["completed"]).chain(SCALAR_OUTPUTS).collect();
+    writeln!(out, "member,{}", names.join(","))?;
+    for (m, (row, output)) in values.iter().zip(outputs).enumerate() {
+        let inputs: Vec<String> = row.iter().map(|v| format!("{:e}", v)).collect();
+        let outputs: Vec<String> = match output {
+            Some(sample) => scalar_outputs(sample).iter().map(|v| format!("{:e}", v)).collect(),
+            None => vec![String::new(); SCALAR_OUTPUTS.len()],
+        };
+        writeln!(out, "{},{},{},{}", m, inputs.join(","), output.is_some(), outputs.join(","))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+// An ensemble as the uq command runs it: `samples` members drawn by `method` from the
+// inputs around `base`, each run for `steps` steps.
+pub struct Study {
+    pub description: String,
+    pub base: SimParameters,
+    pub inputs: Vec<UncertainInput>,
+    pub method: Sampling,
+    pub samples: usize,
+    pub seed: u64,
+    pub steps: usize,
+}
+
+// The members of a study, drawn and recorded as runs under one ensemble.
+pub struct StoredStudy {
+    pub ensemble_id: i32,
+    pub values: Vec<Vec<f64>>, // Input values, one row per member
+    pub members: Vec<SimParameters>,
+    pub run_ids: Vec<i32>,
+    pub steps: usize,
+}
+
+// What running a study left: the final diagnostics of the completed members, and the
+// statistics over them once at least two completed.
+pub struct StudyResults {
+    pub finals: Vec<Option<DiagnosticsSample>>,
+    pub failed: usize,
+    pub statistics: Option<EnsembleStatistics>,
+}
+
+pub struct EnsembleStatistics {
+    pub scalars: Vec<(&'static str, SummaryStats)>,
+    pub fields: [FieldStatistics; 4],
+}
+
+#[cfg(feature = "db")]
+impl Study {
+    // Draw the members and record the ensemble, with a run per member that carries its
+    // parameters and input values.
+    pub fn record(&self, pool: &crate::db::DbPool) -> Result<StoredStudy> {
+        use crate::db;
+
+        anyhow::ensure!(self.samples >= 2, "an ensemble needs at least two members");
+        let values = self.method.sample(&self.inputs, self.samples, self.seed);
+        let ensemble = db::create_ensemble(pool, &self.description, self.method, self.samples, self.seed, &self.inputs, self.steps)?;
+        let members = study_members(&self.base, &self.inputs, &values);
+        let mut run_ids = Vec::new();
+        for (m, (params, row)) in members.iter().zip(&values).enumerate() {
+            let sample: Vec<String> = self.inputs.iter().zip(row).map(|(input, value)| format!("{}={:.6e}", input.parameter.name(), value)).collect();
+            let description = format!("{} member {}", self.description, m);
+            let run = db::create_simulation_run(pool, &description, params.ny as i32, self.steps as i32, params.pr, params.ra, None)?;
+            db::set_run_status(pool, run.id, "running", None)?;
+            db::set_run_domain(pool, run.id, &params.domain)?;
+            db::set_run_parameters(pool, run.id, params)?;
+            db::set_run_ensemble_member(pool, run.id, ensemble.id, &sample.join(" "))?;
+            run_ids.push(run.id);
+        }
+        Ok(StoredStudy { ensemble_id: ensemble.id, values, members, run_ids, steps: self.steps })
+    }
+}
+
+#[cfg(feature = "db")]
+impl StoredStudy {
+    // Run the members on `jobs` threads, reporting each as `run_ensemble` does. The
+    // completed members are saved; the failed and rejected ones are marked and left out of
+    // the statistics, which are stored with the ensemble.
+    pub fn run(&self, pool: &crate::db::DbPool, jobs: usize, report: impl FnMut(usize, usize, &Result<crate::simulation::Simulation, String>)) -> Result<StudyResults> {
+        use crate::db;
+
+        let outcomes = run_ensemble(self.members.clone(), self.steps, jobs, crate::simulation::Simulation::new, report);
+        let mut completed = Vec::new();
+        let mut finals = Vec::new();
+        for (run_id, outcome) in self.run_ids.iter().zip(&outcomes) {
+            match outcome {
+                Ok(sim) => {
+                    let sample = Solver::diagnostics(sim);
+                    let run = db::CompletedRun {
+                        params: &sim.params,
+                        state: &sim.state,
+                        time: sim.time,
+                        growth: None,
+                        diagnostics: None,
+                        probes: None,
+                        balance: &sample.balance,
+                    };
+                    db::save_completed_run(pool, *run_id, &run)?;
+                    finals.push(Some(sample));
+                    completed.push(sim);
+                }
+                Err(reason) => {
+                    db::set_run_status(pool, *run_id, "failed", Some(reason))?;
+                    finals.push(None);
+                }
+            }
+        }
+        let failed = outcomes.len() - completed.len();
+        if completed.len() < 2 {
+            return Ok(StudyResults { finals, failed, statistics: None });
+        }
+
+        let scalars = scalar_statistics(&finals.iter().flatten().copied().collect::<Vec<_>>());
+        db::save_ensemble_statistics(pool, self.ensemble_id, completed.len(), failed, &scalars)?;
+        let fields = field_statistics(&completed);
+        db::save_ensemble_fields(pool, self.ensemble_id, &fields)?;
+        Ok(StudyResults { finals, failed, statistics: Some(EnsembleStatistics { scalars, fields }) })
+    }
+}
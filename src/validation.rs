@@ -0,0 +1,383 @@
+use std::str::FromStr;
+
+use crate::boundary::{BoundarySegment, ThermalBc, ThermalBoundary, Wall};
+use crate::continuation::{Continuation, ContinuationParameter, ContinuationSettings};
+use crate::diagnostics::{local_nusselt, wall_nusselt};
+use crate::simulation::{Domain, SimParameters, Simulation};
+use crate::solutal::SolutalParameters;
+use crate::stability::{self, SteadyOperator, StabilitySettings};
+
+// Published or exact reference results the solver is checked against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValidationCase {
+    DoubleDiffusive,
+    DeVahlDavis { rayleigh: Option<f64> }, // One Rayleigh number of the benchmark, or all four
+    RayleighBenard,
+}
+
+impl FromStr for ValidationCase {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "double-diffusive" => Ok(ValidationCase::DoubleDiffusive),
+            "de-vahl-davis" => Ok(ValidationCase::DeVahlDavis { rayleigh: None }),
+            "rayleigh-benard" => Ok(ValidationCase::RayleighBenard),
+            other => match other.strip_prefix("de-vahl-davis:") {
+                Some(ra) => {
+                    let ra: f64 = ra.parse()?;
+                    anyhow::ensure!(
+                        DE_VAHL_DAVIS.iter().any(|c| c.rayleigh == ra),
+                        "de Vahl Davis has no reference solution at Ra = {} (1e3, 1e4, 1e5, 1e6)",
+                        ra
+                    );
+                    Ok(ValidationCase::DeVahlDavis { rayleigh: Some(ra) })
+                }
+                None => anyhow::bail!("unknown validation case '{}' (double-diffusive, de-vahl-davis[:<Ra>], rayleigh-benard)", other),
+            },
+        }
+    }
+}
+
+// One compared quantity; passes when |observed − reference| ≤ tolerance.
+pub struct Check {
+    pub name: String,
+    pub reference: f64,
+    pub observed: f64,
+    pub tolerance: f64,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        (self.observed - self.reference).abs() <= self.tolerance
+    }
+}
+
+pub fn run(case: ValidationCase) -> Vec<Check> {
+    match case {
+        ValidationCase::DoubleDiffusive => double_diffusive(),
+        ValidationCase::DeVahlDavis { rayleigh } => de_vahl_davis(rayleigh),
+        ValidationCase::RayleighBenard => rayleigh_benard(),
+    }
+}
+
+pub fn print_report(checks: &[Check]) {
+    println!("{:<52} | {:<12} | {:<12} | {:<10} | Result", "Check", "Reference", "Observed", "Tolerance");
+    println!("{}", "-".repeat(104));
+    for check in checks {
+        println!(
+            "{:<52} | {:<12.5e} | {:<12.5e} | {:<10.2e} | {}",
+            check.name,
+            check.reference,
+            check.observed,
+            check.tolerance,
+            if check.passed() { "PASS" } else { "FAIL" }
+        );
+    }
+}
+
+fn cavity(n: usize, pr: f64, ra: f64, thermal: ThermalBoundary, solutal: Option<SolutalParameters>) -> SimParameters {
+    SimParameters { thermal, solutal, ..SimParameters::cavity(n, pr, ra) }
+}
+
+fn max_stream(sim: &Simulation) -> f64 {
+    sim.state.stream.iter().fold(0.0f64, |m, v| m.max(v.abs()))
+}
+
+fn run_steps(params: SimParameters, steps: usize) -> Simulation {
+    let mut sim = Simulation::new(params);
+    for _ in 0..steps {
+        sim.step();
+    }
+    sim
+}
+
+// Vertical walls at fixed T = C (1 left, 0 right), horizontal walls adiabatic and impermeable.
+fn side_heated_walls() -> ThermalBoundary {
+    ThermalBoundary {
+        segments: vec![
+            BoundarySegment::whole(Wall::Bottom, ThermalBc::Adiabatic),
+            BoundarySegment::whole(Wall::Top, ThermalBc::Adiabatic),
+            BoundarySegment::whole(Wall::Left, ThermalBc::Fixed(1.0)),
+            BoundarySegment::whole(Wall::Right, ThermalBc::Fixed(0.0)),
+        ],
+        ..ThermalBoundary::default()
+    }
+}
+
+// Thermosolutal checks:
+//  - with Le = 1 and identical wall conditions C ≡ T, so N = −1 cancels buoyancy exactly
+//    and N > 0 is thermal convection at Ra (1 + N);
+//  - N = 0 must reproduce the thermal solution;
+//  - with opposing, equal buoyancies (N = −1) in the side-heated square the conductive rest
+//    state is exact and loses stability at Ra_T |Le − 1| = 17174 (Ghorayeb & Mojtabi,
+//    Phys. Fluids 9, 1997), found here from the leading eigenvalue of the rest state.
+fn double_diffusive() -> Vec<Check> {
+    let (n, pr, ra, steps) = (21, 0.71, 1.0e4, 2000);
+    let mut checks = Vec::new();
+
+    let walls = ThermalBoundary::default;
+    let solutal = |lewis, buoyancy_ratio| SolutalParameters::new(lewis, buoyancy_ratio, walls()).ok();
+
+    let cancelled = run_steps(cavity(n, pr, ra, walls(), solutal(1.0, -1.0)), steps);
+    checks.push(Check {
+        name: "Le = 1, N = -1: max |psi| (rest)".to_string(),
+        reference: 0.0,
+        observed: max_stream(&cancelled),
+        tolerance: 1e-8,
+    });
+
+    let thermal = run_steps(cavity(n, pr, ra, walls(), None), steps);
+    let passive = run_steps(cavity(n, pr, ra, walls(), solutal(2.0, 0.0)), steps);
+    checks.push(Check {
+        name: "N = 0: max |psi - psi_thermal|".to_string(),
+        reference: 0.0,
+        observed: (&passive.state.stream - &thermal.state.stream).iter().fold(0.0f64, |m, v| m.max(v.abs())),
+        tolerance: 1e-12,
+    });
+
+    let aiding = run_steps(cavity(n, pr, ra, walls(), solutal(1.0, 0.5)), steps);
+    let equivalent = run_steps(cavity(n, pr, 1.5 * ra, walls(), None), steps);
+    checks.push(Check {
+        name: "Le = 1, N = 0.5: max |psi| vs thermal Ra(1+N)".to_string(),
+        reference: max_stream(&equivalent),
+        observed: max_stream(&aiding),
+        tolerance: 1e-6 * max_stream(&equivalent),
+    });
+
+    // Onset of convection from the rest state, interpolated between two Rayleigh numbers
+    let (lewis, reference) = (2.0, 17174.0);
+    let onset_n = 25;
+    let growth_rate = |ra_le: f64| {
+        let params = cavity(onset_n, 7.0, ra_le / (lewis - 1.0), side_heated_walls(), SolutalParameters::new(lewis, -1.0, side_heated_walls()).ok());
+        let mut sim = Simulation::new(params);
+        let (ny, nx) = sim.state.temp.dim();
+        for i in 0..ny {
+            for j in 0..nx {
+                let x = j as f64 / (nx as f64 - 1.0);
+                sim.state.temp[[i, j]] = 1.0 - x;
+                sim.state.conc[[i, j]] = 1.0 - x;
+            }
+        }
+        let mut op = SteadyOperator::new(sim);
+        let settings = StabilitySettings { horizon: 0.02, krylov_dim: 30, modes: 3, seed: 1 };
+        stability::leading_modes(&mut op, &settings)
+            .iter()
+            .map(|m| m.growth_rate)
+            .fold(f64::MIN, f64::max)
+    };
+    let (low, high) = (0.9 * reference, 1.1 * reference);
+    let (sigma_low, sigma_high) = (growth_rate(low), growth_rate(high));
+    checks.push(Check {
+        name: format!("N = -1, Le = {}: critical Ra_T |Le - 1| ({}x{})", lewis, onset_n, onset_n),
+        reference,
+        observed: low - sigma_low * (high - low) / (sigma_high - sigma_low),
+        tolerance: 0.05 * reference,
+    });
+
+    checks
+}
+
+// de Vahl Davis, "Natural convection of air in a square cavity: a bench mark numerical
+// solution", Int. J. Numer. Methods Fluids 3 (1983), Pr = 0.71, velocities scaled with α/L.
+struct Benchmark {
+    rayleigh: f64,
+    grid: usize,
+    psi_max: (f64, f64, f64), // |ψ|max at (x, y)
+    u_max: (f64, f64),        // u on the vertical centerline, at y
+    v_max: (f64, f64),        // v on the horizontal centerline, at x
+    nu_mean: f64,             // Hot wall
+    nu_max: (f64, f64),       // Hot wall, at y
+    nu_min: (f64, f64),
+    tolerance: f64,           // Relative, for values
+}
+
+// Tolerances allow for the first order upwind advection on these grids: the largest value
+// errors are 1.8 %, 2.1 %, 5.5 % and 8.0 % at Ra = 1e3, 1e4, 1e5 and 1e6, mostly in the
+// velocity peaks and, at Ra = 1e6, in |ψ|max.
+const DE_VAHL_DAVIS: [Benchmark; 4] = [
+    Benchmark {
+        rayleigh: 1e3,
+        grid: 41,
+        psi_max: (1.174, 0.5, 0.5),
+        u_max: (3.649, 0.813),
+        v_max: (3.697, 0.178),
+        nu_mean: 1.118,
+        nu_max: (1.505, 0.092),
+        nu_min: (0.692, 1.0),
+        tolerance: 0.02,
+    },
+    Benchmark {
+        rayleigh: 1e4,
+        grid: 41,
+        psi_max: (5.071, 0.5, 0.5),
+        u_max: (16.178, 0.823),
+        v_max: (19.617, 0.119),
+        nu_mean: 2.243,
+        nu_max: (3.528, 0.143),
+        nu_min: (0.586, 1.0),
+        tolerance: 0.03,
+    },
+    Benchmark {
+        rayleigh: 1e5,
+        grid: 61,
+        psi_max: (9.612, 0.285, 0.601),
+        u_max: (34.73, 0.855),
+        v_max: (68.59, 0.066),
+        nu_mean: 4.519,
+        nu_max: (7.717, 0.081),
+        nu_min: (0.729, 1.0),
+        tolerance: 0.06,
+    },
+    Benchmark {
+        rayleigh: 1e6,
+        grid: 81,
+        psi_max: (16.750, 0.151, 0.547),
+        u_max: (64.63, 0.850),
+        v_max: (219.36, 0.0379),
+        nu_mean: 8.800,
+        nu_max: (17.925, 0.0378),
+        nu_min: (0.989, 1.0),
+        tolerance: 0.09,
+    },
+];
+
+// Largest sample and its position, refined with a parabola through the neighbours.
+fn peak(values: &[f64], h: f64) -> (f64, f64) {
+    let k = (0..values.len()).max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap_or(0);
+    if k == 0 || k + 1 == values.len() {
+        return (values[k], k as f64 * h);
+    }
+    let (a, b, c) = (values[k - 1], values[k], values[k + 1]);
+    let curvature = a - 2.0 * b + c;
+    if curvature >= 0.0 {
+        return (b, k as f64 * h);
+    }
+    let offset = 0.5 * (a - c) / curvature;
+    (b - 0.25 * (a - c) * offset, (k as f64 + offset) * h)
+}
+
+// Time march with the largest stable step until the flow has settled, then converge the
+// steady equations of the same discretization with Newton–GMRES.
+fn steady_state(params: SimParameters) -> anyhow::Result<Simulation> {
+    let mut sim = Simulation::new(params);
+    while sim.time < 0.3 {
+        sim.params.dt = 0.9 * sim.stable_time_step();
+        sim.step();
+    }
+    let settings = ContinuationSettings {
+        parameter: ContinuationParameter::Rayleigh,
+        target: sim.params.ra,
+        ds: 0.0,
+        ds_min: 0.0,
+        ds_max: 0.0,
+        max_points: 1,
+        newton_tol: 1e-9,
+        max_newton: 20,
+        nusselt_wall: Wall::Left,
+        eigenvalues: None,
+    };
+    let mut newton = Continuation::new(SteadyOperator::new(sim), settings);
+    newton.converge_start()?;
+    Ok(newton.op.sim)
+}
+
+// Differentially heated square cavity: hot left wall, cold right wall, adiabatic top and
+// bottom, Pr = 0.71, at Ra = 1e3 ... 1e6 (or the requested one).
+fn de_vahl_davis(rayleigh: Option<f64>) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let relative = |checks: &mut Vec<Check>, name: String, reference: f64, observed: f64, tolerance: f64| {
+        checks.push(Check { name, reference, observed, tolerance: tolerance * reference.abs() });
+    };
+
+    for case in DE_VAHL_DAVIS.iter().filter(|c| rayleigh.is_none_or(|ra| ra == c.rayleigh)) {
+        let n = case.grid;
+        let h = 1.0 / (n as f64 - 1.0);
+        let label = |quantity: &str| format!("Ra = {:.0e}: {} ({}x{})", case.rayleigh, quantity, n, n);
+        let sim = match steady_state(cavity(n, 0.71, case.rayleigh, side_heated_walls(), None)) {
+            Ok(sim) => sim,
+            Err(e) => {
+                checks.push(Check { name: label(&format!("steady state ({})", e)), reference: 0.0, observed: f64::NAN, tolerance: 0.0 });
+                continue;
+            }
+        };
+        let state = &sim.state;
+        // Location tolerances: one and a half grid spacings
+        let position = 1.5 * h;
+
+        let ((i, j), psi) = state
+            .stream
+            .indexed_iter()
+            .fold(((0, 0), 0.0f64), |best, (ij, v)| if v.abs() > best.1 { (ij, v.abs()) } else { best });
+        // The solution is centro-symmetric; of the two equal maxima the benchmark gives the
+        // one in the left half
+        let (x, y) = (j as f64 * h, i as f64 * h);
+        let (x, y) = if x > 0.5 { (1.0 - x, 1.0 - y) } else { (x, y) };
+        relative(&mut checks, label("|psi|max"), case.psi_max.0, psi, case.tolerance);
+        checks.push(Check { name: label("|psi|max x"), reference: case.psi_max.1, observed: x, tolerance: position });
+        checks.push(Check { name: label("|psi|max y"), reference: case.psi_max.2, observed: y, tolerance: position });
+
+        let u_centerline: Vec<f64> = state.u.column((n - 1) / 2).to_vec();
+        let (u_max, u_at) = peak(&u_centerline, h);
+        relative(&mut checks, label("u max, x = 0.5"), case.u_max.0, u_max, case.tolerance);
+        checks.push(Check { name: label("u max y"), reference: case.u_max.1, observed: u_at, tolerance: position });
+
+        let v_centerline: Vec<f64> = state.v.row((n - 1) / 2).to_vec();
+        let (v_max, v_at) = peak(&v_centerline, h);
+        relative(&mut checks, label("v max, y = 0.5"), case.v_max.0, v_max, case.tolerance);
+        checks.push(Check { name: label("v max x"), reference: case.v_max.1, observed: v_at, tolerance: position });
+
+        let nusselt = local_nusselt(&state.temp, Wall::Left, &sim.params);
+        relative(&mut checks, label("mean Nu, hot wall"), case.nu_mean, wall_nusselt(&state.temp, Wall::Left, &sim.params), case.tolerance);
+        let (nu_max, nu_max_at) = peak(&nusselt, h);
+        relative(&mut checks, label("max Nu, hot wall"), case.nu_max.0, nu_max, case.tolerance);
+        checks.push(Check { name: label("max Nu y"), reference: case.nu_max.1, observed: nu_max_at, tolerance: position });
+        let negated: Vec<f64> = nusselt.iter().map(|v| -v).collect();
+        let (nu_min, nu_min_at) = peak(&negated, h);
+        relative(&mut checks, label("min Nu, hot wall"), case.nu_min.0, -nu_min, case.tolerance);
+        checks.push(Check { name: label("min Nu y"), reference: case.nu_min.1, observed: nu_min_at, tolerance: position });
+    }
+    checks
+}
+
+// Layer heated from below between rigid walls: the conductive state T = 1 − y loses
+// stability at Ra_c = 1707.76 with wavenumber k_c = 3.117 (Chandrasekhar, Hydrodynamic and
+// Hydromagnetic Stability, 1961). The periodic cell is one critical wavelength wide, so
+// its fundamental mode is the critical one, and Ra_c is interpolated from the leading
+// eigenvalue of the rest state on either side, as for the double-diffusive onset.
+fn rayleigh_benard() -> Vec<Check> {
+    let (reference, wavenumber) = (1707.76, 3.117);
+    let ny = 25;
+    let domain = Domain { width: 2.0 * std::f64::consts::PI / wavenumber, periodic: true };
+    let walls = ThermalBoundary {
+        segments: vec![
+            BoundarySegment::whole(Wall::Bottom, ThermalBc::Fixed(1.0)),
+            BoundarySegment::whole(Wall::Top, ThermalBc::Fixed(0.0)),
+        ],
+        ..ThermalBoundary::default()
+    };
+    let growth_rate = |ra: f64| {
+        let params = SimParameters { thermal: walls.clone(), ..SimParameters::on_domain(ny, 0.71, ra, domain) };
+        let mut sim = Simulation::new(params);
+        let (ny, nx) = sim.state.temp.dim();
+        for i in 0..ny {
+            for j in 0..nx {
+                sim.state.temp[[i, j]] = 1.0 - i as f64 / (ny as f64 - 1.0);
+            }
+        }
+        let mut op = SteadyOperator::new(sim);
+        let settings = StabilitySettings { horizon: 0.05, krylov_dim: 20, modes: 3, seed: 1 };
+        stability::leading_modes(&mut op, &settings)
+            .iter()
+            .map(|m| m.growth_rate)
+            .fold(f64::MIN, f64::max)
+    };
+    let (low, high) = (0.9 * reference, 1.1 * reference);
+    let (sigma_low, sigma_high) = (growth_rate(low), growth_rate(high));
+    vec![Check {
+        name: format!("Rigid-rigid onset: critical Ra ({}x{}, periodic)", domain.nx(ny) - 2, ny),
+        reference,
+        observed: low - sigma_low * (high - low) / (sigma_high - sigma_low),
+        tolerance: 0.03 * reference,
+    }]
+}
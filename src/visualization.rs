@@ -1,4 +1,8 @@
-use crate::simulation::SimState;
+use crate::continuation::{BranchPoint, PointKind};
+use crate::diagnostics::{local_nusselt, DiagnosticsSample, WALLS};
+use crate::mask::CellType;
+use ndarray::Array2;
+use crate::simulation::{SimParameters, SimState};
 use anyhow::Result;
 use plotters::prelude::*;
 
@@ -8,7 +12,7 @@ pub fn draw_temperature_map(state: &SimState, output_path: &str) -> Result<()> {
     root.fill(&WHITE)?;
 
     let mut chart = ChartBuilder::on(&root)
-        .title("Temperature Distribution", ("sans-serif", 30))
+        .caption("Temperature Distribution", ("sans-serif", 30))
         .margin(20)
         .build_cartesian_2d(0..nx, 0..ny)?;
 
@@ -27,8 +31,15 @@ pub fn draw_temperature_map(state: &SimState, output_path: &str) -> Result<()> {
         })
     )?;
 
+    // Outline the staircase boundary of the cell mask
+    chart.draw_series(
+        (0..nx).flat_map(|x| (0..ny).map(move |y| (x, y)))
+        .filter(|&(x, y)| state.mask[[y, x]] == CellType::Boundary)
+        .map(|(x, y)| Rectangle::new([(x, y), (x + 1, y + 1)], BLACK.stroke_width(1)))
+    )?;
+
     // Add a color bar
-    chart.configure_series_labels().border_style(&BLACK).draw()?;
+    chart.configure_series_labels().border_style(BLACK).draw()?;
     
     let mut color_bar_chart = ChartBuilder::on(&root)
         .margin_left(700)
@@ -47,4 +58,215 @@ pub fn draw_temperature_map(state: &SimState, output_path: &str) -> Result<()> {
     root.present()?;
     println!("Visualization saved to {}", output_path);
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn draw_cell_mask(state: &SimState, output_path: &str) -> Result<()> {
+    let (ny, nx) = state.mask.dim();
+    let root = BitMapBackend::new(output_path, (700, 700)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Cell Mask (fluid / boundary / solid)", ("sans-serif", 30))
+        .margin(20)
+        .build_cartesian_2d(0..nx, 0..ny)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .disable_y_mesh()
+        .draw()?;
+
+    chart.draw_series(
+        (0..nx).flat_map(|x| (0..ny).map(move |y| (x, y, state.mask[[y, x]])))
+        .map(|(x, y, cell)| {
+            let color = match cell {
+                CellType::Fluid => RGBColor(120, 170, 230),
+                CellType::Boundary => RGBColor(60, 60, 60),
+                CellType::Solid => RGBColor(170, 170, 170),
+            };
+            Rectangle::new([(x, y), (x + 1, y + 1)], color.filled())
+        })
+    )?;
+
+    root.present()?;
+    println!("Cell mask saved to {}", output_path);
+    Ok(())
+}
+// Signed field, e.g. an eigenmode, on a symmetric blue-white-red scale scaled to max |f|.
+pub fn draw_field_map(field: &Array2<f64>, title: &str, output_path: &str) -> Result<()> {
+    let (ny, nx) = field.dim();
+    let scale = field.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(f64::MIN_POSITIVE);
+    let root = BitMapBackend::new(output_path, (700, 700)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 30))
+        .margin(20)
+        .build_cartesian_2d(0..nx, 0..ny)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .disable_y_mesh()
+        .draw()?;
+
+    chart.draw_series(
+        (0..nx).flat_map(|x| (0..ny).map(move |y| (x, y, field[[y, x]] / scale)))
+        .map(|(x, y, value)| {
+            let fade = (255.0 * (1.0 - value.abs())) as u8;
+            let color = if value >= 0.0 { RGBColor(255, fade, fade) } else { RGBColor(fade, fade, 255) };
+            Rectangle::new([(x, y), (x + 1, y + 1)], color.filled())
+        })
+    )?;
+
+    root.present()?;
+    println!("{} saved to {}", title, output_path);
+    Ok(())
+}
+
+// Nu against the continuation parameter. Segments are blue where the end point is
+// linearly stable, red where unstable and grey without eigenvalues; special points are circled.
+pub fn draw_bifurcation_diagram(points: &[BranchPoint], parameter: &str, output_path: &str) -> Result<()> {
+    let root = BitMapBackend::new(output_path, (800, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let (p_min, p_max) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.parameter), hi.max(p.parameter)));
+    let (nu_min, nu_max) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.nusselt), hi.max(p.nusselt)));
+    let pad = |lo: f64, hi: f64| 0.05 * (hi - lo).max(1e-12);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Bifurcation Diagram", ("sans-serif", 30))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(
+            (p_min - pad(p_min, p_max))..(p_max + pad(p_min, p_max)),
+            (nu_min - pad(nu_min, nu_max))..(nu_max + pad(nu_min, nu_max)),
+        )?;
+
+    chart.configure_mesh().x_desc(parameter).y_desc("Nu").draw()?;
+
+    for pair in points.windows(2) {
+        let color = match pair[1].growth_rate {
+            Some(sigma) if sigma > 0.0 => RED,
+            Some(_) => BLUE,
+            None => RGBColor(110, 110, 110),
+        };
+        chart.draw_series(LineSeries::new(
+            pair.iter().map(|p| (p.parameter, p.nusselt)),
+            color.stroke_width(2),
+        ))?;
+    }
+
+    chart.draw_series(
+        points
+            .iter()
+            .filter(|p| p.kind != PointKind::Regular)
+            .map(|p| Circle::new((p.parameter, p.nusselt), 6, BLACK.stroke_width(2))),
+    )?;
+
+    root.present()?;
+    println!("Bifurcation diagram saved to {}", output_path);
+    Ok(())
+}
+
+const WALL_COLORS: [RGBColor; 4] = [RGBColor(200, 30, 30), RGBColor(30, 30, 200), RGBColor(30, 150, 30), RGBColor(200, 130, 0)];
+const WALL_NAMES: [&str; 4] = ["bottom", "top", "left", "right"];
+
+// Time series of a run: mean wall Nusselt numbers, kinetic energy, enstrophy and the
+// conservation errors.
+pub fn draw_diagnostics(history: &[DiagnosticsSample], output_path: &str) -> Result<()> {
+    let root = BitMapBackend::new(output_path, (900, 1300)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let panels = root.split_evenly((4, 1));
+
+    let (t_min, t_max) = history.iter().fold((f64::MAX, f64::MIN), |(lo, hi), s| (lo.min(s.time), hi.max(s.time)));
+    let t_max = t_max.max(t_min + 1e-12);
+    let range = |values: &mut dyn Iterator<Item = f64>| {
+        let (lo, hi) = values.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let pad = 0.05 * (hi - lo).max(1e-12);
+        (lo - pad)..(hi + pad)
+    };
+
+    let mut chart = ChartBuilder::on(&panels[0])
+        .caption("Mean wall Nusselt numbers", ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(60)
+        .build_cartesian_2d(t_min..t_max, range(&mut history.iter().flat_map(|s| s.nusselt)))?;
+    chart.configure_mesh().x_desc("t").y_desc("Nu").draw()?;
+    for (k, color) in WALL_COLORS.iter().enumerate() {
+        chart
+            .draw_series(LineSeries::new(history.iter().map(|s| (s.time, s.nusselt[k])), color.stroke_width(2)))?
+            .label(WALL_NAMES[k])
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+
+    let kinetic_energy = |s: &DiagnosticsSample| s.kinetic_energy;
+    let enstrophy = |s: &DiagnosticsSample| s.enstrophy;
+    for (panel, (title, value)) in panels[1..3].iter().zip([("Kinetic energy", kinetic_energy as fn(&DiagnosticsSample) -> f64), ("Enstrophy", enstrophy)]) {
+        let mut chart = ChartBuilder::on(panel)
+            .caption(title, ("sans-serif", 24))
+            .margin(15)
+            .x_label_area_size(35)
+            .y_label_area_size(60)
+            .build_cartesian_2d(t_min..t_max, range(&mut history.iter().map(value)))?;
+        chart.configure_mesh().x_desc("t").draw()?;
+        chart.draw_series(LineSeries::new(history.iter().map(|s| (s.time, value(s))), BLUE.stroke_width(2)))?;
+    }
+
+    // Relative conservation errors on a log scale, floored so exact zeros stay on the chart
+    let errors = |s: &DiagnosticsSample| [s.balance.heat_imbalance, s.balance.divergence, s.balance.poisson_residual].map(|e| e.max(1e-16).log10());
+    let mut chart = ChartBuilder::on(&panels[3])
+        .caption("Conservation errors", ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(60)
+        .build_cartesian_2d(t_min..t_max, range(&mut history.iter().flat_map(errors)))?;
+    chart.configure_mesh().x_desc("t").y_desc("log10 relative error").draw()?;
+    for (k, (name, color)) in [("Heat", RED), ("Divergence", BLUE), ("Poisson", GREEN)].into_iter().enumerate() {
+        chart
+            .draw_series(LineSeries::new(history.iter().map(|s| (s.time, errors(s)[k])), color.stroke_width(2)))?
+            .label(name)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+
+    root.present()?;
+    println!("Diagnostics plot saved to {}", output_path);
+    Ok(())
+}
+
+// Local Nusselt number along each outer wall against the position along it.
+pub fn draw_local_nusselt(temp: &Array2<f64>, params: &SimParameters, output_path: &str) -> Result<()> {
+    let root = BitMapBackend::new(output_path, (800, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let profiles = WALLS.map(|wall| local_nusselt(temp, wall, params));
+    let (lo, hi) = profiles.iter().flatten().fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    let pad = 0.05 * (hi - lo).max(1e-12);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Local Nusselt Numbers", ("sans-serif", 30))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..params.domain.width.max(1.0), (lo - pad)..(hi + pad))?;
+    chart.configure_mesh().x_desc("Position along the wall").y_desc("Nu").draw()?;
+
+    for (k, profile) in profiles.iter().enumerate() {
+        let (dx, dy) = params.spacing();
+        let h = if k < 2 { dx } else { dy };
+        let color = WALL_COLORS[k];
+        chart
+            .draw_series(LineSeries::new(profile.iter().enumerate().map(|(s, nu)| (s as f64 * h, *nu)), color.stroke_width(2)))?
+            .label(WALL_NAMES[k])
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+
+    root.present()?;
+    println!("Local Nusselt numbers saved to {}", output_path);
+    Ok(())
+}
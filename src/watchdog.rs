@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::simulation::SimState;
+
+// Largest |value| a field may reach before the run counts as blown up. The nondimensional
+// fields stay many orders of magnitude below this at any Rayleigh number the grids resolve.
+pub const MAGNITUDE_LIMIT: f64 = 1e12;
+// Factor by which max |field| may grow between two checks once it exceeds 1. The first
+// check only records the peaks, as the impulsive start legitimately jumps from rest.
+pub const GROWTH_LIMIT: f64 = 10.0;
+
+const FIELD_NAMES: [&str; 8] = ["temperature", "vorticity", "stream function", "u", "v", "concentration", "gas vorticity", "gas stream function"];
+
+// Time step limits of a state: the advective Courant number dt (|u|/Δx + |v|/Δy), the
+// diffusive number 2 D dt (1/Δx² + 1/Δy²) and dt over the stable forward Euler step.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CflStats {
+    pub dt: f64,
+    pub stable_dt: f64,
+    pub advective: f64,
+    pub diffusive: f64,
+    pub max_u: f64,
+    pub max_v: f64,
+}
+
+impl fmt::Display for CflStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "dt = {:.3e} (stable {:.3e}, ratio {:.2}), advective CFL {:.3}, diffusive {:.3}, max |u| {:.3e}, max |v| {:.3e}",
+            self.dt,
+            self.stable_dt,
+            self.dt / self.stable_dt,
+            self.advective,
+            self.diffusive,
+            self.max_u,
+            self.max_v
+        )
+    }
+}
+
+// What stopped a run, with the step limits just before and at the failure.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlowUp {
+    pub step: usize,
+    pub time: f64,
+    pub reason: String,
+    pub last_good_step: usize,
+    pub last_good: Option<CflStats>,
+    pub first_bad: CflStats,
+    pub dumps: Vec<PathBuf>, // First bad and last good states, if written
+}
+
+impl BlowUp {
+    pub fn print(&self) {
+        println!("Run blew up at step {} (t = {:.6}): {}", self.step, self.time, self.reason);
+        if let Some(stats) = &self.last_good {
+            println!("  last good, step {}: {}", self.last_good_step, stats);
+        }
+        println!("  first bad, step {}: {}", self.step, self.first_bad);
+        for path in &self.dumps {
+            println!("  state written to {}", path.display());
+        }
+    }
+}
+
+// A state that passed the check, kept to be dumped next to the first bad one.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub state: SimState,
+    pub steps_taken: usize,
+    pub time: f64,
+    pub cfl: CflStats,
+}
+
+// Checks the fields every `interval` steps of `Simulation::run`. With a `dump_prefix` the
+// last kept good state and the first bad one are written as `<prefix>_last_good.ckpt` and
+// `<prefix>_first_bad.ckpt` when the run blows up.
+pub struct Watchdog {
+    pub interval: usize, // 0 disables the checks
+    pub dump_prefix: Option<String>,
+    pub last_good: Option<Snapshot>,
+    pub peaks: [f64; 8], // max |field| at the last check
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Watchdog { interval: 1, dump_prefix: None, last_good: None, peaks: [0.0; 8] }
+    }
+}
+
+impl Watchdog {
+    pub fn due(&self, steps_taken: usize) -> bool {
+        self.interval > 0 && steps_taken.is_multiple_of(self.interval)
+    }
+
+    // Whether a state that just passed the check is kept for the dump: at most once per
+    // `spacing` steps (the checkpoint interval), so long runs do not copy the fields at
+    // every check.
+    pub fn snapshot_due(&self, steps_taken: usize, spacing: usize) -> bool {
+        self.dump_prefix.is_some() && self.last_good.as_ref().is_none_or(|s| steps_taken >= s.steps_taken + spacing)
+    }
+
+    // Why the state is unusable, if it is: a non-finite value, a field beyond
+    // `MAGNITUDE_LIMIT` or one that grew by more than `GROWTH_LIMIT` since the last check.
+    pub fn check(&mut self, state: &SimState) -> Option<String> {
+        let fields = [&state.temp, &state.vort, &state.stream, &state.u, &state.v, &state.conc, &state.gas_vort, &state.gas_stream];
+        let mut peaks = [0.0; 8];
+        for (k, field) in fields.iter().enumerate() {
+            if let Some(((i, j), value)) = field.indexed_iter().find(|(_, v)| !v.is_finite()) {
+                let count = field.iter().filter(|v| !v.is_finite()).count();
+                return Some(format!("{} is {} at node (i = {}, j = {}), {} non-finite values", FIELD_NAMES[k], value, i, j, count));
+            }
+            peaks[k] = field.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+            if peaks[k] > MAGNITUDE_LIMIT {
+                return Some(format!("max |{}| = {:.3e} exceeds {:.0e}", FIELD_NAMES[k], peaks[k], MAGNITUDE_LIMIT));
+            }
+            if self.peaks[k] > 0.0 && peaks[k] > GROWTH_LIMIT * self.peaks[k].max(1.0) {
+                return Some(format!("max |{}| grew from {:.3e} to {:.3e} in {} steps", FIELD_NAMES[k], self.peaks[k], peaks[k], self.interval));
+            }
+        }
+        self.peaks = peaks;
+        None
+    }
+}
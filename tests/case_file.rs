@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use cz_cfd_simulator::boundary::{ThermalBc, Wall};
+use cz_cfd_simulator::case_file::CaseFile;
+use cz_cfd_simulator::regions::Region;
+use cz_cfd_simulator::simulation::{SimParameters, Simulation};
+
+fn write(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cz_case_{}_{}", std::process::id(), name));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+// The same case written as TOML and as JSON parses to the same shapes, wall segments
+// and probes.
+#[test]
+fn toml_and_json_cases_agree() {
+    let toml = write(
+        "case.toml",
+        r#"
+background = "melt"
+[[shapes]]
+region = "crucible"
+type = "polygon"
+points = [[0.0, 0.0], [1.0, 0.0], [1.0, 0.1], [0.0, 0.1]]
+bc = { fixed = 1.0 }
+[[shapes]]
+region = "crystal"
+type = "arc"
+center = [0.5, 1.0]
+radius = 0.2
+start_angle = 180.0
+end_angle = 360.0
+sector = true
+[[probes]]
+name = "tc"
+x = 0.5
+y = 0.05
+"#,
+    );
+    let json = write(
+        "case.json",
+        r#"{
+  "background": "melt",
+  "shapes": [
+    { "region": "crucible", "type": "polygon", "points": [[0.0, 0.0], [1.0, 0.0], [1.0, 0.1], [0.0, 0.1]], "bc": { "fixed": 1.0 } },
+    { "region": "crystal", "type": "arc", "center": [0.5, 1.0], "radius": 0.2, "start_angle": 180.0, "end_angle": 360.0, "sector": true }
+  ],
+  "probes": [{ "name": "tc", "x": 0.5, "y": 0.05 }]
+}"#,
+    );
+    let (from_toml, from_json) = (CaseFile::load(&toml).unwrap(), CaseFile::load(&json).unwrap());
+    std::fs::remove_file(toml).ok();
+    std::fs::remove_file(json).ok();
+
+    assert_eq!(from_toml.obstacles(1.0), from_json.obstacles(1.0));
+    assert_eq!(from_toml.obstacles(1.0).len(), 3);
+    assert_eq!(from_toml.boundary_segments(1.0), from_json.boundary_segments(1.0));
+    assert_eq!((from_json.probes[0].name.as_str(), from_json.probes[0].x, from_json.probes[0].y), ("tc", 0.5, 0.05));
+    assert!(CaseFile::load(Path::new("case.yaml")).is_err());
+}
+
+// The shipped heat shield case rasterizes to its regions at the expected nodes, with the
+// crucible's and crystal's tags on the walls they touch and the explicit segments last.
+#[test]
+fn heat_shield_case_rasterizes() {
+    let case = CaseFile::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("cases/crucible_heat_shield.toml")).unwrap();
+    let sim = Simulation::new(SimParameters { obstacles: case.obstacles(1.0), ..SimParameters::cavity(41, 0.01, 1e3) });
+    // Node (i, j) at (x, y) = (j, i) / 40
+    let at = |x: f64, y: f64| sim.state.region[[(y * 40.0).round() as usize, (x * 40.0).round() as usize]];
+    assert_eq!(at(0.5, 0.05), Region::Crucible);
+    assert_eq!(at(0.05, 0.5), Region::Crucible);
+    assert_eq!(at(0.5, 0.2), Region::Melt);
+    assert_eq!(at(0.5, 0.5), Region::Melt);
+    assert_eq!(at(0.5, 0.9), Region::Crystal);
+    assert_eq!(at(0.2, 0.85), Region::HeatShield);
+    assert_eq!(at(0.05, 0.9), Region::Gas);
+
+    let segments = case.boundary_segments(1.0);
+    let on = |wall: Wall| segments.iter().filter(move |s| s.wall == wall);
+    assert_eq!(on(Wall::Bottom).map(|s| (s.start, s.end, s.bc)).collect::<Vec<_>>(), [(0.0, 1.0, ThermalBc::Fixed(1.0))]);
+    let crystal = on(Wall::Top).find(|s| s.bc == ThermalBc::Fixed(0.0)).unwrap();
+    assert!((crystal.start - 0.35).abs() < 1e-3 && (crystal.end - 0.65).abs() < 1e-3, "{:?}", crystal);
+    let left = on(Wall::Left).next().unwrap();
+    assert!(left.start == 0.0 && (left.end - 0.8).abs() < 1e-3 && left.bc == ThermalBc::Fixed(1.0), "{:?}", left);
+    assert!(segments[segments.len() - 2..].iter().all(|s| s.wall == Wall::Top && matches!(s.bc, ThermalBc::Radiative { .. })));
+}
+
+// Wall segments and shape conditions of a case file are range checked like the command
+// line ones.
+#[test]
+fn case_boundaries_are_range_checked() {
+    let segment = write("segment.toml", "[[boundaries]]\nwall = \"top\"\nstart = 0.0\nend = 3.0\nbc = \"adiabatic\"\n");
+    let shape = write(
+        "shape.json",
+        r#"{ "shapes": [{ "region": "crucible", "type": "polygon", "points": [[0.0, 0.0], [1.0, 0.0], [1.0, 0.1]], "bc": { "radiative": { "emissivity": 7.0, "t_env": 0.0 } } }] }"#,
+    );
+    let (segment_error, shape_error) = (CaseFile::load(&segment).unwrap_err(), CaseFile::load(&shape).unwrap_err());
+    std::fs::remove_file(segment).ok();
+    std::fs::remove_file(shape).ok();
+    assert!(segment_error.root_cause().to_string().ends_with("must satisfy 0 <= start < end <= 1"), "{:#}", segment_error);
+    assert!(shape_error.root_cause().to_string().starts_with("emissivity must satisfy"), "{:#}", shape_error);
+}
@@ -0,0 +1,115 @@
+use cz_cfd_simulator::checkpoint::Checkpoint;
+use cz_cfd_simulator::diagnostics::DiagnosticsLog;
+use cz_cfd_simulator::gas::GasParameters;
+use cz_cfd_simulator::growth::GrowthParameters;
+use cz_cfd_simulator::induction::InductionParameters;
+use cz_cfd_simulator::regions::RegionLayout;
+use cz_cfd_simulator::probes::ProbeSet;
+use cz_cfd_simulator::simulation::{CheckpointSettings, RunOutcome, SimParameters, Simulation};
+use cz_cfd_simulator::Solver;
+
+// Induction, purge gas and a crystal pulled fast enough to remesh the melt a few times,
+// so every piece of solver state a checkpoint has to carry is in play.
+fn furnace() -> SimParameters {
+    SimParameters {
+        dt: 1e-5,
+        layout: Some(RegionLayout::default()),
+        growth: Some(GrowthParameters { pull_rate: 2e4, density_ratio: 0.91, log_interval: 10 }),
+        induction: Some(InductionParameters::new(50.0, 1.0, vec!["0.8,0.3".parse().unwrap(), "0.8,0.5".parse().unwrap()]).unwrap()),
+        gas: Some(GasParameters::new(vec!["top:0.12:0.17".parse().unwrap()], vec!["top:0.22:0.27".parse().unwrap()], 50.0, 0.0, 10.0).unwrap()),
+        ..SimParameters::cavity(21, 0.01, 1e4)
+    }
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    let steps = 200;
+    let mut straight = Simulation::new(furnace());
+    straight.check_geometry().unwrap();
+    assert!(straight.advance_watched(steps).is_none());
+
+    let path = std::env::temp_dir().join(format!("cz_resume_{}.ckpt", std::process::id()));
+    let mut first = Simulation::new(furnace());
+    assert!(first.advance_watched(steps / 2).is_none());
+    first.checkpoint = Some(CheckpointSettings { path: path.clone(), interval: 0, run_id: None });
+    first.write_checkpoint(steps).unwrap();
+    let mut resumed = Simulation::from_checkpoint(Checkpoint::load(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert!(resumed.advance_watched(steps - steps / 2).is_none());
+
+    let melt_height = |sim: &Simulation| sim.params.layout.as_ref().unwrap().melt_height;
+    assert!(melt_height(&straight) < RegionLayout::default().melt_height - 0.1, "the melt surface should have crossed grid rows");
+    assert_eq!(resumed.steps_taken, straight.steps_taken);
+    assert_eq!(resumed.time, straight.time);
+    assert_eq!(melt_height(&resumed), melt_height(&straight));
+    assert_eq!(resumed.growth.as_ref().unwrap().crystal_length, straight.growth.as_ref().unwrap().crystal_length);
+    assert_eq!(resumed.state.region, straight.state.region);
+    assert_eq!(resumed.joule_heating(), straight.joule_heating());
+    let (a, b) = (&resumed.state, &straight.state);
+    assert!(b.gas_stream.iter().any(|psi| *psi != 0.0), "the purge gas should be flowing");
+    for (name, x, y) in [
+        ("temperature", &a.temp, &b.temp),
+        ("vorticity", &a.vort, &b.vort),
+        ("stream function", &a.stream, &b.stream),
+        ("u", &a.u, &b.u),
+        ("v", &a.v, &b.v),
+        ("concentration", &a.conc, &b.conc),
+        ("gas vorticity", &a.gas_vort, &b.gas_vort),
+        ("gas stream function", &a.gas_stream, &b.gas_stream),
+    ] {
+        assert!(x == y, "{} differs after the resume", name);
+    }
+}
+
+// The watchdog resumes as it was set for the run, comparing its next growth check with
+// the peaks of the last one before the checkpoint.
+#[test]
+fn resume_keeps_the_watchdog() {
+    let path = std::env::temp_dir().join(format!("cz_watchdog_{}.ckpt", std::process::id()));
+    let mut sim = Simulation::new(furnace());
+    sim.watchdog.interval = 7;
+    sim.watchdog.dump_prefix = Some("run_7_blowup".to_string());
+    assert!(sim.advance_watched(14).is_none());
+    assert!(sim.watchdog.peaks.iter().any(|&p| p > 0.0));
+    sim.checkpoint = Some(CheckpointSettings { path: path.clone(), interval: 0, run_id: None });
+    sim.write_checkpoint(20).unwrap();
+    let resumed = Simulation::from_checkpoint(Checkpoint::load(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed.watchdog.interval, 7);
+    assert_eq!(resumed.watchdog.peaks, sim.watchdog.peaks);
+    assert_eq!(resumed.watchdog.dump_prefix.as_deref(), Some("run_7_blowup"));
+}
+
+// A run far past its stable time step blows up; both dumps load, although the first bad
+// one carries NaN in the conservation balance, the diagnostics and the probe samples.
+#[test]
+fn blow_up_dumps_load() {
+    let prefix = std::env::temp_dir().join(format!("cz_blowup_{}", std::process::id()));
+    let params = SimParameters { dt: 1e-5, ..SimParameters::cavity(21, 0.71, 1e9) };
+    let mut sim = Simulation::new(params.clone());
+    sim.watchdog.interval = 8;
+    sim.watchdog.dump_prefix = Some(prefix.display().to_string());
+    sim.diagnostics = Some(DiagnosticsLog { interval: 1, history: Vec::new() });
+    sim.probes = Some(ProbeSet::new(1, &["centre:0.5,0.5".parse().unwrap()], &[], &params).unwrap());
+    assert!(matches!(sim.run(8).unwrap(), RunOutcome::Completed));
+    sim.params.dt = 1e-2;
+    let RunOutcome::BlewUp(report) = sim.run(1000).unwrap() else {
+        panic!("the run should have blown up");
+    };
+    assert!(report.reason.contains("NaN"), "{}", report.reason);
+    assert_eq!(report.dumps.len(), 2);
+
+    let bad = Checkpoint::load(&report.dumps[0]).unwrap();
+    let good = Checkpoint::load(&report.dumps[1]).unwrap();
+    for path in &report.dumps {
+        std::fs::remove_file(path).unwrap();
+    }
+    assert_eq!(bad.meta.steps_taken, report.step);
+    assert_eq!(good.meta.steps_taken, report.last_good_step);
+    let stored = bad.meta.blow_up.unwrap();
+    assert_eq!(stored.reason, report.reason);
+    let same = |a: f64, b: f64| a == b || (a.is_nan() && b.is_nan());
+    assert!(same(stored.first_bad.max_u, report.first_bad.max_u));
+    assert!(same(bad.meta.conservation.latest.divergence, sim.conservation.latest.divergence));
+    assert!(good.state.temp.iter().all(|t| t.is_finite()));
+}
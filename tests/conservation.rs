@@ -0,0 +1,68 @@
+use cz_cfd_simulator::boundary::{BoundarySegment, ThermalBc, ThermalBoundary, Wall};
+use cz_cfd_simulator::conservation::{DIVERGENCE_LIMIT, HEAT_IMBALANCE_LIMIT};
+use cz_cfd_simulator::gas::GasParameters;
+use cz_cfd_simulator::heating::HeatSource;
+use cz_cfd_simulator::regions::RegionLayout;
+use cz_cfd_simulator::simulation::{SimParameters, Simulation};
+use cz_cfd_simulator::Solver;
+
+fn cavity(ra: f64, thermal: ThermalBoundary) -> SimParameters {
+    SimParameters { thermal, ..SimParameters::cavity(21, 0.71, ra) }
+}
+
+fn run(params: SimParameters, steps: usize) -> Simulation {
+    let mut sim = Simulation::new(params);
+    sim.params.dt = 0.5 * sim.stable_time_step();
+    assert!(sim.advance_watched(steps).is_none());
+    sim
+}
+
+// Without flow the balance is the scheme's own conduction, which closes to round-off,
+// also across the material interfaces of the furnace regions.
+#[test]
+fn conduction_closes_the_heat_balance() {
+    let mut params = cavity(0.0, ThermalBoundary::default());
+    params.layout = Some(RegionLayout::default());
+    let sim = run(params, 200);
+    let balance = sim.conservation.latest;
+    assert!(balance.heat_imbalance < 1e-9, "{:?}", balance);
+}
+
+// Heat released in an adiabatic box is all stored.
+#[test]
+fn source_heat_is_stored() {
+    let adiabatic = ThermalBoundary {
+        segments: [Wall::Bottom, Wall::Top, Wall::Left, Wall::Right].into_iter().map(|w| BoundarySegment::whole(w, ThermalBc::Adiabatic)).collect(),
+        ..ThermalBoundary::default()
+    };
+    let mut params = cavity(0.0, adiabatic);
+    params.heat_sources = vec!["uniform:1".parse::<HeatSource>().unwrap()];
+    let sim = run(params, 200);
+    let balance = sim.conservation.latest;
+    assert_eq!((balance.heat_in, balance.heat_out), (0.0, 0.0));
+    assert!((balance.storage_rate - balance.heat_generated).abs() < 1e-9 * balance.heat_generated, "{:?}", balance);
+}
+
+// A convecting cavity stays within the limits the runs are flagged at.
+#[test]
+fn convection_is_not_flagged() {
+    let sim = run(cavity(1e4, ThermalBoundary::default()), 500);
+    let latest = sim.conservation.latest;
+    assert!(!latest.flagged(), "{:?}", latest);
+    assert!(latest.divergence < DIVERGENCE_LIMIT, "{:?}", latest);
+    assert!(latest.heat_imbalance < HEAT_IMBALANCE_LIMIT);
+}
+
+// Velocities differenced centrally from ψ are divergence free to round-off, in the melt
+// of a convecting cavity and in the melt and purge gas of a furnace.
+#[test]
+fn divergence_is_round_off_on_a_solved_field() {
+    let gas = GasParameters::new(vec!["top:0.12:0.17".parse().unwrap()], vec!["top:0.22:0.27".parse().unwrap()], 50.0, 0.0, 10.0).unwrap();
+    let furnace = SimParameters { layout: Some(RegionLayout::default()), gas: Some(gas), ..cavity(1e4, ThermalBoundary::default()) };
+    for params in [cavity(1e4, ThermalBoundary::default()), furnace] {
+        let sim = run(params, 500);
+        assert!(sim.state.stream.iter().chain(sim.state.gas_stream.iter()).any(|psi| psi.abs() > 1e-3));
+        let divergence = sim.conservation.latest.divergence;
+        assert!(divergence < 1e-12, "divergence {:e}", divergence);
+    }
+}
@@ -0,0 +1,23 @@
+use cz_cfd_simulator::continuation::ContinuationParameter;
+use cz_cfd_simulator::simulation::SimParameters;
+
+#[test]
+fn parameters_get_and_set_their_number() {
+    let mut params = SimParameters::cavity(21, 0.71, 1e3);
+    let parameter: ContinuationParameter = "prandtl".parse().unwrap();
+    assert_eq!(parameter, ContinuationParameter::Prandtl);
+    assert_eq!(parameter.name(), "pr");
+    assert_eq!(parameter.get(&params), 0.71);
+    parameter.set(&mut params, 0.02);
+    assert_eq!(params.pr, 0.02);
+}
+
+// The model has no rotation and no thermocapillary surface: asking for Re or Ma is an error
+// that says so, not an unknown name.
+#[test]
+fn reynolds_and_marangoni_numbers_are_refused() {
+    for (name, missing) in [("re", "no Reynolds number"), ("reynolds", "no Reynolds number"), ("ma", "no Marangoni number"), ("marangoni", "no Marangoni number")] {
+        let error = name.parse::<ContinuationParameter>().unwrap_err().to_string();
+        assert!(error.contains(missing), "{}", error);
+    }
+}
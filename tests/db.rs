@@ -0,0 +1,125 @@
+#![cfg(feature = "db")]
+
+use cz_cfd_simulator::conservation::Balance;
+use cz_cfd_simulator::continuation::{BranchPoint, PointKind};
+use cz_cfd_simulator::db;
+use cz_cfd_simulator::diagnostics::DiagnosticsSample;
+use cz_cfd_simulator::growth::GrowthRecord;
+use cz_cfd_simulator::regions::RegionLayout;
+use cz_cfd_simulator::simulation::{Domain, SimParameters, SimState};
+
+// Needs DATABASE_URL pointing at a migrated database:
+// `cargo test --test db -- --ignored`.
+#[test]
+#[ignore]
+fn results_beyond_the_bind_parameter_limit_are_saved() {
+    let pool = db::establish_connection_pool().unwrap();
+    let domain = Domain { width: 2.0, periodic: false };
+    let ny = 81;
+    let run = db::create_simulation_run(&pool, "bind parameter limit", ny as i32, 0, 0.71, 1e4, None).unwrap();
+    db::set_run_domain(&pool, run.id, &domain).unwrap();
+
+    // 161 x 81 nodes, 13 columns each: more than twice the limit of one statement
+    let mut state = SimState::new(domain.nx(ny), ny);
+    state.temp.indexed_iter_mut().for_each(|((i, j), t)| *t = (i * 1000 + j) as f64);
+    state.gas_stream.indexed_iter_mut().for_each(|((i, j), psi)| *psi = (j * 1000 + i) as f64);
+    state.gas_vort.fill(-1.0);
+    db::save_simulation_results(&pool, run.id, &state).unwrap();
+
+    // The gas fields come back too, so a restart from the run keeps the purge gas flowing
+    let stored = db::get_simulation_results(&pool, run.id).unwrap();
+    assert_eq!(stored.temp, state.temp);
+    assert_eq!(stored.gas_stream, state.gas_stream);
+    assert_eq!(stored.gas_vort, state.gas_vort);
+}
+
+// The stored parameters rebuild the run's geometry and conditions, not a plain cavity.
+#[test]
+#[ignore]
+fn run_parameters_round_trip() {
+    let pool = db::establish_connection_pool().unwrap();
+    let params = SimParameters {
+        layout: Some(RegionLayout { crystal_length: Some(0.1), ..RegionLayout::default() }),
+        obstacles: vec!["disk:0.5,0.0,0.2:crucible".parse().unwrap()],
+        ..SimParameters::on_domain(21, 0.01, 1e3, Domain { width: 1.5, periodic: false })
+    };
+    let run = db::create_simulation_run(&pool, "parameters", 21, 0, params.pr, params.ra, None).unwrap();
+    assert!(db::get_run_parameters(&db::get_simulation_run(&pool, run.id).unwrap()).unwrap().is_none());
+
+    db::set_run_parameters(&pool, run.id, &params).unwrap();
+    let stored = db::get_run_parameters(&db::get_simulation_run(&pool, run.id).unwrap()).unwrap().unwrap();
+    assert_eq!(serde_json::to_value(&stored).unwrap(), serde_json::to_value(&params).unwrap());
+}
+
+// A run sampled every 10 steps for 40k steps: more diagnostics than one statement can
+// bind, saved together with the fields and the completed status.
+#[test]
+#[ignore]
+fn long_diagnostics_histories_are_saved_with_the_run() {
+    let pool = db::establish_connection_pool().unwrap();
+    let params = SimParameters::on_domain(11, 0.71, 1e3, Domain { width: 1.0, periodic: false });
+    let run = db::create_simulation_run(&pool, "long diagnostics", 11, 40_000, params.pr, params.ra, None).unwrap();
+    db::set_run_status(&pool, run.id, "running", None).unwrap();
+
+    let state = SimState::new(params.nx, params.ny);
+    let sample = DiagnosticsSample {
+        step: 0,
+        time: 0.0,
+        nusselt: [1.0; 4],
+        kinetic_energy: 0.0,
+        enstrophy: 0.0,
+        max_stream: 0.0,
+        max_u: (0.0, 0.0, 0.0),
+        max_v: (0.0, 0.0, 0.0),
+        balance: Balance::default(),
+    };
+    let history: Vec<DiagnosticsSample> = (0..4000).map(|k| DiagnosticsSample { step: 10 * k, time: 1e-4 * k as f64, ..sample }).collect();
+    let completed = db::CompletedRun {
+        params: &params,
+        state: &state,
+        time: 4.0,
+        growth: None,
+        diagnostics: Some(&history),
+        probes: None,
+        balance: &Balance::default(),
+    };
+    db::save_completed_run(&pool, run.id, &completed).unwrap();
+
+    assert_eq!(db::get_diagnostics(&pool, run.id).unwrap(), history);
+    let stored = db::get_simulation_run(&pool, run.id).unwrap();
+    assert_eq!((stored.status.as_str(), stored.final_time), ("completed", Some(4.0)));
+}
+
+// Long pulls and long branches give more rows than one statement can bind: 14000 growth
+// records at 5 columns and 7000 branch points at 10.
+#[test]
+#[ignore]
+fn long_growth_histories_and_branches_are_saved() {
+    use cz_cfd_simulator::schema::{continuation_points, growth_history};
+    use diesel::prelude::*;
+
+    let pool = db::establish_connection_pool().unwrap();
+    let run = db::create_simulation_run(&pool, "long pull", 21, 14_000, 0.01, 1e3, None).unwrap();
+    let history: Vec<GrowthRecord> = (0..14_000)
+        .map(|k| GrowthRecord { step: k, time: 1e-5 * k as f64, crystal_length: 0.1, melt_height: 0.75 })
+        .collect();
+    db::save_growth_history(&pool, run.id, &history).unwrap();
+    let points: Vec<BranchPoint> = (0..7000)
+        .map(|k| BranchPoint {
+            index: k,
+            parameter: 1e3 + k as f64,
+            nusselt: 1.0,
+            max_stream: 0.0,
+            arclength: k as f64,
+            growth_rate: None,
+            frequency: None,
+            kind: PointKind::Regular,
+        })
+        .collect();
+    db::save_continuation_points(&pool, run.id, "ra", &points).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    let records: i64 = growth_history::table.filter(growth_history::run_id.eq(run.id)).count().get_result(&mut conn).unwrap();
+    let branch: i64 = continuation_points::table.filter(continuation_points::run_id.eq(run.id)).count().get_result(&mut conn).unwrap();
+    assert_eq!((records, branch), (14_000, 7000));
+}
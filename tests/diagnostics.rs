@@ -0,0 +1,43 @@
+use cz_cfd_simulator::boundary::Wall;
+use cz_cfd_simulator::diagnostics::{local_nusselt, wall_nusselt};
+use cz_cfd_simulator::probes::bilinear;
+use cz_cfd_simulator::simulation::{wrap_columns, Domain, SimParameters};
+use ndarray::Array2;
+
+fn grid(domain: Domain, ny: usize) -> SimParameters {
+    SimParameters::on_domain(ny, 0.71, 1e3, domain)
+}
+
+// Pure conduction across a wide cell: T = 1 − x / W between the side walls.
+#[test]
+fn side_wall_nusselt_uses_the_domain_width() {
+    let params = grid(Domain { width: 2.0, periodic: false }, 11);
+    let (dx, _) = params.spacing();
+    let temp = Array2::from_shape_fn((params.ny, params.nx), |(_, j)| 1.0 - j as f64 * dx / 2.0);
+    assert!((wall_nusselt(&temp, Wall::Left, &params) - 0.5).abs() < 1e-12);
+    assert!((wall_nusselt(&temp, Wall::Right, &params) + 0.5).abs() < 1e-12);
+}
+
+// Conduction through a periodic layer, T = 1 − y: Nu = 1 on the bottom wall and no heat
+// through the periodic sides.
+#[test]
+fn periodic_nusselt_skips_the_ghost_columns() {
+    let params = grid(Domain { width: 2.0, periodic: true }, 11);
+    let (_, dy) = params.spacing();
+    let temp = Array2::from_shape_fn((params.ny, params.nx), |(i, _)| 1.0 - i as f64 * dy);
+    assert_eq!(local_nusselt(&temp, Wall::Bottom, &params).len(), params.nx - 2);
+    assert!((wall_nusselt(&temp, Wall::Bottom, &params) - 1.0).abs() < 1e-12);
+    assert_eq!(wall_nusselt(&temp, Wall::Left, &params), 0.0);
+}
+
+#[test]
+fn periodic_probes_wrap_around_the_width() {
+    let domain = Domain { width: 2.0, periodic: true };
+    let params = grid(domain, 11);
+    let (dx, _) = params.spacing();
+    let mut field = Array2::from_shape_fn((params.ny, params.nx), |(_, j)| (std::f64::consts::PI * (j as f64 - 1.0) * dx).cos());
+    wrap_columns(&mut field);
+    assert!((bilinear(&field, &domain, 0.0, 0.5) - 1.0).abs() < 1e-12);
+    assert!((bilinear(&field, &domain, 1.0, 0.5) + 1.0).abs() < 1e-12);
+    assert!((bilinear(&field, &domain, 2.0, 0.5) - 1.0).abs() < 1e-12);
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "capi")]
+
+// Status codes of the C interface, called as a C host would: `cargo test --features capi --test ffi`.
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use cz_cfd_simulator::ffi::*;
+use cz_cfd_simulator::simulation::SimParameters;
+
+fn last_error() -> String {
+    unsafe { CStr::from_ptr(cz_last_error()) }.to_string_lossy().into_owned()
+}
+
+// An 11 x 11 cavity, freed by the caller.
+fn cavity() -> *mut CzSimulation {
+    let mut sim = ptr::null_mut();
+    assert_eq!(unsafe { cz_simulation_new_cavity(11, 1e3, 0.71, 1e-4, &mut sim) }, CzStatus::Ok);
+    sim
+}
+
+// Every entry point refuses null handles and null outputs with a status, not a crash.
+#[test]
+fn null_pointers_are_reported() {
+    let mut buffer = vec![0.0; 121];
+    unsafe {
+        assert_eq!(cz_simulation_advance(ptr::null_mut(), 1), CzStatus::NullPointer);
+        assert_eq!(last_error(), "null simulation handle");
+        assert_eq!(cz_simulation_info(ptr::null(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut()), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_read_field(ptr::null(), 0, buffer.as_mut_ptr(), 121), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_write_field(ptr::null_mut(), 0, buffer.as_ptr(), 121), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_read_wall(ptr::null(), 0, buffer.as_mut_ptr(), 11), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_set_wall_temperature(ptr::null_mut(), 0, 0.0, 1.0, 1.0), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_new(ptr::null(), &mut ptr::null_mut()), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_new_cavity(11, 1e3, 0.71, 1e-4, ptr::null_mut()), CzStatus::NullPointer);
+        assert_eq!(last_error(), "null output pointer");
+        cz_simulation_free(ptr::null_mut());
+
+        let sim = cavity();
+        assert_eq!(cz_simulation_read_field(sim, 0, ptr::null_mut(), 121), CzStatus::NullPointer);
+        assert_eq!(cz_simulation_write_field(sim, 0, ptr::null(), 121), CzStatus::NullPointer);
+        assert_eq!(last_error(), "null buffer");
+        cz_simulation_free(sim);
+    }
+}
+
+// Buffers must match the field (nx·ny) or the wall (nx or ny) exactly.
+#[test]
+fn buffer_lengths_are_checked() {
+    let sim = cavity();
+    let mut buffer = vec![0.0; 122];
+    unsafe {
+        for len in [120, 122] {
+            assert_eq!(cz_simulation_read_field(sim, 0, buffer.as_mut_ptr(), len), CzStatus::BufferSize);
+            assert_eq!(cz_simulation_write_field(sim, 0, buffer.as_ptr(), len), CzStatus::BufferSize);
+        }
+        assert_eq!(last_error(), "field has 121 values, buffer 122");
+        assert_eq!(cz_simulation_read_wall(sim, 2, buffer.as_mut_ptr(), 121), CzStatus::BufferSize);
+        assert_eq!(cz_simulation_read_wall(sim, 2, buffer.as_mut_ptr(), 11), CzStatus::Ok);
+
+        // The right length round-trips an interior value
+        assert_eq!(cz_simulation_read_field(sim, 0, buffer.as_mut_ptr(), 121), CzStatus::Ok);
+        buffer[5 * 11 + 5] = 0.25;
+        assert_eq!(cz_simulation_write_field(sim, 0, buffer.as_ptr(), 121), CzStatus::Ok);
+        buffer[5 * 11 + 5] = 0.0;
+        assert_eq!(cz_simulation_read_field(sim, 0, buffer.as_mut_ptr(), 121), CzStatus::Ok);
+        assert_eq!(buffer[5 * 11 + 5], 0.25);
+        cz_simulation_free(sim);
+    }
+}
+
+// Derived fields can be read but not written; unknown ids and bad values are invalid.
+#[test]
+fn read_only_fields_and_bad_arguments_are_refused() {
+    let sim = cavity();
+    let mut buffer = vec![0.0; 121];
+    unsafe {
+        for id in [CzField::VelocityX, CzField::VelocityY, CzField::GasVorticity, CzField::GasStreamFunction] {
+            assert_eq!(cz_simulation_read_field(sim, id as u32, buffer.as_mut_ptr(), 121), CzStatus::Ok);
+            assert_eq!(cz_simulation_write_field(sim, id as u32, buffer.as_ptr(), 121), CzStatus::InvalidArgument, "{:?}", id);
+            assert_eq!(last_error(), format!("field {} is derived and read only", id as u32));
+        }
+        assert_eq!(cz_simulation_read_field(sim, 8, buffer.as_mut_ptr(), 121), CzStatus::InvalidArgument);
+        assert_eq!(last_error(), "unknown field 8");
+        assert_eq!(cz_simulation_read_wall(sim, 4, buffer.as_mut_ptr(), 11), CzStatus::InvalidArgument);
+
+        buffer[60] = f64::NAN;
+        assert_eq!(cz_simulation_write_field(sim, 0, buffer.as_ptr(), 121), CzStatus::InvalidArgument);
+        assert_eq!(cz_simulation_set_wall_temperature(sim, 0, 0.5, 0.2, 1.0), CzStatus::InvalidArgument);
+        cz_simulation_free(sim);
+    }
+}
+
+// A cavity needs a finite Ra >= 0, Pr > 0 and dt > 0; NaN is refused like a negative value.
+#[test]
+fn cavity_numbers_are_checked() {
+    for (ra, pr, dt) in [(f64::NAN, 0.71, 1e-4), (-1.0, 0.71, 1e-4), (1e3, 0.0, 1e-4), (1e3, f64::INFINITY, 1e-4), (1e3, 0.71, f64::NAN)] {
+        let mut sim = ptr::null_mut();
+        assert_eq!(unsafe { cz_simulation_new_cavity(11, ra, pr, dt, &mut sim) }, CzStatus::InvalidArgument, "Ra = {}, Pr = {}, dt = {}", ra, pr, dt);
+        assert!(sim.is_null());
+        assert!(last_error().starts_with("need finite Ra >= 0, Pr > 0 and dt > 0"), "{}", last_error());
+    }
+}
+
+// Parameters from JSON get the same checks: the width must be positive and nx must match
+// the domain, with its ghost columns when periodic.
+#[test]
+fn json_domains_are_checked() {
+    let json = |width: f64, periodic: bool, nx: usize| {
+        let mut params = serde_json::to_value(SimParameters::cavity(11, 0.71, 1e3)).unwrap();
+        params["domain"] = serde_json::json!({ "width": width, "periodic": periodic });
+        params["nx"] = nx.into();
+        CString::new(params.to_string()).unwrap()
+    };
+    let cases = [
+        (json(0.0, false, 11), "need a finite, positive domain width, got 0"),
+        (json(-2.0, false, 21), "need a finite, positive domain width, got -2"),
+        (json(2.0, false, 11), "a domain 2 wide with ny = 11 has nx = 21, got 11"),
+        (json(2.0, true, 21), "a domain 2 wide with ny = 11 has nx = 22 including the two ghost columns, got 21"),
+    ];
+    for (json, message) in cases {
+        let mut sim = ptr::null_mut();
+        assert_eq!(unsafe { cz_simulation_new(json.as_ptr(), &mut sim) }, CzStatus::InvalidArgument);
+        assert!(sim.is_null());
+        assert_eq!(last_error(), message);
+    }
+    let mut sim = ptr::null_mut();
+    assert_eq!(unsafe { cz_simulation_new(json(2.0, true, 22).as_ptr(), &mut sim) }, CzStatus::Ok);
+    unsafe { cz_simulation_free(sim) };
+}
+
+// The committed header is the one the build script generates from ffi.rs; refresh it with
+// `cbindgen --config cbindgen.toml --output include/cz_cfd_simulator.h`.
+#[test]
+fn committed_header_is_current() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/cz_cfd_simulator.h"));
+    let committed = include_str!("../include/cz_cfd_simulator.h");
+    assert!(generated == committed, "include/cz_cfd_simulator.h is out of date with src/ffi.rs");
+}
@@ -0,0 +1,116 @@
+use cz_cfd_simulator::gas::{GasFlow, GasNode, GasParameters};
+use cz_cfd_simulator::growth::GrowthParameters;
+use cz_cfd_simulator::regions::{Region, RegionLayout};
+use cz_cfd_simulator::simulation::{SimParameters, SimState, Simulation};
+use cz_cfd_simulator::watchdog::Watchdog;
+use ndarray::Array2;
+
+// 11 x 11 nodes at Δ = 0.1: melt up to y = 0.4, gas above it, and a crystal hanging from
+// the top in the middle column down to y = 0.7.
+fn furnace() -> Array2<Region> {
+    Array2::from_shape_fn((11, 11), |(i, j)| match (i, j) {
+        (0..=4, _) => Region::Melt,
+        (7.., 5) => Region::Crystal,
+        _ => Region::Gas,
+    })
+}
+
+fn gas_flow(inlet: &str, outlet: &str) -> GasFlow {
+    let params = GasParameters::new(vec![inlet.parse().unwrap()], vec![outlet.parse().unwrap()], 1.0, 0.0, 1.0).unwrap();
+    GasFlow::new(&params, &furnace(), (0.1, 0.1))
+}
+
+// Three inlet nodes on the left feed four outlet nodes on the right: the outlets run at
+// 3/4 of the inflow speed so that what comes in goes out, ψ rises by the inflow 0.3
+// across the outlets and falls by as much across the inlets, and so closes around the
+// frame. The crystal, joined to the top, and the melt, joined to the bottom, take the
+// frame's ψ there.
+#[test]
+fn gas_stream_function_is_single_valued() {
+    let flow = gas_flow("left:0.65:0.95", "right:0.55:0.95");
+    flow.check().unwrap();
+    assert_eq!((flow.nodes[[8, 0]], flow.nodes[[8, 10]], flow.nodes[[5, 3]]), (GasNode::Inlet, GasNode::Outlet, GasNode::Surface));
+    assert_eq!(flow.fastest_port(), 1.0);
+
+    let mut state = Simulation::new(SimParameters::cavity(11, 0.71, 1e3)).state;
+    flow.solve_stream_function(&mut state, 0);
+    flow.update_velocities(&mut state);
+    let psi = &state.gas_stream;
+
+    let inflow: f64 = (7..=9).map(|i| 0.1 * state.u[[i, 0]]).sum();
+    let outflow: f64 = (6..=9).map(|i| 0.1 * state.u[[i, 10]]).sum();
+    assert!((inflow - 0.3).abs() < 1e-12 && (outflow - inflow).abs() < 1e-12, "in {}, out {}", inflow, outflow);
+
+    // Below the ports both side walls are back at the bottom's ψ = 0
+    assert!(psi[[5, 0]].abs() < 1e-12 && psi[[6, 0]].abs() < 1e-12 && psi[[5, 10]].abs() < 1e-12);
+    for j in (0..11).filter(|&j| j != 5) {
+        assert!((psi[[10, j]] - 0.3).abs() < 1e-12, "top at column {}: {}", j, psi[[10, j]]);
+    }
+    assert_eq!(flow.nodes[[8, 4]], GasNode::Wall);
+    assert!((psi[[8, 4]] - 0.3).abs() < 1e-12 && (psi[[8, 6]] - 0.3).abs() < 1e-12);
+    assert!((1..10).all(|j| psi[[5, j]] == 0.0));
+}
+
+// An outlet over the melt is no outlet of the gas: the inflow has nowhere to go.
+#[test]
+fn inflow_without_an_outlet_is_rejected() {
+    let flow = gas_flow("left:0.65:0.95", "right:0.0:0.3");
+    assert_eq!(flow.unbalanced, 1);
+    assert!(flow.check().is_err());
+    assert_eq!(flow.fastest_port(), 0.0);
+}
+
+// A gas run keeps to half the stable step, which counts the inlet speed before the gas
+// has started to move. The parameters alone give the same step as the new simulation.
+#[test]
+fn gas_runs_lower_their_time_step() {
+    let gas = GasParameters::new(vec!["top:0.12:0.17".parse().unwrap()], vec!["top:0.22:0.27".parse().unwrap()], 200.0, 0.0, 10.0).unwrap();
+    let params = SimParameters { layout: Some(Default::default()), gas: Some(gas), ..SimParameters::cavity(21, 0.71, 1e3) };
+    let requested = params.dt;
+    let sim = Simulation::new(params.clone());
+    sim.check_geometry().unwrap();
+    assert_eq!(params.stable_time_step(), sim.stable_time_step());
+    let (dx, dy) = sim.params.spacing();
+    let inflow = sim.gas.as_ref().unwrap().fastest_port() * (1.0 / dx + 1.0 / dy);
+    assert!(inflow > 0.0);
+    assert!(sim.stable_time_step() <= 1.0 / inflow);
+    assert_eq!(sim.time_step(), requested.min(0.5 * sim.stable_time_step()));
+    assert!(sim.time_step() < requested);
+    assert_eq!(sim.params.dt, requested, "the requested step stays in the parameters");
+}
+
+// The outlet speed follows the shape of the gas region: the growing crystal reaches the
+// top wall and closes most of the outlet there, and the run lowers its step to the new
+// limit.
+#[test]
+fn gas_time_step_follows_the_growing_crystal() {
+    let gas = GasParameters::new(vec!["top:0.1:0.2".parse().unwrap()], vec!["top:0.25:0.4".parse().unwrap()], 200.0, 0.0, 10.0).unwrap();
+    let mut sim = Simulation::new(SimParameters {
+        layout: Some(RegionLayout { crystal_length: Some(0.1), ..RegionLayout::default() }),
+        growth: Some(GrowthParameters { pull_rate: 1e3, density_ratio: 0.91, log_interval: 1 }),
+        gas: Some(gas),
+        ..SimParameters::cavity(21, 0.71, 1e3)
+    });
+    let (dt, outlet_speed) = (sim.time_step(), sim.gas.as_ref().unwrap().fastest_port());
+    let reaches_top = |sim: &Simulation| sim.params.layout.as_ref().is_some_and(|l| l.melt_height + l.crystal_length.unwrap() >= 1.0);
+    for _ in 0..1000 {
+        if reaches_top(&sim) {
+            break;
+        }
+        sim.step();
+    }
+    assert!(reaches_top(&sim));
+    assert!(sim.gas.as_ref().unwrap().fastest_port() > outlet_speed);
+    assert!(sim.time_step() < dt);
+    assert!(sim.time_step() <= 0.5 * sim.params.stable_time_step());
+}
+
+// A diverging purge gas solve stops the run like the melt fields do, before it is stored.
+#[test]
+fn watchdog_checks_the_gas_fields() {
+    let mut watchdog = Watchdog::default();
+    let mut state = SimState::new(5, 5);
+    assert_eq!(watchdog.check(&state), None);
+    state.gas_stream[[2, 3]] = f64::NAN;
+    assert_eq!(watchdog.check(&state).as_deref(), Some("gas stream function is NaN at node (i = 2, j = 3), 1 non-finite values"));
+}
@@ -0,0 +1,76 @@
+use cz_cfd_simulator::mask::{Obstacle, Shape};
+use cz_cfd_simulator::regions::{FurnaceParts, Region, RegionLayout};
+use cz_cfd_simulator::simulation::{Domain, SimParameters, Simulation};
+
+// Columns of each region along one row of the region map.
+fn columns(sim: &Simulation, i: usize, region: Region) -> Vec<usize> {
+    sim.state.region.row(i).iter().enumerate().filter(|(_, r)| **r == region).map(|(j, _)| j).collect()
+}
+
+// On a domain two heights wide the walls keep their thickness and the crystal its width,
+// centred at x = 1, instead of both stretching with the width.
+#[test]
+fn layout_keeps_its_size_on_a_wide_domain() {
+    // Sizes between grid nodes, so rounding cannot tip a node either way
+    let layout = RegionLayout { susceptor_thickness: 0.06, crystal_width: 0.43, ..RegionLayout::default() };
+    let domain = Domain { width: 2.0, periodic: false };
+    let sim = Simulation::new(SimParameters { layout: Some(layout.clone()), ..SimParameters::on_domain(41, 0.71, 1e3, domain) });
+    let (dx, dy) = sim.params.spacing();
+    assert_eq!(dx, dy);
+
+    // Susceptor nodes at x < 0.06 on either side of a row through the melt
+    let susceptor = columns(&sim, 20, Region::Susceptor);
+    assert_eq!(susceptor, vec![0, 1, 2, 78, 79, 80]);
+
+    // Crystal nodes with |x − 1| ≤ 0.215 in a row above the melt
+    let crystal = columns(&sim, 38, Region::Crystal);
+    assert_eq!((crystal[0], *crystal.last().unwrap()), (32, 48));
+    assert!((layout.crucible_width(domain.width) - 1.78).abs() < 1e-12);
+}
+
+// With periodic x the column at x = 0 is the one after the ghost column, so an obstacle is
+// painted at the same physical place as without the ghost columns, and the ghost columns
+// copy the interior ones.
+#[test]
+fn periodic_obstacles_skip_the_ghost_column() {
+    let domain = Domain { width: 2.0, periodic: true };
+    let obstacle = Obstacle { shape: Shape::Rect { x0: 0.0, y0: 0.0, x1: 0.25, y1: 0.5 }, region: Region::Crucible };
+    let sim = Simulation::new(SimParameters { obstacles: vec![obstacle], ..SimParameters::on_domain(9, 0.71, 1e3, domain) });
+    let nx = sim.params.nx;
+    assert_eq!(nx, 18);
+    // x = 0, 0.125 and 0.25 at columns 1 to 3, and the ghost copy of column 1
+    assert_eq!(columns(&sim, 2, Region::Crucible), vec![1, 2, 3, nx - 1]);
+    assert_eq!(columns(&sim, 6, Region::Crucible), Vec::<usize>::new());
+}
+
+// The curved bottom spans the whole width: its lowest point is at the centre.
+#[test]
+fn curved_bottom_spans_the_domain_width() {
+    let bowl = Shape::CurvedBottom { depth: 0.2 };
+    assert!(!bowl.contains(1.0, 0.01, 2.0));
+    assert!(bowl.contains(0.05, 0.1, 2.0));
+    assert!(bowl.contains(1.95, 0.1, 2.0));
+    assert!(!bowl.contains(0.5, 0.1, 2.0));
+}
+
+// The dipped crystal end and the heat shields follow the layout's crystal and melt level
+// and stay centred on a wide domain.
+#[test]
+fn furnace_parts_follow_the_layout() {
+    let layout = RegionLayout { melt_height: 0.6, crystal_width: 0.3, ..RegionLayout::default() };
+    let parts = FurnaceParts { immersed_crystal: Some(0.1), heat_shield: true, ..FurnaceParts::default() };
+    let shapes: Vec<Shape> = parts.obstacles(Some(&layout), 2.0).into_iter().map(|o| o.shape).collect();
+    let expected = [
+        Shape::Rect { x0: 0.85, y0: 0.5, x1: 1.15, y1: 1.0 },
+        Shape::Rect { x0: 0.7, y0: 0.65, x1: 0.8, y1: 1.0 },
+        Shape::Rect { x0: 1.2, y0: 0.65, x1: 1.3, y1: 1.0 },
+    ];
+    assert_eq!(shapes.len(), expected.len());
+    for (shape, expected) in shapes.iter().zip(&expected) {
+        let (Shape::Rect { x0, y0, x1, y1 }, Shape::Rect { x0: a0, y0: b0, x1: a1, y1: b1 }) = (shape, expected) else {
+            panic!("{:?} is not a rectangle", shape);
+        };
+        assert!([x0 - a0, y0 - b0, x1 - a1, y1 - b1].iter().all(|d| d.abs() < 1e-12), "{:?} instead of {:?}", shape, expected);
+    }
+    assert!(FurnaceParts::default().obstacles(Some(&layout), 2.0).is_empty());
+}
@@ -0,0 +1,49 @@
+use cz_cfd_simulator::growth::{Growth, GrowthParameters};
+use cz_cfd_simulator::regions::{Region, RegionLayout};
+use cz_cfd_simulator::simulation::{SimParameters, Simulation};
+
+// Mass conservation: per unit of pulled length the melt level falls by
+// (ρ_s/ρ_l) w_crystal / w_crucible, in square and wide crucibles alike.
+#[test]
+fn melt_level_falls_with_the_pulled_length() {
+    let params = GrowthParameters { pull_rate: 0.5, density_ratio: 0.91, log_interval: 1 };
+    for width in [1.0, 2.0] {
+        let mut layout = RegionLayout::default();
+        let mut growth = Growth::new(params, &layout);
+        let (length, height) = (growth.crystal_length, layout.melt_height);
+        assert!(growth.advance(&mut layout, 0.1, width));
+
+        let pulled = growth.crystal_length - length;
+        let fall = height - layout.melt_height;
+        assert!((pulled - 0.05).abs() < 1e-15);
+        let expected = params.density_ratio * layout.crystal_width / layout.crucible_width(width);
+        assert!((fall / pulled - expected).abs() < 1e-12, "width {}: {} per unit pulled, expected {}", width, fall / pulled, expected);
+        assert_eq!(layout.crystal_length, Some(growth.crystal_length));
+    }
+}
+
+// A short seed crystal rises with the pull: the crystal nodes of the centre column reach
+// from the melt surface to the surface plus the logged crystal length.
+#[test]
+fn crystal_region_follows_the_pulled_length() {
+    let layout = RegionLayout { crystal_length: Some(0.1), ..RegionLayout::default() };
+    let mut sim = Simulation::new(SimParameters {
+        layout: Some(layout),
+        growth: Some(GrowthParameters { pull_rate: 500.0, density_ratio: 0.91, log_interval: 1 }),
+        ..SimParameters::cavity(41, 0.01, 1e3)
+    });
+    for _ in 0..3 {
+        sim.step();
+    }
+    let growth = sim.growth.as_ref().unwrap();
+    let record = growth.history.last().unwrap();
+    assert!((record.crystal_length - 0.25).abs() < 1e-12);
+
+    let dy = sim.params.spacing().1;
+    let centre = sim.params.nx / 2;
+    let crystal: Vec<usize> = (0..sim.params.ny).filter(|&i| sim.state.region[[i, centre]] == Region::Crystal).collect();
+    let (bottom, top) = (record.melt_height, record.melt_height + record.crystal_length);
+    assert_eq!(crystal.first().copied(), Some((bottom / dy).floor() as usize + 1));
+    assert_eq!(crystal.last().copied(), Some((top / dy).floor() as usize));
+    assert_eq!(sim.state.region[[crystal.last().unwrap() + 1, centre]], Region::Gas);
+}
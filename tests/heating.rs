@@ -0,0 +1,20 @@
+use cz_cfd_simulator::heating::HeatSource;
+use cz_cfd_simulator::regions::Region;
+use cz_cfd_simulator::simulation::Domain;
+use ndarray::Array2;
+
+// A Gaussian source centred at x = 1.5 of a periodic cell two heights wide peaks on the
+// interior column at that x, and the ghost columns copy their interior partners.
+#[test]
+fn sources_are_placed_in_domain_coordinates() {
+    let domain = Domain { width: 2.0, periodic: true };
+    let (ny, nx) = (11, domain.nx(11));
+    let (dx, _) = domain.spacing(nx, ny);
+    let source: HeatSource = "gauss:1.5,0.5,0.2:1".parse().unwrap();
+    let field = source.field(&Array2::from_elem((ny, nx), Region::Melt), &domain);
+
+    let (peak, _) = field.indexed_iter().fold(((0, 0), 0.0), |best, (ij, v)| if *v > best.1 { (ij, *v) } else { best });
+    assert_eq!(peak, (5, 1 + (1.5 / dx).round() as usize));
+    assert_eq!(field.column(0), field.column(nx - 2));
+    assert_eq!(field.column(nx - 1), field.column(1));
+}
@@ -0,0 +1,49 @@
+use cz_cfd_simulator::mask::{classify, floating_solids, CellType, Obstacle};
+use cz_cfd_simulator::regions::Region;
+use cz_cfd_simulator::simulation::Domain;
+use ndarray::Array2;
+
+// Region map of an 11 x 11 melt with the given obstacles painted over it.
+fn painted(obstacles: &[&str]) -> Array2<Region> {
+    let mut region = Array2::from_elem((11, 11), Region::Melt);
+    for obstacle in obstacles {
+        obstacle.parse::<Obstacle>().unwrap().paint(&mut region, &Domain::default());
+    }
+    region
+}
+
+// A block over the nodes with x ≤ 0.5 and y ≤ 0.3 at Δ = 0.1, its edges between nodes:
+// its nodes next to the melt are boundary nodes, the ones inside it only conduct and the
+// melt around it flows.
+#[test]
+fn painted_block_is_classified_around_its_edge() {
+    let region = painted(&["rect:0,0,0.55,0.35:crucible"]);
+    assert_eq!(region[[3, 5]], Region::Crucible);
+    assert_eq!(region[[4, 5]], Region::Melt);
+    assert_eq!(region[[3, 6]], Region::Melt);
+
+    let mask = classify(&region, false);
+    for node in [[3, 1], [3, 5], [1, 5], [3, 3]] {
+        assert_eq!(mask[node], CellType::Boundary, "{:?}", node);
+    }
+    for node in [[1, 1], [2, 3], [0, 0], [0, 4]] {
+        assert_eq!(mask[node], CellType::Solid, "{:?}", node);
+    }
+    for node in [[4, 5], [3, 6], [5, 5], [1, 6]] {
+        assert_eq!(mask[node], CellType::Fluid, "{:?}", node);
+    }
+    // The outer frame is never fluid, only a wall next to the melt
+    assert_eq!(mask[[0, 8]], CellType::Boundary);
+    assert!(floating_solids(&mask, false).is_empty());
+}
+
+// A disk in the middle of the melt touches no wall, so it is reported as floating.
+#[test]
+fn obstacle_away_from_the_walls_floats() {
+    let mask = classify(&painted(&["disk:0.5,0.5,0.15:crystal"]), false);
+    assert_eq!(mask[[5, 5]], CellType::Solid);
+    assert_eq!(mask[[5, 4]], CellType::Boundary);
+    let floating = floating_solids(&mask, false);
+    assert!(floating.contains(&(5, 5)) && floating.contains(&(5, 4)), "{:?}", floating);
+    assert!(floating.iter().all(|&(i, j)| (4..=6).contains(&i) && (4..=6).contains(&j)));
+}
@@ -0,0 +1,42 @@
+use cz_cfd_simulator::materials::{DimensionlessGroups, MaterialProperties, ProcessInputs};
+
+fn process(crucible_radius: f64) -> ProcessInputs {
+    ProcessInputs {
+        crucible_radius,
+        melt_height: 0.05,
+        crystal_radius: 0.05,
+        delta_t: 10.0,
+        crystal_rotation: 0.0,
+        crucible_rotation: 0.0,
+        magnetic_field: 0.0,
+        gravity: 9.81,
+    }
+}
+
+fn close(value: f64, expected: f64) -> bool {
+    (value / expected - 1.0).abs() < 1e-6
+}
+
+// Silicon (ρ 2570, μ 8.6e-4, k 64, c_p 1000, β 1.4e-4, dσ/dT −2.8e-4) in a melt 5 cm deep
+// with ΔT = 10 K, worked out by hand:
+//   Pr = μ c_p / k                      = 0.86 / 64                      = 0.0134375
+//   Gr = g β ΔT H³ ρ² / μ²              = 1.71675e-6 · 2570² / 8.6e-4²   = 1.53312e7
+//   Ra = Gr Pr                                                          = 2.06013e5
+//   Ma = |dσ/dT| ΔT H ρ c_p / (μ k)     = 1.4e-4 · 2.57e6 / 0.05504      = 6537.06
+#[test]
+fn silicon_groups_match_the_hand_values() {
+    let silicon = MaterialProperties::builtin("silicon").unwrap();
+    let groups = DimensionlessGroups::new(&silicon, &process(0.1));
+    assert!(close(groups.prandtl, 0.0134375), "Pr = {}", groups.prandtl);
+    assert!(close(groups.grashof, 1.533121e7), "Gr = {}", groups.grashof);
+    assert!(close(groups.rayleigh, 2.060131e5), "Ra = {}", groups.rayleigh);
+    assert!(close(groups.marangoni, 6537.064), "Ma = {}", groups.marangoni);
+}
+
+// Ra and Ma are on the melt height: a wider crucible leaves them alone.
+#[test]
+fn buoyancy_and_surface_groups_ignore_the_crucible_radius() {
+    let silicon = MaterialProperties::builtin("silicon").unwrap();
+    let (narrow, wide) = (DimensionlessGroups::new(&silicon, &process(0.1)), DimensionlessGroups::new(&silicon, &process(0.3)));
+    assert_eq!((narrow.rayleigh, narrow.marangoni), (wide.rayleigh, wide.marangoni));
+}
@@ -0,0 +1,50 @@
+use cz_cfd_simulator::perturbation::Perturbation;
+use cz_cfd_simulator::simulation::{SimParameters, Simulation};
+use ndarray::Array2;
+
+fn perturbed(spec: &str) -> Array2<f64> {
+    let mut sim = Simulation::new(SimParameters::cavity(21, 0.71, 1e4));
+    sim.perturb(&spec.parse::<Perturbation>().unwrap());
+    sim.state.temp
+}
+
+// The same seed gives the same field, bit for bit, and another seed a different one.
+#[test]
+fn perturbations_are_reproducible_per_seed() {
+    for (spec, other) in [
+        ("temp:random:amp=0.01,seed=42", "temp:random:amp=0.01,seed=43"),
+        ("temp:blobs:amp=0.05,count=3,radius=0.1,seed=7", "temp:blobs:amp=0.05,count=3,radius=0.1,seed=8"),
+    ] {
+        let first = perturbed(spec);
+        assert_eq!(first, perturbed(spec), "{}", spec);
+        assert_ne!(first, perturbed(other), "{} and {}", spec, other);
+    }
+}
+
+// Random noise stays within its amplitude and leaves the walls at their imposed values.
+#[test]
+fn random_noise_is_bounded_and_leaves_the_walls() {
+    let base = Simulation::new(SimParameters::cavity(21, 0.71, 1e4));
+    let noisy = perturbed("temp:random:amp=0.01,seed=42");
+    let (ny, nx) = noisy.dim();
+    let mut changed = 0;
+    for ((i, j), t) in noisy.indexed_iter() {
+        let delta = t - base.state.temp[[i, j]];
+        if base.state.is_fluid(i, j) {
+            assert!(delta.abs() <= 0.01, "{} at ({}, {})", delta, i, j);
+            changed += usize::from(delta != 0.0);
+        } else if i == 0 || i == ny - 1 || j == 0 || j == nx - 1 {
+            assert_eq!(delta, 0.0, "wall node ({}, {})", i, j);
+        }
+    }
+    assert_eq!(changed, (ny - 2) * (nx - 2));
+}
+
+// The written form parses back to the same perturbation, so a stored run can be redone.
+#[test]
+fn written_perturbations_parse_back() {
+    for spec in ["temp:random:amp=0.01,seed=42", "vort:fourier:amp=5,kx=2,ky=1", "conc:blobs:amp=0.05,count=3,radius=0.1,seed=7"] {
+        let perturbation: Perturbation = spec.parse().unwrap();
+        assert_eq!(perturbation.to_string().parse::<Perturbation>().unwrap(), perturbation, "{}", spec);
+    }
+}
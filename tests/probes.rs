@@ -0,0 +1,88 @@
+use cz_cfd_simulator::probes::{bilinear, write_csv, MonitorLine, Probe, ProbeSet};
+use cz_cfd_simulator::regions::RegionLayout;
+use cz_cfd_simulator::simulation::{Domain, SimParameters, Simulation};
+use ndarray::Array2;
+
+// f = 1 + 3x − 2y + xy at the nodes of a grid over `domain`, where bilinear interpolation
+// is exact. With periodic x the ghost columns hold x = −Δx and x = W.
+fn bilinear_field(ny: usize, nx: usize, domain: &Domain) -> Array2<f64> {
+    let (dx, dy) = domain.spacing(nx, ny);
+    let first = domain.first_column() as f64;
+    Array2::from_shape_fn((ny, nx), |(i, j)| {
+        let (x, y) = ((j as f64 - first) * dx, i as f64 * dy);
+        1.0 + 3.0 * x - 2.0 * y + x * y
+    })
+}
+
+// Between nodes and on the walls, on square, wide and periodic domains, the probes read
+// the field at the point (x, y) of the domain.
+#[test]
+fn interpolation_is_exact_for_bilinear_fields() {
+    let domains = [Domain::default(), Domain { width: 2.0, periodic: false }, Domain { width: 2.0, periodic: true }];
+    for domain in domains {
+        let (ny, nx) = (11, domain.nx(11));
+        let field = bilinear_field(ny, nx, &domain);
+        for (s, y) in [(0.0, 0.0), (0.37, 0.61), (0.5, 0.5), (0.93, 0.08), (1.0, 1.0)] {
+            let x = s * domain.width;
+            let exact = 1.0 + 3.0 * x - 2.0 * y + x * y;
+            let value = bilinear(&field, &domain, x, y);
+            assert!((value - exact).abs() < 1e-12, "{:?} at ({}, {}): {} instead of {}", domain, x, y, value, exact);
+        }
+    }
+}
+
+// A probe set samples every probe and every point of its lines, named after the line.
+// On a domain two heights wide the preset lines span the width.
+#[test]
+fn probe_sets_sample_points_and_lines() {
+    let domain = Domain { width: 2.0, periodic: false };
+    let sim = Simulation::new(SimParameters::on_domain(11, 0.71, 1e3, domain));
+    let probe: Probe = "tc:1.5,0.25".parse().unwrap();
+    let lines: Vec<MonitorLine> = ["mid:0,0.5:1,0.5:3", "vertical-centerline", "surface:0.9"].iter().map(|l| l.parse().unwrap()).collect();
+    let mut set = ProbeSet::new(1, &[probe], &lines, &sim.params).unwrap();
+    set.sample(&sim.state, &domain, 0, 0.0);
+    let names: Vec<&str> = set.history.iter().take(5).map(|s| s.probe.as_str()).collect();
+    assert_eq!(names, ["tc", "mid[0]", "mid[1]", "mid[2]", "vertical-centerline[0]"]);
+    assert_eq!((set.history[2].x, set.history[2].y), (0.5, 0.5));
+    assert_eq!((set.history[4].x, set.history[44].x), (1.0, 1.0));
+    let surface = set.history.last().unwrap();
+    assert_eq!((surface.x, surface.y), (2.0, 0.9));
+    assert_eq!(set.history[0].temp, bilinear(&sim.state.temp, &domain, 1.5, 0.25));
+
+    // Past the right wall of a unit square
+    let error = ProbeSet::new(1, &["tc:1.5,0.25".parse().unwrap()], &[], &SimParameters::cavity(11, 0.71, 1e3)).unwrap_err();
+    assert!(error.to_string().contains("outside the domain"), "{}", error);
+}
+
+// The `surface` preset runs at the melt surface of the layout, not along the top wall,
+// and needs the height when there is no layout.
+#[test]
+fn surface_line_follows_the_melt_surface() {
+    let line: MonitorLine = "surface".parse().unwrap();
+    let params = SimParameters { layout: Some(RegionLayout::default()), ..SimParameters::cavity(11, 0.71, 1e3) };
+    let set = ProbeSet::new(1, &[], std::slice::from_ref(&line), &params).unwrap();
+    assert!(set.probes.iter().all(|p| p.y == RegionLayout::default().melt_height));
+    assert!(RegionLayout::default().melt_height < 1.0);
+
+    let error = ProbeSet::new(1, &[], &[line], &SimParameters::cavity(11, 0.71, 1e3)).unwrap_err();
+    assert!(error.to_string().contains("surface:<height>"), "{}", error);
+}
+
+// Names with commas or quotes are quoted, so every row keeps its nine columns.
+#[test]
+fn csv_quotes_probe_names() {
+    let sim = Simulation::new(SimParameters::cavity(11, 0.71, 1e3));
+    let probes = [Probe { name: "tc \"a\", bottom".to_string(), x: 0.5, y: 0.1 }, Probe { name: "plain".to_string(), x: 0.5, y: 0.9 }];
+    let mut set = ProbeSet::new(1, &probes, &[], &sim.params).unwrap();
+    set.sample(&sim.state, &sim.params.domain, 0, 0.0);
+    let path = std::env::temp_dir().join(format!("cz_probes_{}.csv", std::process::id()));
+    write_csv(&set.history, &path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let rows: Vec<&str> = text.lines().collect();
+    assert!(rows[1].starts_with("\"tc \"\"a\"\", bottom\",0,"), "{}", rows[1]);
+    assert!(rows[2].starts_with("plain,0,"), "{}", rows[2]);
+    let unquoted = rows[1].rsplit_once("\",").unwrap().1;
+    assert_eq!(unquoted.split(',').count(), 8);
+}
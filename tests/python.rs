@@ -0,0 +1,102 @@
+#![cfg(feature = "python")]
+
+// The Rust side of the Python classes without an interpreter:
+// `cargo test --features python --test python`. The NumPy copies are not covered.
+
+use cz_cfd_simulator::python::{cavity_parameters, mask_ids, region_ids, run_steps, set_checked};
+use cz_cfd_simulator::regions::{Region, RegionLayout};
+use cz_cfd_simulator::simulation::{Domain, SimParameters, Simulation};
+use cz_cfd_simulator::Solver;
+
+// The Python defaults give the CLI's default cavity.
+#[test]
+fn defaults_are_the_plain_cavity() {
+    let params = cavity_parameters(41, 1e4, 0.71, 1e-4, 1.0, false, false).unwrap();
+    let cavity = SimParameters::cavity(41, 0.71, 1e4);
+    assert_eq!(serde_json::to_string(&params).unwrap(), serde_json::to_string(&cavity).unwrap());
+}
+
+// Width, periodicity and the furnace regions reach the parameters, and the result runs.
+#[test]
+fn options_shape_the_domain() {
+    let params = cavity_parameters(11, 1e3, 0.01, 2e-5, 2.0, true, true).unwrap();
+    let domain = Domain { width: 2.0, periodic: true };
+    assert_eq!((params.nx, params.ny, params.dt), (domain.nx(11), 11, 2e-5));
+    assert_eq!((params.domain.width, params.domain.periodic), (2.0, true));
+    assert_eq!(params.layout, Some(RegionLayout::default()));
+    let mut sim = Simulation::new(params);
+    assert!(sim.advance_watched(5).is_none());
+}
+
+#[test]
+fn degenerate_grids_are_refused() {
+    for (grid_size, width) in [(2, 1.0), (11, 0.0), (11, -1.0)] {
+        let error = cavity_parameters(grid_size, 1e3, 0.71, 1e-4, width, false, false).unwrap_err();
+        assert_eq!(error.to_string(), "need grid_size >= 3 and a positive width");
+    }
+}
+
+// Ra, Pr and dt get the checks of the C interface, in the constructor and the setters; a
+// refused change leaves the parameters as they were.
+#[test]
+fn numbers_are_checked() {
+    for (ra, pr, dt) in [(f64::NAN, 0.71, 1e-4), (1e3, 0.0, 1e-4), (1e3, 0.71, -1e-4)] {
+        let error = cavity_parameters(11, ra, pr, dt, 1.0, false, false).unwrap_err();
+        assert!(error.to_string().starts_with("need finite Ra >= 0, Pr > 0 and dt > 0"), "{}", error);
+    }
+    let mut params = cavity_parameters(11, 1e3, 0.71, 1e-4, 1.0, false, false).unwrap();
+    assert!(set_checked(&mut params, |p| p.dt = 0.0).is_err());
+    assert!(set_checked(&mut params, |p| p.pr = f64::NAN).is_err());
+    assert!(set_checked(&mut params, |p| p.ra = -1.0).is_err());
+    assert_eq!((params.ra, params.pr, params.dt), (1e3, 0.71, 1e-4));
+    set_checked(&mut params, |p| p.ra = 2e3).unwrap();
+    assert_eq!(params.ra, 2e3);
+}
+
+// `Simulation.run` completes a stable run and raises on a blow-up.
+#[test]
+fn runs_raise_on_a_blow_up() {
+    let mut sim = Simulation::new(cavity_parameters(11, 1e3, 0.71, 1e-4, 1.0, false, false).unwrap());
+    run_steps(&mut sim, 10).unwrap();
+    assert_eq!(sim.steps_taken, 10);
+
+    let mut sim = Simulation::new(cavity_parameters(11, 1e6, 0.71, 1.0, 1.0, false, false).unwrap());
+    let error = run_steps(&mut sim, 200).unwrap_err();
+    assert!(error.to_string().starts_with("blew up at step"), "{}", error);
+}
+
+// `SimState.region` and `SimState.mask` use the ids the database stores.
+#[test]
+fn region_and_mask_ids() {
+    let sim = Simulation::new(cavity_parameters(21, 1e3, 0.01, 2e-5, 1.0, false, true).unwrap());
+    let (regions, mask) = (region_ids(&sim.state), mask_ids(&sim.state));
+    assert_eq!((regions.dim(), mask.dim()), (sim.state.temp.dim(), sim.state.temp.dim()));
+    for ((n, region), cell) in sim.state.region.indexed_iter().zip(sim.state.mask.iter()) {
+        assert_eq!(regions[n], region.id());
+        assert_eq!(mask[n], cell.id());
+    }
+    assert!(regions.iter().any(|&id| id == Region::Melt.id()) && regions.iter().any(|&id| id == Region::Crystal.id()));
+    assert!(mask.iter().any(|&id| id == 0) && mask.iter().any(|&id| id == 2));
+}
+
+// `Database.results` gives back the fields of a run saved by the CLI. Needs DATABASE_URL
+// pointing at a migrated database: `cargo test --features python --test python -- --ignored`.
+#[cfg(feature = "db")]
+#[test]
+#[ignore]
+fn stored_runs_load_their_fields() {
+    use cz_cfd_simulator::db;
+
+    let pool = db::establish_connection_pool().unwrap();
+    let mut sim = Simulation::new(cavity_parameters(21, 1e3, 0.01, 2e-5, 1.0, false, true).unwrap());
+    run_steps(&mut sim, 20).unwrap();
+    let run = db::create_simulation_run(&pool, "python fields", 21, 20, 0.01, 1e3, None).unwrap();
+    db::save_simulation_results(&pool, run.id, &sim.state).unwrap();
+
+    let stored = db::get_simulation_results(&pool, run.id).unwrap();
+    assert_eq!(stored.temp, sim.state.temp);
+    assert_eq!(stored.stream, sim.state.stream);
+    assert_eq!(region_ids(&stored), region_ids(&sim.state));
+    assert_eq!(mask_ids(&stored), mask_ids(&sim.state));
+    assert!(db::load_simulation_runs(&pool).unwrap().iter().any(|r| r.id == run.id));
+}
@@ -0,0 +1,141 @@
+use ndarray::Array2;
+
+use cz_cfd_simulator::boundary::{BoundarySegment, SurfaceEmission, ThermalBc, ThermalBoundary, Wall};
+use cz_cfd_simulator::radiation::RadiationModel;
+use cz_cfd_simulator::regions::Region;
+use cz_cfd_simulator::simulation::{Domain, SimParameters};
+
+const RAD: ThermalBc = ThermalBc::Radiative { emissivity: 0.6, t_env: 0.0 };
+
+// Radiating walls of the gas space above a melt pool whose surface sits between rows 10
+// and 11 of a 21 × 21 grid: the top wall and the side walls above y = 0.52. With the melt
+// surface they close the gas space.
+fn gas_space(axisymmetric: bool) -> (ThermalBoundary, Array2<Region>) {
+    let mut boundary = ThermalBoundary::default();
+    boundary.segments.push(BoundarySegment::whole(Wall::Top, RAD));
+    boundary.segments.push(BoundarySegment { wall: Wall::Left, start: 0.52, end: 1.0, bc: RAD });
+    boundary.segments.push(BoundarySegment { wall: Wall::Right, start: 0.52, end: 1.0, bc: RAD });
+    boundary.radiation.surface_to_surface = true;
+    boundary.radiation.axisymmetric = axisymmetric;
+    boundary.radiation.melt_surface = Some(SurfaceEmission { emissivity: 0.3, t_env: 0.0 });
+    let region = Array2::from_shape_fn((21, 21), |(i, _)| if i > 10 { Region::Gas } else { Region::Melt });
+    (boundary, region)
+}
+
+fn row_sums(model: &RadiationModel) -> Vec<f64> {
+    model.view_factors.rows().into_iter().map(|row| row.sum()).collect()
+}
+
+// Every element of a closed planar enclosure sees all of it: the crossed-strings factors of
+// each row add up to one, and A_a F_ab = A_b F_ba.
+#[test]
+fn planar_enclosure_view_factors_sum_to_one() {
+    let (boundary, region) = gas_space(false);
+    let model = RadiationModel::new(&boundary, &Domain::default(), &region);
+    // 19 top and 2 × 9 side wall elements, then 19 surface faces
+    assert_eq!(model.areas.len(), 56);
+    for (a, sum) in row_sums(&model).iter().enumerate() {
+        assert!((sum - 1.0).abs() < 1e-12, "element {} sees {} of the enclosure", a, sum);
+    }
+    for ((a, b), f) in model.view_factors.indexed_iter() {
+        let reverse = model.areas[b] * model.view_factors[[b, a]];
+        assert!((model.areas[a] * f - reverse).abs() < 1e-12);
+    }
+}
+
+// The melt is opaque: with the whole frame radiating, the walls next to the melt see
+// nothing, and neither do the bottom and the surface see each other through it.
+#[test]
+fn melt_blocks_radiation() {
+    let (mut boundary, region) = gas_space(false);
+    boundary.segments = [Wall::Bottom, Wall::Top, Wall::Left, Wall::Right].map(|wall| BoundarySegment::whole(wall, RAD)).to_vec();
+    let model = RadiationModel::new(&boundary, &Domain::default(), &region);
+    let sums = row_sums(&model);
+    // Bottom and top wall nodes alternate, then left and right ones row by row
+    for k in 0..19 {
+        assert_eq!(sums[2 * k], 0.0, "bottom element {} sees through the melt", k);
+        assert!((sums[2 * k + 1] - 1.0).abs() < 1e-12);
+    }
+    for i in 1..20 {
+        let (left, right) = (sums[38 + 2 * (i - 1)], sums[39 + 2 * (i - 1)]);
+        let expected = if i > 10 { 1.0 } else { 0.0 };
+        assert!((left - expected).abs() < 1e-12 && (right - expected).abs() < 1e-12, "side elements of row {} see {} and {}", i, left, right);
+    }
+}
+
+// Net radiation over a closed enclosure balances: whatever temperatures the walls and the
+// surface are at, the element emissions Σ A ε (E_b − G) cancel.
+#[test]
+fn enclosure_exchanges_no_net_energy() {
+    let (boundary, region) = gas_space(false);
+    let model = RadiationModel::new(&boundary, &Domain::default(), &region);
+    let temp = Array2::from_shape_fn((21, 21), |(i, j)| 1.0 - 0.04 * i as f64 + 0.3 * (0.3 * j as f64).sin());
+    let net = model.net_emission(&temp, &boundary.radiation);
+    let total: f64 = net.iter().zip(&model.areas).map(|(q, area)| q * area).sum();
+    let emitted: f64 = net.iter().zip(&model.areas).map(|(q, area)| q.abs() * area).sum();
+    assert!(emitted > 0.1);
+    assert!(total.abs() < 1e-8 * emitted, "{} left the enclosure out of {}", total, emitted);
+
+    // The surface faces hand their loss to the melt nodes under them
+    let heat = model.surface_heat(&temp, &boundary.radiation).unwrap();
+    let dx = 0.05;
+    let surface: f64 = net[37..].iter().zip(&model.areas[37..]).map(|(q, area)| q * area).sum();
+    assert!((heat.sum() * dx * dx + surface).abs() < 1e-10);
+    assert!(heat.indexed_iter().all(|((i, _), q)| i == 10 || *q == 0.0));
+}
+
+// Rings about the centre line close the same gas space: the numerically integrated factors
+// add up to one and stay reciprocal, and mirror elements see the same.
+#[test]
+fn ring_view_factors_sum_to_one() {
+    let (boundary, region) = gas_space(true);
+    let model = RadiationModel::new(&boundary, &Domain::default(), &region);
+    for (a, sum) in row_sums(&model).iter().enumerate() {
+        assert!((sum - 1.0).abs() < 0.02, "element {} sees {} of the enclosure", a, sum);
+    }
+    for ((a, b), f) in model.view_factors.indexed_iter() {
+        let reverse = model.areas[b] * model.view_factors[[b, a]];
+        assert!((model.areas[a] * f - reverse).abs() < 1e-12 * model.areas[a].max(model.areas[b]));
+    }
+    // The top wall's centre element is a disk, its neighbours halves of a ring
+    assert!((model.areas[9] - std::f64::consts::PI * 0.025f64.powi(2)).abs() < 1e-12);
+    assert!((model.view_factors.row(3).sum() - model.view_factors.row(15).sum()).abs() < 1e-12);
+}
+
+// Segments lie within their wall and emissivities within [0, 1], like the gas ports.
+#[test]
+fn segments_and_emissivities_are_range_checked() {
+    let segment: BoundarySegment = "top:0.3:1.0:rad=0.3,0.0".parse().unwrap();
+    assert_eq!(segment, BoundarySegment { wall: Wall::Top, start: 0.3, end: 1.0, bc: ThermalBc::Radiative { emissivity: 0.3, t_env: 0.0 } });
+    for spec in ["top:0.5:0.5:adiabatic", "top:0.6:0.4:adiabatic", "top:-0.1:0.5:adiabatic", "top:0.0:1.2:adiabatic", "top:NaN:1.0:adiabatic"] {
+        let error = spec.parse::<BoundarySegment>().unwrap_err();
+        assert_eq!(error.to_string(), format!("invalid segment '{}'", spec));
+        assert!(error.root_cause().to_string().ends_with("must satisfy 0 <= start < end <= 1"), "{:#}", error);
+    }
+    for spec in ["top:0.0:1.0:rad=1.5,0.0", "top:0.0:1.0:rad=-0.1,0.0", "top:0.0:1.0:rad=NaN,0.0", "top:0.0:1.0:rad=0.5,inf"] {
+        assert!(spec.parse::<BoundarySegment>().is_err(), "{}", spec);
+    }
+    assert_eq!("1,0.5".parse::<SurfaceEmission>().unwrap(), SurfaceEmission { emissivity: 1.0, t_env: 0.5 });
+    assert!("1.01,0.5".parse::<SurfaceEmission>().is_err());
+}
+
+// Parameters read as JSON skip `FromStr`, so validation checks the segments and the melt
+// surface emission again.
+#[test]
+fn deserialized_boundaries_are_range_checked() {
+    let mut params = SimParameters::cavity(21, 0.71, 1e4);
+    params.validate().unwrap();
+    let json = serde_json::to_string(&params).unwrap();
+    let bad_segment = json.replacen("\"end\":1.0", "\"end\":3.0", 1);
+    assert_ne!(bad_segment, json);
+    let error = serde_json::from_str::<SimParameters>(&bad_segment).unwrap().validate().unwrap_err();
+    assert!(error.to_string().ends_with("must satisfy 0 <= start < end <= 1"), "{}", error);
+
+    params.thermal.segments.push(BoundarySegment::whole(Wall::Top, ThermalBc::Radiative { emissivity: 7.0, t_env: 0.0 }));
+    let bad_wall = serde_json::from_str::<SimParameters>(&serde_json::to_string(&params).unwrap()).unwrap();
+    assert!(bad_wall.validate().unwrap_err().to_string().starts_with("emissivity must satisfy"));
+
+    params.thermal.segments.pop();
+    params.thermal.radiation.melt_surface = Some(SurfaceEmission { emissivity: 0.5, t_env: f64::INFINITY });
+    assert!(params.validate().is_err());
+}
@@ -0,0 +1,50 @@
+use cz_cfd_simulator::boundary::{BoundarySegment, ThermalBc, ThermalBoundary, Wall};
+use cz_cfd_simulator::simulation::{SimParameters, Simulation};
+
+// Side-heated square at Ra = 1e4 with adiabatic top and bottom, run for a while.
+fn side_heated(hot: Wall, cold: Wall) -> Simulation {
+    let thermal = ThermalBoundary {
+        segments: vec![
+            BoundarySegment::whole(Wall::Bottom, ThermalBc::Adiabatic),
+            BoundarySegment::whole(Wall::Top, ThermalBc::Adiabatic),
+            BoundarySegment::whole(hot, ThermalBc::Fixed(1.0)),
+            BoundarySegment::whole(cold, ThermalBc::Fixed(0.0)),
+        ],
+        ..ThermalBoundary::default()
+    };
+    let mut sim = Simulation::new(SimParameters { thermal, ..SimParameters::cavity(21, 0.71, 1e4) });
+    for _ in 0..300 {
+        sim.step();
+    }
+    sim
+}
+
+// Flipping the heated wall mirrors the solution: T(x, y) → T(1 − x, y), ψ → −ψ. Every
+// stencil has to treat +x and −x, and +y and −y alike for this to hold.
+#[test]
+fn mirrored_cavity_gives_the_mirrored_flow() {
+    let left = side_heated(Wall::Left, Wall::Right);
+    let right = side_heated(Wall::Right, Wall::Left);
+    let (ny, nx) = left.state.temp.dim();
+    let scale = left.state.stream.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    assert!(scale > 1e-3, "no flow developed: max |psi| = {:e}", scale);
+    for i in 0..ny {
+        for j in 0..nx {
+            let m = nx - 1 - j;
+            assert!((left.state.temp[[i, j]] - right.state.temp[[i, m]]).abs() < 1e-10, "T at ({}, {})", i, j);
+            assert!((left.state.stream[[i, j]] + right.state.stream[[i, m]]).abs() < 1e-10 * scale, "psi at ({}, {})", i, j);
+        }
+    }
+}
+
+// Buoyancy lifts the fluid next to the hot wall and sinks it next to the cold one, so
+// ∇²ψ = −ω has to turn the buoyant vorticity into a clockwise circulation.
+#[test]
+fn hot_fluid_rises() {
+    let sim = side_heated(Wall::Left, Wall::Right);
+    let (ny, nx) = sim.state.temp.dim();
+    let mid = ny / 2;
+    assert!(sim.state.v[[mid, 1]] > 0.0, "v next to the hot wall: {}", sim.state.v[[mid, 1]]);
+    assert!(sim.state.v[[mid, nx - 2]] < 0.0, "v next to the cold wall: {}", sim.state.v[[mid, nx - 2]]);
+    assert!(sim.state.stream[[mid, nx / 2]] < 0.0, "psi at the centre: {}", sim.state.stream[[mid, nx / 2]]);
+}
@@ -0,0 +1,163 @@
+use ndarray::Array2;
+
+use cz_cfd_simulator::diagnostics::{self, DiagnosticsSample};
+use cz_cfd_simulator::simulation::{SimParameters, SimState, Simulation};
+use cz_cfd_simulator::Solver;
+use cz_cfd_simulator::uq::{ensemble_time_step, member_parameters, run_ensemble, study_members, FieldStatistics, SummaryStats, UncertainInput, FIELD_STATISTICS, STEP_FRACTION};
+use cz_cfd_simulator::watchdog::Watchdog;
+
+#[test]
+fn a_single_value_is_every_statistic() {
+    let stats = SummaryStats::of(&[2.5]);
+    assert_eq!(stats, SummaryStats { mean: 2.5, std: 0.0, p05: 2.5, p50: 2.5, p95: 2.5 });
+    assert_eq!(SummaryStats::of(&[]), SummaryStats::default());
+}
+
+// Field statistics are looked up by name; an unknown one is an error, not the mean.
+#[test]
+fn field_statistics_by_name() {
+    let (a, b) = (Array2::from_elem((2, 3), 1.0), Array2::from_elem((2, 3), 3.0));
+    let stats = FieldStatistics::of("temperature", &[&a, &b]);
+    for name in FIELD_STATISTICS {
+        assert!(stats.get(name).is_ok(), "{}", name);
+    }
+    assert_eq!(stats.get("mean").unwrap(), &Array2::from_elem((2, 3), 2.0));
+    let error = stats.get("median").unwrap_err();
+    assert_eq!(error.to_string(), "unknown statistic 'median' (mean, std, p05, p50, p95)");
+}
+
+// 0, 1, ..., 100 in reverse: the percentiles fall on the values themselves.
+#[test]
+fn percentiles_of_evenly_spaced_values() {
+    let values: Vec<f64> = (0..=100).rev().map(f64::from).collect();
+    let stats = SummaryStats::of(&values);
+    assert_eq!((stats.mean, stats.p05, stats.p50, stats.p95), (50.0, 5.0, 50.0, 95.0));
+    assert!((stats.std - (101.0 * 102.0 / 12.0f64).sqrt()).abs() < 1e-12);
+}
+
+// Between order statistics the percentiles interpolate linearly.
+#[test]
+fn percentiles_interpolate_between_values() {
+    let stats = SummaryStats::of(&[4.0, 1.0, 3.0, 2.0]);
+    assert!((stats.p05 - 1.15).abs() < 1e-12);
+    assert!((stats.p50 - 2.5).abs() < 1e-12);
+    assert!((stats.p95 - 3.85).abs() < 1e-12);
+    assert!((stats.std - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+}
+
+// Every member is reported once, on the calling thread, and returned in member order.
+#[test]
+fn ensemble_members_are_reported_to_the_caller() {
+    let member = |ra: f64| SimParameters::cavity(11, 0.71, ra);
+    let caller = std::thread::current().id();
+    let mut reported = Vec::new();
+    let outcomes = run_ensemble(vec![member(1e3), member(2e3), member(3e3)], 10, 2, Simulation::new, |m, done, outcome| {
+        assert_eq!(std::thread::current().id(), caller);
+        assert!(outcome.is_ok());
+        reported.push((m, done));
+    });
+    reported.sort();
+    assert_eq!(reported.iter().map(|r| r.0).collect::<Vec<_>>(), [0, 1, 2]);
+    let mut done: Vec<usize> = reported.iter().map(|r| r.1).collect();
+    done.sort();
+    assert_eq!(done, [1, 2, 3]);
+    let rayleigh: Vec<f64> = outcomes.iter().map(|o| o.as_ref().unwrap().params.ra).collect();
+    assert_eq!(rayleigh, [1e3, 2e3, 3e3]);
+}
+
+// Every member runs at the step of the most restrictive one, so all reach the same time
+// after the same number of steps: a larger Pr diffuses vorticity faster and sets it. A
+// rejected member does not.
+#[test]
+fn members_share_the_smallest_stable_time_step() {
+    let base = SimParameters::cavity(21, 0.71, 1e4);
+    let inputs = ["pr=normal:4,4".parse::<UncertainInput>().unwrap()];
+    let members = study_members(&base, &inputs, &[vec![2.0], vec![8.0], vec![-1.0]]);
+    let h = 1.0 / 20.0;
+    assert!((members[1].dt - STEP_FRACTION * h * h / 32.0).abs() < 1e-15, "dt = {}", members[1].dt);
+    assert!(members.iter().all(|params| params.dt == members[1].dt));
+    assert_eq!(ensemble_time_step(&members[2..]), None);
+}
+
+// A normal distribution can draw Pr ≤ 0 or Ra < 0; those members fail with the reason
+// instead of being run, and the others complete.
+#[test]
+fn members_outside_the_model_are_rejected() {
+    let base = SimParameters::cavity(11, 0.71, 1e3);
+    let pr = ["pr=normal:0.71,1".parse::<UncertainInput>().unwrap()];
+    let ra = ["ra=normal:1e3,1e3".parse::<UncertainInput>().unwrap()];
+    let members = vec![member_parameters(&base, &pr, &[-0.2]), member_parameters(&base, &ra, &[-5.0]), member_parameters(&base, &pr, &[0.5])];
+    let outcomes = run_ensemble(members, 5, 1, Simulation::new, |_, _, _| {});
+    assert_eq!(outcomes[0].as_ref().err().map(String::as_str), Some("Pr = -2.000e-1 is not positive"));
+    assert_eq!(outcomes[1].as_ref().err().map(String::as_str), Some("Ra = -5.000e0 is negative"));
+    assert_eq!(outcomes[2].as_ref().map(|sim| sim.steps_taken).ok(), Some(5));
+}
+
+// A stand-in discretization whose temperature grows tenfold per step: the ensemble runs it
+// through the `Solver` trait alone, and the watchdog stops it once the growth is caught.
+struct Runaway {
+    params: SimParameters,
+    state: SimState,
+    time: f64,
+    steps_taken: usize,
+    watchdog: Watchdog,
+}
+
+impl Solver for Runaway {
+    fn initialize(&mut self, params: SimParameters) {
+        *self = Runaway::new(params);
+    }
+
+    fn step(&mut self) {
+        self.state.temp.mapv_inplace(|t| 10.0 * t + 1.0);
+        self.time += self.params.dt;
+        self.steps_taken += 1;
+    }
+
+    fn state(&self) -> &SimState {
+        &self.state
+    }
+
+    fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn steps_taken(&self) -> usize {
+        self.steps_taken
+    }
+
+    fn watchdog(&mut self) -> &mut Watchdog {
+        &mut self.watchdog
+    }
+
+    fn diagnostics(&self) -> DiagnosticsSample {
+        diagnostics::sample(&self.state, &self.params, 0, self.time)
+    }
+}
+
+impl Runaway {
+    fn new(params: SimParameters) -> Self {
+        let state = Simulation::new(params.clone()).state;
+        Runaway { params, state, time: 0.0, steps_taken: 0, watchdog: Watchdog::default() }
+    }
+}
+
+#[test]
+fn ensembles_run_any_solver() {
+    let members = vec![SimParameters::cavity(5, 0.71, 1e3)];
+    let finished = run_ensemble(members.clone(), 1, 1, Runaway::new, |_, _, _| {});
+    assert_eq!(finished[0].as_ref().map(|solver| solver.time()).ok(), Some(members[0].dt));
+
+    let blown_up = run_ensemble(members, 20, 1, Runaway::new, |_, _, _| {});
+    let reason = blown_up[0].as_ref().err().unwrap();
+    assert!(reason.starts_with("step 2: max |temperature| grew"), "{}", reason);
+}
+
+// The melt surface has no thermocapillary stress, so Ma is refused as an input, saying so.
+#[test]
+fn marangoni_number_is_not_an_input() {
+    for spec in ["ma=normal:1e3,10%", "marangoni=uniform:1e2,1e3"] {
+        let error = spec.parse::<UncertainInput>().unwrap_err();
+        assert!(format!("{:#}", error).contains("Ma is not supported as an uncertain input"), "{:#}", error);
+    }
+}
@@ -0,0 +1,58 @@
+use cz_cfd_simulator::validation::{self, ValidationCase};
+
+fn assert_passes(case: &str) {
+    let case: ValidationCase = case.parse().unwrap();
+    let failed: Vec<String> = validation::run(case)
+        .iter()
+        .filter(|check| !check.passed())
+        .map(|check| format!("{}: observed {:e}, reference {:e} ± {:e}", check.name, check.observed, check.reference, check.tolerance))
+        .collect();
+    assert!(failed.is_empty(), "{:#?}", failed);
+}
+
+// The benchmark has reference solutions at four Rayleigh numbers only; any other would
+// select no case and check nothing.
+#[test]
+fn de_vahl_davis_needs_a_benchmark_rayleigh_number() {
+    assert_eq!("de-vahl-davis:1e5".parse::<ValidationCase>().unwrap(), ValidationCase::DeVahlDavis { rayleigh: Some(1e5) });
+    assert!("de-vahl-davis:2e4".parse::<ValidationCase>().is_err());
+}
+
+// One leading-eigenvalue computation on either side of the onset in a periodic layer.
+#[test]
+fn rayleigh_benard_onset() {
+    assert_passes("rayleigh-benard");
+}
+
+// The cases below take 10 to 40 s each with optimization, and 3.5 minutes for de Vahl
+// Davis at Ra = 1e6, and about twenty times as long without it (7 minutes for Ra = 1e3),
+// so they are left out of the default run: `cargo test --release --test validation -- --ignored`.
+#[test]
+#[ignore]
+fn double_diffusive_cavity() {
+    assert_passes("double-diffusive");
+}
+
+#[test]
+#[ignore]
+fn de_vahl_davis_ra_1e3() {
+    assert_passes("de-vahl-davis:1e3");
+}
+
+#[test]
+#[ignore]
+fn de_vahl_davis_ra_1e4() {
+    assert_passes("de-vahl-davis:1e4");
+}
+
+#[test]
+#[ignore]
+fn de_vahl_davis_ra_1e5() {
+    assert_passes("de-vahl-davis:1e5");
+}
+
+#[test]
+#[ignore]
+fn de_vahl_davis_ra_1e6() {
+    assert_passes("de-vahl-davis:1e6");
+}
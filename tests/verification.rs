@@ -0,0 +1,37 @@
+use cz_cfd_simulator::verification::{space_study, time_study, ManufacturedSolution, OrderStudy};
+
+const T_END: f64 = 0.05;
+
+fn assert_orders(study: OrderStudy) {
+    assert!(study.failures().is_empty(), "{:?}", study.failures());
+}
+
+fn solution(name: &str) -> ManufacturedSolution {
+    name.parse().unwrap()
+}
+
+// The temporal studies of `verify` with one refinement fewer.
+#[test]
+fn steady_solution_converges_at_the_formal_order_in_time() {
+    assert_orders(time_study(&solution("steady"), 17, &[64, 128, 256], T_END));
+}
+
+#[test]
+fn unsteady_solution_converges_at_the_formal_order_in_time() {
+    assert_orders(time_study(&solution("unsteady"), 17, &[64, 128, 256], T_END));
+}
+
+// ψ and ω reach their formal order in space only from about 41 points on, so the finest
+// pair needs 81, which takes minutes without optimization:
+// `cargo test --release --test verification -- --ignored`.
+#[test]
+#[ignore]
+fn steady_solution_converges_at_the_formal_order_in_space() {
+    assert_orders(space_study(&solution("steady"), &[21, 41, 81], T_END));
+}
+
+#[test]
+#[ignore]
+fn unsteady_solution_converges_at_the_formal_order_in_space() {
+    assert_orders(space_study(&solution("unsteady"), &[21, 41, 81], T_END));
+}